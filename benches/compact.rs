@@ -0,0 +1,53 @@
+// benches/compact.rs
+
+// Compact (`to_compact_json`) against verbose (serde_json) encoding of a
+// 1000-block request: size is reported as throughput, time as usual.
+// Run with `cargo bench --no-default-features --features compact --bench compact`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use redstonesim::compact::{from_compact_json, to_compact_json};
+use redstonesim::{Direction, SimRequest, WorldBuilder};
+
+// 100 lever → dust → repeater → lamp lines, ten blocks each
+fn request() -> SimRequest {
+    let world = (0..100)
+        .fold(WorldBuilder::new(), |b, z| {
+            (1..8)
+                .fold(b.lever((0, 0, z * 2), Direction::East, true), |b, x| b.dust((x, 0, z * 2)))
+                .repeater((8, 0, z * 2), Direction::East, 4)
+                .lamp((9, 0, z * 2))
+        })
+        .build()
+        .unwrap();
+    SimRequest { ticks: 20, world, ..Default::default() }
+}
+
+fn encodings(c: &mut Criterion) {
+    let req = request();
+    let verbose = serde_json::to_string(&req).unwrap();
+    let compact = to_compact_json(&req);
+    println!(
+        "1000 blocks: verbose {} bytes, compact {} bytes ({:.0}%)",
+        verbose.len(),
+        compact.len(),
+        100.0 * compact.len() as f64 / verbose.len() as f64
+    );
+
+    let mut group = c.benchmark_group("sim_request_1000_blocks");
+    group.throughput(Throughput::Bytes(verbose.len() as u64));
+    group.bench_with_input(BenchmarkId::new("serialize", "verbose"), &req, |b, req| {
+        b.iter(|| serde_json::to_string(req).unwrap())
+    });
+    group.bench_with_input(BenchmarkId::new("deserialize", "verbose"), &verbose, |b, json| {
+        b.iter(|| serde_json::from_str::<SimRequest>(json).unwrap())
+    });
+    group.throughput(Throughput::Bytes(compact.len() as u64));
+    group.bench_with_input(BenchmarkId::new("serialize", "compact"), &req, |b, req| b.iter(|| to_compact_json(req)));
+    group.bench_with_input(BenchmarkId::new("deserialize", "compact"), &compact, |b, json| {
+        b.iter(|| from_compact_json(json).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, encodings);
+criterion_main!(benches);
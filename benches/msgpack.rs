@@ -0,0 +1,42 @@
+// benches/msgpack.rs
+
+// A whole simulate round trip (decode the request, simulate, encode the
+// response) through JSON and through `simulate_msgpack`, for a lever
+// driving a 999-block dust line.
+// Run with `cargo bench --no-default-features --features msgpack --bench msgpack`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use redstonesim::msgpack::{simulate_msgpack, to_msgpack};
+use redstonesim::{simulate, Direction, SimRequest, WorldBuilder};
+
+fn request() -> SimRequest {
+    let world = (1..1000)
+        .fold(WorldBuilder::new().lever((0, 0, 0), Direction::East, true), |b, x| b.dust((x, 0, 0)))
+        .build()
+        .unwrap();
+    SimRequest { ticks: 20, world, ..Default::default() }
+}
+
+fn round_trips(c: &mut Criterion) {
+    let req = request();
+    let json = serde_json::to_string(&req).unwrap();
+    let packed = to_msgpack(&req).unwrap();
+    println!("1000 blocks: json {} bytes, msgpack {} bytes", json.len(), packed.len());
+
+    let mut group = c.benchmark_group("simulate_round_trip_1000_blocks");
+    group.throughput(Throughput::Bytes(json.len() as u64));
+    group.bench_with_input(BenchmarkId::from_parameter("json"), &json, |b, json| {
+        b.iter(|| {
+            let req: SimRequest = serde_json::from_str(json).unwrap();
+            serde_json::to_string(&simulate(req).unwrap()).unwrap()
+        })
+    });
+    group.throughput(Throughput::Bytes(packed.len() as u64));
+    group.bench_with_input(BenchmarkId::from_parameter("msgpack"), &packed, |b, packed| {
+        b.iter(|| simulate_msgpack(packed).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, round_trips);
+criterion_main!(benches);
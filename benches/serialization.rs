@@ -0,0 +1,91 @@
+// benches/serialization.rs
+
+// JSON encoding and decoding of requests and responses, the cost every
+// simulate_py call pays on top of the simulation itself, and JSON against
+// bincode for a 1000-block world.
+// Run with `cargo bench --no-default-features --features bincode --bench serialization`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use redstonesim::binary::{deserialize_world_bin, serialize_world_bin};
+use redstonesim::{simulate, Direction, SimRequest, SimResponse, World, WorldBuilder};
+
+// `chains` lever → dust → repeater → lamp lines, ten blocks each
+fn request(chains: i32) -> SimRequest {
+    let world = (0..chains)
+        .fold(WorldBuilder::new(), |b, z| {
+            (1..8)
+                .fold(b.lever((0, 0, z * 2), Direction::East, true), |b, x| b.dust((x, 0, z * 2)))
+                .repeater((8, 0, z * 2), Direction::East, 4)
+                .lamp((9, 0, z * 2))
+        })
+        .build()
+        .unwrap();
+    SimRequest { ticks: 20, world, ..Default::default() }
+}
+
+fn requests(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sim_request_json");
+    for chains in [1, 20, 100] {
+        let req = request(chains);
+        let json = serde_json::to_string(&req).unwrap();
+        group.throughput(Throughput::Bytes(json.len() as u64));
+        group.bench_with_input(BenchmarkId::new("serialize", chains * 10), &req, |b, req| {
+            b.iter(|| serde_json::to_string(req).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("deserialize", chains * 10), &json, |b, json| {
+            b.iter(|| serde_json::from_str::<SimRequest>(json).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn responses(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sim_response_json");
+    for chains in [1, 20, 100] {
+        let resp = simulate(request(chains)).unwrap();
+        let json = serde_json::to_string(&resp).unwrap();
+        group.throughput(Throughput::Bytes(json.len() as u64));
+        group.bench_with_input(BenchmarkId::new("serialize", chains * 10), &resp, |b, resp| {
+            b.iter(|| serde_json::to_string(resp).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("deserialize", chains * 10), &json, |b, json| {
+            b.iter(|| serde_json::from_str::<SimResponse>(json).unwrap())
+        });
+    }
+    group.finish();
+}
+
+// a lever driving a 999-block dust line
+fn big_world() -> World {
+    (1..1000)
+        .fold(WorldBuilder::new().lever((0, 0, 0), Direction::East, true), |b, x| b.dust((x, 0, 0)))
+        .build()
+        .unwrap()
+}
+
+fn json_vs_binary(c: &mut Criterion) {
+    let world = big_world();
+    let json = serde_json::to_string(&world).unwrap();
+    let bin = serialize_world_bin(&world);
+    println!("1000 blocks: json {} bytes, bincode {} bytes", json.len(), bin.len());
+
+    let mut group = c.benchmark_group("world_1000_blocks");
+    group.throughput(Throughput::Bytes(json.len() as u64));
+    group.bench_with_input(BenchmarkId::new("serialize", "json"), &world, |b, world| {
+        b.iter(|| serde_json::to_string(world).unwrap())
+    });
+    group.bench_with_input(BenchmarkId::new("deserialize", "json"), &json, |b, json| {
+        b.iter(|| serde_json::from_str::<World>(json).unwrap())
+    });
+    group.throughput(Throughput::Bytes(bin.len() as u64));
+    group.bench_with_input(BenchmarkId::new("serialize", "bincode"), &world, |b, world| {
+        b.iter(|| serialize_world_bin(world))
+    });
+    group.bench_with_input(BenchmarkId::new("deserialize", "bincode"), &bin, |b, bin| {
+        b.iter(|| deserialize_world_bin(bin).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, requests, responses, json_vs_binary);
+criterion_main!(benches);
@@ -0,0 +1,172 @@
+// benches/simulate.rs
+
+// Baselines for `simulate`. Run with `cargo bench --bench simulate`.
+// Throughput is reported in simulated ticks per second; with early_exit the
+// run stops once the world settles, so each input is counted by the ticks it
+// actually took. Levers toggle every 10 ticks so no world settles for good.
+// Build with `--features parallel` to time the parallel paths for real;
+// without it they run sequentially.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use redstonesim::{simulate, simulate_many, ComparatorMode, Direction, SimRequest, TimedEvent, World, WorldBuilder};
+
+const TICKS: u32 = 100;
+
+fn request(world: &World, levers: &[(i32, i32, i32)], early_exit: bool) -> SimRequest {
+    let events = (1..TICKS / 10)
+        .flat_map(|i| levers.iter().map(move |p| (i * 10, TimedEvent::LeverToggle((*p).into()))))
+        .collect();
+    SimRequest { ticks: TICKS, world: world.clone(), early_exit, events, ..Default::default() }
+}
+
+// ticks the request runs for before it ends
+fn ticks_run(req: SimRequest) -> u64 {
+    let mut sim = req.into_iter();
+    sim.by_ref().for_each(drop);
+    u64::from(sim.tick())
+}
+
+// block count, world, levers to toggle
+type Case = (usize, World, Vec<(i32, i32, i32)>);
+
+fn bench_world(c: &mut Criterion, group_name: &str, worlds: &[Case]) {
+    let mut group = c.benchmark_group(group_name);
+    for (size, world, levers) in worlds {
+        for early_exit in [true, false] {
+            let req = request(world, levers, early_exit);
+            group.throughput(Throughput::Elements(ticks_run(req.clone())));
+            let id = BenchmarkId::new(if early_exit { "early_exit" } else { "full" }, size);
+            group.bench_with_input(id, &req, |b, req| b.iter(|| simulate(req.clone()).unwrap()));
+        }
+    }
+    group.finish();
+}
+
+// lever, dust, lamp; a repeater every 15 blocks keeps the signal going
+fn dust_chain(blocks: usize) -> World {
+    let last = blocks as i32 - 1;
+    (1..last)
+        .fold(WorldBuilder::new().lever((0, 0, 0), Direction::East, true), |b, x| {
+            if x % 15 == 0 { b.repeater((x, 0, 0), Direction::East, 1) } else { b.dust((x, 0, 0)) }
+        })
+        .lamp((last, 0, 0))
+        .build()
+        .unwrap()
+}
+
+fn repeater_chain(blocks: usize) -> World {
+    let last = blocks as i32 - 1;
+    (1..last)
+        .fold(WorldBuilder::new().lever((0, 0, 0), Direction::East, true), |b, x| {
+            b.repeater((x, 0, 0), Direction::East, 4)
+        })
+        .lamp((last, 0, 0))
+        .build()
+        .unwrap()
+}
+
+// a square of dust fed by a lever on each side
+fn dust_field(side: i32) -> (World, Vec<(i32, i32, i32)>) {
+    let levers = vec![(-1, 0, 0), (side, 0, side - 1), (0, 0, side), (side - 1, 0, -1)];
+    let builder = (0..side).flat_map(|x| (0..side).map(move |z| (x, 0, z))).fold(WorldBuilder::new(), |b, p| b.dust(p));
+    let world = builder
+        .lever(levers[0], Direction::East, true)
+        .lever(levers[1], Direction::West, true)
+        .lever(levers[2], Direction::North, true)
+        .lever(levers[3], Direction::South, true)
+        .build()
+        .unwrap();
+    (world, levers)
+}
+
+// rows of comparators, alternating compare and subtract, each fed by a lever
+fn comparator_rows(rows: i32, len: i32) -> (World, Vec<(i32, i32, i32)>) {
+    let levers: Vec<_> = (0..rows).map(|z| (0, 0, z * 2)).collect();
+    let world = levers
+        .iter()
+        .fold(WorldBuilder::new(), |b, &(_, _, z)| {
+            (1..=len).fold(b.lever((0, 0, z), Direction::East, true), |b, x| {
+                let mode = if x % 2 == 0 { ComparatorMode::Subtract } else { ComparatorMode::Compare };
+                b.comparator((x, 0, z), Direction::East, mode)
+            })
+        })
+        .build()
+        .unwrap();
+    (world, levers)
+}
+
+// `chains` lever → dust → repeater → lamp lines, ten blocks each, none
+// touching another
+fn independent_chains(chains: i32) -> World {
+    (0..chains)
+        .fold(WorldBuilder::new(), |b, z| {
+            (1..8)
+                .fold(b.lever((0, 0, z * 2), Direction::East, true), |b, x| b.dust((x, 0, z * 2)))
+                .repeater((8, 0, z * 2), Direction::East, 4)
+                .lamp((9, 0, z * 2))
+        })
+        .build()
+        .unwrap()
+}
+
+fn world_sizes(c: &mut Criterion) {
+    let worlds: Vec<_> = [10, 50, 200, 500].into_iter().map(|n| (n, dust_chain(n), vec![(0, 0, 0)])).collect();
+    bench_world(c, "dust_chain", &worlds);
+}
+
+fn timer_heavy(c: &mut Criterion) {
+    let worlds: Vec<_> = [10, 50, 200].into_iter().map(|n| (n, repeater_chain(n), vec![(0, 0, 0)])).collect();
+    bench_world(c, "repeater_chain", &worlds);
+}
+
+fn dust_dense(c: &mut Criterion) {
+    let worlds: Vec<_> = [8, 16, 32]
+        .into_iter()
+        .map(|side| {
+            let (world, levers) = dust_field(side);
+            (world.blocks.len(), world, levers)
+        })
+        .collect();
+    bench_world(c, "dust_field", &worlds);
+}
+
+fn comparator_network(c: &mut Criterion) {
+    let worlds: Vec<_> = [(5, 10), (10, 20), (20, 25)]
+        .into_iter()
+        .map(|(rows, len)| {
+            let (world, levers) = comparator_rows(rows, len);
+            (world.blocks.len(), world, levers)
+        })
+        .collect();
+    bench_world(c, "comparator_rows", &worlds);
+}
+
+// SimRequest::parallel on 500 blocks in 50 chains
+fn parallel_updates(c: &mut Criterion) {
+    let world = independent_chains(50);
+    let levers: Vec<_> = (0..50).map(|z| (0, 0, z * 2)).collect();
+    let mut group = c.benchmark_group("parallel_updates");
+    for parallel in [false, true] {
+        let req = SimRequest { parallel, ..request(&world, &levers, false) };
+        group.throughput(Throughput::Elements(ticks_run(req.clone())));
+        let id = BenchmarkId::new(if parallel { "parallel" } else { "sequential" }, world.blocks.len());
+        group.bench_with_input(id, &req, |b, req| b.iter(|| simulate(req.clone()).unwrap()));
+    }
+    group.finish();
+}
+
+// 100 identical requests on 50 blocks, one by one and through simulate_many
+fn batch(c: &mut Criterion) {
+    let req = SimRequest { ticks: 20, world: independent_chains(5), ..Default::default() };
+    let requests = vec![req; 100];
+    let mut group = c.benchmark_group("batch_100");
+    group.throughput(Throughput::Elements(requests.len() as u64));
+    group.bench_with_input("sequential", &requests, |b, requests| {
+        b.iter(|| requests.iter().map(|req| simulate(req.clone())).collect::<Vec<_>>())
+    });
+    group.bench_with_input("simulate_many", &requests, |b, requests| b.iter(|| simulate_many(requests.clone())));
+    group.finish();
+}
+
+criterion_group!(benches, world_sizes, timer_heavy, dust_dense, comparator_network, parallel_updates, batch);
+criterion_main!(benches);
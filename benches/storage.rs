@@ -0,0 +1,80 @@
+// benches/storage.rs
+
+// Block storage on its own: `SpatialGrid` (a hash map) against `ChunkWorld`
+// (dense 16×16×16 arrays) on a world filling a 64×64×64 volume, and against
+// a std `HashMap` with the default SipHash on every position of that
+// volume. The neighbour scans read what a tick reads, six lookups around
+// every block.
+// Run with `cargo bench --bench storage`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use redstonesim::chunk::ChunkWorld;
+use redstonesim::grid::SpatialGrid;
+use redstonesim::{BlockKind, Direction, Pos, WorldBuilder};
+use std::collections::HashMap;
+
+// a lever-fed dust line along x on every other row and layer
+fn blocks() -> Vec<(Pos, BlockKind)> {
+    let mut builder = WorldBuilder::new();
+    for y in (0..64).step_by(2) {
+        for z in (0..64).step_by(2) {
+            builder = (1..64).fold(builder.lever((0, y, z), Direction::East, true), |b, x| b.dust((x, y, z)));
+        }
+    }
+    builder.build().unwrap().blocks.into_iter().map(|b| (b.pos, b.kind)).collect()
+}
+
+fn chunk_vs_grid(c: &mut Criterion) {
+    let blocks = blocks();
+    let positions: Vec<Pos> = blocks.iter().map(|(p, _)| *p).collect();
+    let grid: SpatialGrid<BlockKind> = blocks.iter().cloned().collect();
+    let chunks: ChunkWorld = blocks.iter().cloned().collect();
+
+    let mut group = c.benchmark_group("storage_64");
+    group.throughput(Throughput::Elements(blocks.len() as u64));
+    group.bench_with_input(BenchmarkId::new("build", "spatial_grid"), &blocks, |b, blocks| {
+        b.iter(|| blocks.iter().cloned().collect::<SpatialGrid<BlockKind>>())
+    });
+    group.bench_with_input(BenchmarkId::new("build", "chunk_world"), &blocks, |b, blocks| {
+        b.iter(|| blocks.iter().cloned().collect::<ChunkWorld>())
+    });
+    group.bench_with_input(BenchmarkId::new("neighbors", "spatial_grid"), &positions, |b, positions| {
+        b.iter(|| positions.iter().flat_map(|p| p.neighbors()).filter(|(n, _)| grid.contains_key(n)).count())
+    });
+    group.bench_with_input(BenchmarkId::new("neighbors", "chunk_world"), &positions, |b, positions| {
+        b.iter(|| positions.iter().flat_map(|p| p.neighbors()).filter(|(n, _)| chunks.contains_key(n)).count())
+    });
+    group.finish();
+}
+
+fn grid_vs_std(c: &mut Criterion) {
+    let positions: Vec<Pos> =
+        (0..64).flat_map(|x| (0..64).flat_map(move |y| (0..64).map(move |z| Pos { x, y, z }))).collect();
+    let std_map: HashMap<Pos, u32> = positions.iter().map(|p| (*p, p.x as u32)).collect();
+    let grid: SpatialGrid<u32> = positions.iter().map(|p| (*p, p.x as u32)).collect();
+
+    let mut group = c.benchmark_group("pos_hash_64");
+    group.throughput(Throughput::Elements(positions.len() as u64));
+    group.bench_with_input(BenchmarkId::new("insert", "std"), &positions, |b, positions| {
+        b.iter(|| positions.iter().map(|p| (*p, p.x as u32)).collect::<HashMap<_, _>>())
+    });
+    group.bench_with_input(BenchmarkId::new("insert", "spatial_grid"), &positions, |b, positions| {
+        b.iter(|| positions.iter().map(|p| (*p, p.x as u32)).collect::<SpatialGrid<_>>())
+    });
+    group.bench_with_input(BenchmarkId::new("lookup", "std"), &positions, |b, positions| {
+        b.iter(|| positions.iter().map(|p| u64::from(std_map[p])).sum::<u64>())
+    });
+    group.bench_with_input(BenchmarkId::new("lookup", "spatial_grid"), &positions, |b, positions| {
+        b.iter(|| positions.iter().map(|p| u64::from(grid[p])).sum::<u64>())
+    });
+    group.bench_with_input(BenchmarkId::new("neighbors", "std"), &positions, |b, positions| {
+        b.iter(|| positions.iter().flat_map(|p| p.neighbors()).filter(|(n, _)| std_map.contains_key(n)).count())
+    });
+    group.bench_with_input(BenchmarkId::new("neighbors", "spatial_grid"), &positions, |b, positions| {
+        b.iter(|| positions.iter().flat_map(|p| p.neighbors()).filter(|(n, _)| grid.contains_key(n)).count())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, chunk_vs_grid, grid_vs_std);
+criterion_main!(benches);
@@ -0,0 +1,36 @@
+/*
+ * Simulates a lever powering a lamp through the C API and prints the
+ * response. Build and run from the repository root with `make c-example`.
+ */
+#include <stdio.h>
+
+#include "redstonesim.h"
+
+static const char *WORLD =
+    "{\"blocks\": ["
+    "{\"x\": 0, \"y\": 0, \"z\": 0, \"type\": \"lever\", \"on\": true, \"facing\": \"east\"},"
+    "{\"x\": 1, \"y\": 0, \"z\": 0, \"type\": \"dust\", \"power\": 0},"
+    "{\"x\": 2, \"y\": 0, \"z\": 0, \"type\": \"lamp\", \"on\": false}"
+    "]}";
+
+int main(void) {
+    char request[1024];
+    char *out = NULL;
+    int code;
+
+    code = redstonesim_validate(WORLD, &out);
+    printf("validate -> %d: %s\n", code, out ? out : "(null)");
+    redstonesim_free(out);
+
+    snprintf(request, sizeof request, "{\"ticks\": 5, \"world\": %s}", WORLD);
+    code = redstonesim_simulate(request, &out);
+    printf("simulate -> %d: %s\n", code, out ? out : "(null)");
+    redstonesim_free(out);
+
+    /* errors still hand back JSON, except for bad arguments */
+    code = redstonesim_simulate("{not json", &out);
+    printf("bad input -> %d: %s\n", code, out ? out : "(null)");
+    redstonesim_free(out);
+
+    return code == REDSTONESIM_ERR_PARSE ? 0 : 1;
+}
@@ -0,0 +1,65 @@
+// examples/no_std_demo.rs
+
+// The simulator inside microcontroller firmware. Built as a static library
+// exporting `redstonesim_demo`, which the firmware calls like any C
+// function. For Cortex-M:
+//
+//     cargo build --example no_std_demo --no-default-features --target thumbv7em-none-eabihf
+//
+// On the host the same file builds with `std`, so it stays under test.
+
+#![cfg_attr(target_os = "none", no_std)]
+
+use redstonesim::{simulate, BlockKind, Direction, SimRequest, WorldBuilder};
+
+/// Flip a lever next to a line of dust and report whether the lamp at the
+/// far end lights. Errors are reported as `false`; nothing here panics.
+#[no_mangle]
+pub extern "C" fn redstonesim_demo() -> bool {
+    #[cfg(target_os = "none")]
+    embedded::init_heap();
+
+    let Ok(world) = WorldBuilder::new()
+        .lever((0, 0, 0), Direction::East, true)
+        .dust((1, 0, 0))
+        .dust((2, 0, 0))
+        .lamp((3, 0, 0))
+        .build()
+    else {
+        return false;
+    };
+    let request = SimRequest { ticks: 10, world, ..Default::default() };
+    match simulate(request) {
+        Ok(resp) => resp.diffs.iter().flat_map(|d| d.changed_kinds()).any(|k| *k == BlockKind::Lamp { on: true }),
+        Err(_) => false,
+    }
+}
+
+// heap and panic handling the firmware would otherwise lack
+#[cfg(target_os = "none")]
+mod embedded {
+    use core::mem::MaybeUninit;
+    use core::sync::atomic::{AtomicBool, Ordering};
+    use embedded_alloc::LlffHeap as Heap;
+
+    const HEAP_SIZE: usize = 32 * 1024;
+
+    #[global_allocator]
+    static HEAP: Heap = Heap::empty();
+    static HEAP_READY: AtomicBool = AtomicBool::new(false);
+
+    pub fn init_heap() {
+        static mut HEAP_MEM: [MaybeUninit<u8>; HEAP_SIZE] = [MaybeUninit::uninit(); HEAP_SIZE];
+        if !HEAP_READY.swap(true, Ordering::AcqRel) {
+            // SAFETY: runs once, before anything allocates
+            unsafe { HEAP.init(core::ptr::addr_of_mut!(HEAP_MEM) as usize, HEAP_SIZE) }
+        }
+    }
+
+    #[panic_handler]
+    fn panic(_: &core::panic::PanicInfo) -> ! {
+        loop {
+            cortex_m::asm::nop();
+        }
+    }
+}
@@ -0,0 +1,27 @@
+// fuzz/fuzz_targets/fuzz_simulate.rs
+
+// Arbitrary bytes through the same path as simulate_py: parse the request
+// JSON, simulate, serialize the response. Every failure must come back as
+// a SimError; any panic is a bug.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use redstonesim::{simulate, SimError, SimRequest};
+
+// long runs are not bugs, only slow; keep each input well under the timeout
+const MAX_TICKS: u32 = 1_000;
+
+fn simulate_impl(json_text: &str) -> Result<String, SimError> {
+    let mut req: SimRequest =
+        serde_json::from_str(json_text).map_err(|e| SimError::DeserializationFailed(e.to_string()))?;
+    req.ticks = req.ticks.min(MAX_TICKS);
+    let resp = simulate(req)?;
+    Ok(serde_json::to_string(&resp).expect("a response always serializes"))
+}
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(json_text) = core::str::from_utf8(data) {
+        let _ = simulate_impl(json_text);
+    }
+});
@@ -0,0 +1,15 @@
+// fuzz/fuzz_targets/fuzz_world_validate.rs
+
+// World::validate on arbitrary world JSON. It must report every problem as
+// a WorldError without panicking.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use redstonesim::World;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(world) = serde_json::from_slice::<World>(data) {
+        let _ = world.validate();
+    }
+});
@@ -0,0 +1,71 @@
+#ifndef REDSTONESIM_H
+#define REDSTONESIM_H
+
+/* Generated by cbindgen from src/ffi.rs. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * The call succeeded and the output holds the result.
+ */
+#define REDSTONESIM_OK 0
+
+/**
+ * A pointer was null or the input was not UTF-8. No output is written.
+ */
+#define REDSTONESIM_ERR_ARGUMENT 1
+
+/**
+ * The input JSON could not be parsed. The output holds `{"error": "..."}`.
+ */
+#define REDSTONESIM_ERR_PARSE 2
+
+/**
+ * The world cannot be simulated. The output holds `{"error": "..."}`.
+ */
+#define REDSTONESIM_ERR_SIMULATION 3
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Simulate a `SimRequest` given as JSON. On success `*out_json` receives
+ * the `SimResponse` as JSON; on a parse or simulation error it receives
+ * `{"error": "..."}`. Returns one of the `REDSTONESIM_*` codes.
+ *
+ * # Safety
+ * `request_json` must be null or a valid NUL-terminated string, and
+ * `out_json` must be null or valid for a write. A string written to
+ * `*out_json` must be released with `redstonesim_free`.
+ */
+int redstonesim_simulate(const char *request_json, char **out_json);
+
+/**
+ * Check a `World` given as JSON. On success `*out_warnings` receives a JSON
+ * array of wiring warnings (see `World::validate_wiring`), empty if none.
+ * A world that cannot be simulated at all returns
+ * `REDSTONESIM_ERR_SIMULATION`.
+ *
+ * # Safety
+ * Same as `redstonesim_simulate`.
+ */
+int redstonesim_validate(const char *world_json, char **out_warnings);
+
+/**
+ * Release a string returned by this library. Null is ignored.
+ *
+ * # Safety
+ * `ptr` must be null or a string written by a `redstonesim_*` function
+ * that has not been freed yet.
+ */
+void redstonesim_free(char *ptr);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* REDSTONESIM_H */
@@ -0,0 +1,525 @@
+# redstonesim.pyi
+#
+# 拡張モジュール redstonesim (src/py.rs) の型スタブ。手で管理しているので、
+# py.rs の関数・クラスや JSON の形 (src/lib.rs、www/redstonesim-types.d.ts、
+# documentation/simulate_py_input.md) を変えたらここも合わせること。
+#
+# 関数は JSON 文字列をやり取りする。下の TypedDict は json.loads した後の形:
+#
+#     req: SimRequest = {"ticks": 10, "world": {"blocks": []}}
+#     resp: SimResponse = json.loads(redstonesim.simulate_py(json.dumps(req)))
+
+from typing import Awaitable, ClassVar, Literal, Union, overload
+
+import numpy as np
+import numpy.typing as npt
+from typing_extensions import NotRequired, TypedDict
+
+# ─── JSON の形 ───────────────────────────────────────
+
+class Pos(TypedDict):
+    x: int
+    y: int
+    z: int
+
+Direction = Literal["north", "east", "south", "west", "up", "down"]
+ComparatorMode = Literal["compare", "subtract"]
+Instrument = Literal[
+    "harp", "basedrum", "snare", "hat", "bass", "flute", "bell", "guitar",
+    "chime", "xylophone", "iron_xylophone", "cow_bell", "didgeridoo", "bit",
+    "banjo", "pling",
+]
+RailShape = Literal[
+    "north_south", "east_west", "ascending_east", "ascending_west",
+    "ascending_north", "ascending_south", "south_east", "south_west",
+    "north_west", "north_east",
+]
+PlateKind = Literal["stone", "wood", "light_weighted", "heavy_weighted"]
+OxidationLevel = Literal["unoxidized", "exposed", "weathered", "oxidized"]
+BellAttachment = Literal["floor", "ceiling", "single_wall", "double_wall"]
+
+# ワールドに置かれたブロック (PlacedBlock) は座標を同じ dict に持つ。
+# block_replace イベントのブロックには座標がない
+class _BlockPos(TypedDict, total=False):
+    x: int
+    y: int
+    z: int
+
+class LeverBlock(_BlockPos):
+    type: Literal["lever"]
+    on: bool
+    facing: Direction
+
+class ButtonBlock(_BlockPos):
+    type: Literal["button"]
+    ticks_remaining: int
+    facing: Direction
+
+class DustBlock(_BlockPos):
+    type: Literal["dust"]
+    power: int
+    connections: NotRequired[tuple[bool, bool, bool, bool]]
+
+class LampBlock(_BlockPos):
+    type: Literal["lamp"]
+    on: bool
+
+class RepeaterBlock(_BlockPos):
+    type: Literal["repeater"]
+    delay: int
+    ticks_remaining: int
+    powered: bool
+    facing: Direction
+    locked: NotRequired[bool]
+
+class ComparatorBlock(_BlockPos):
+    type: Literal["comparator"]
+    output: int
+    facing: Direction
+    mode: NotRequired[ComparatorMode]
+
+class TorchBlock(_BlockPos):
+    type: Literal["torch"]
+    lit: bool
+    facing: Direction
+    burnout_counter: NotRequired[int]
+    burnout_cooldown: NotRequired[int]
+    burned_out: NotRequired[bool]
+
+class PistonBlock(_BlockPos):
+    type: Literal["piston"]
+    extended: bool
+    facing: Direction
+    sticky: NotRequired[bool]
+
+class PistonArmBlock(_BlockPos):
+    type: Literal["pistonarm"]
+    facing: Direction
+
+class HopperBlock(_BlockPos):
+    type: Literal["hopper"]
+    enabled: bool
+    facing: Direction
+    items: NotRequired[int]
+
+class ObserverBlock(_BlockPos):
+    type: Literal["observer"]
+    facing: Direction
+    powered: bool
+
+class OpaqueSolidBlock(_BlockPos):
+    type: Literal["opaquesolid"]
+    strong_power: int
+
+class RedstoneBlockBlock(_BlockPos):
+    type: Literal["redstoneblock"]
+
+class TargetBlock(_BlockPos):
+    type: Literal["target"]
+    power: int
+    ticks_remaining: int
+
+class SculkSensorBlock(_BlockPos):
+    type: Literal["sculksensor"]
+    power: int
+    ticks_remaining: int
+    waterlogged: bool
+
+class CalibratedSculkSensorBlock(_BlockPos):
+    type: Literal["calibratedsculksensor"]
+    power: int
+    ticks_remaining: int
+    waterlogged: bool
+    facing: Direction
+
+class DoorBlock(_BlockPos):
+    type: Literal["door"]
+    open: bool
+    hinge: Literal["left", "right"]
+    facing: Direction
+    half: Literal["upper", "lower"]
+
+class TrapdoorBlock(_BlockPos):
+    type: Literal["trapdoor"]
+    open: bool
+    facing: Direction
+    half: Literal["top", "bottom"]
+
+class NoteBlockBlock(_BlockPos):
+    type: Literal["noteblock"]
+    instrument: Instrument
+    pitch: int
+    triggered: bool
+    powered: NotRequired[bool]
+
+class PoweredRailBlock(_BlockPos):
+    type: Literal["poweredrail"]
+    powered: bool
+    shape: RailShape
+
+class DetectorRailBlock(_BlockPos):
+    type: Literal["detectorrail"]
+    powered: bool
+    shape: RailShape
+
+class ActivatorRailBlock(_BlockPos):
+    type: Literal["activatorrail"]
+    powered: bool
+    shape: RailShape
+
+class PressurePlateBlock(_BlockPos):
+    type: Literal["pressureplate"]
+    powered: bool
+    kind: PlateKind
+    power: NotRequired[int]
+
+class DropperBlock(_BlockPos):
+    type: Literal["dropper"]
+    facing: Direction
+    powered: bool
+    cooldown: NotRequired[int]
+
+class DispenserBlock(_BlockPos):
+    type: Literal["dispenser"]
+    facing: Direction
+    powered: bool
+    cooldown: NotRequired[int]
+
+class ChestBlock(_BlockPos):
+    type: Literal["chest"]
+    items: int
+    max_items: NotRequired[int]  # 27、ラージチェストは 54
+
+class BarrelBlock(_BlockPos):
+    type: Literal["barrel"]
+    items: int
+    max_items: NotRequired[int]  # 27 (省略時) か 54
+
+class FurnaceBlock(_BlockPos):
+    type: Literal["furnace"]
+    fuel_ticks: int
+    cooking: bool
+
+class TripwireHookBlock(_BlockPos):
+    type: Literal["tripwirehook"]
+    attached: bool
+    powered: bool
+    facing: Direction
+
+class TripwireBlock(_BlockPos):
+    type: Literal["tripwire"]
+    powered: bool
+
+class DaylightSensorBlock(_BlockPos):
+    type: Literal["daylightsensor"]
+    inverted: bool
+    power: int
+
+class LightningRodBlock(_BlockPos):
+    type: Literal["lightningrod"]
+    powered: bool
+    ticks_remaining: NotRequired[int]
+
+class CopperBulbBlock(_BlockPos):
+    type: Literal["copperbulb"]
+    lit: bool
+    powered: bool
+    oxidation: NotRequired[OxidationLevel]
+
+class CrafterBlock(_BlockPos):
+    type: Literal["crafter"]
+    powered: bool
+    cooldown: NotRequired[int]
+    craft_count: NotRequired[int]
+
+class BellBlock(_BlockPos):
+    type: Literal["bell"]
+    attachment: BellAttachment
+    facing: Direction
+    ringing: bool
+    powered: NotRequired[bool]
+    ticks_remaining: NotRequired[int]
+
+class FenceGateBlock(_BlockPos):
+    type: Literal["fencegate"]
+    open: bool
+    facing: Direction
+    in_wall: bool
+
+BlockKind = Union[
+    LeverBlock,
+    ButtonBlock,
+    DustBlock,
+    LampBlock,
+    RepeaterBlock,
+    ComparatorBlock,
+    TorchBlock,
+    PistonBlock,
+    PistonArmBlock,
+    HopperBlock,
+    ObserverBlock,
+    OpaqueSolidBlock,
+    RedstoneBlockBlock,
+    TargetBlock,
+    SculkSensorBlock,
+    CalibratedSculkSensorBlock,
+    DoorBlock,
+    TrapdoorBlock,
+    NoteBlockBlock,
+    PoweredRailBlock,
+    DetectorRailBlock,
+    ActivatorRailBlock,
+    PressurePlateBlock,
+    DropperBlock,
+    DispenserBlock,
+    ChestBlock,
+    BarrelBlock,
+    FurnaceBlock,
+    TripwireHookBlock,
+    TripwireBlock,
+    DaylightSensorBlock,
+    LightningRodBlock,
+    CopperBulbBlock,
+    CrafterBlock,
+    BellBlock,
+    FenceGateBlock,
+]
+
+# 座標付きの BlockKind。TypedDict では交差型を書けないので同じ型にしている
+PlacedBlock = BlockKind
+BlockChange = BlockKind
+
+class World(TypedDict):
+    blocks: list[PlacedBlock]
+
+class _TargetHit(TypedDict):
+    x: int
+    y: int
+    z: int
+    strength: int
+
+class TargetHitEvent(TypedDict):
+    target_hit: _TargetHit
+
+class LeverToggleEvent(TypedDict):
+    lever_toggle: Pos
+
+class ButtonPressEvent(TypedDict):
+    button_press: Pos
+
+class BlockReplaceEvent(TypedDict):
+    block_replace: tuple[Pos, BlockKind]
+
+class FurnaceFuelEvent(TypedDict):
+    furnace_fuel: tuple[Pos, int]  # 追加する燃焼 tick 数
+
+TimedEvent = Union[TargetHitEvent, LeverToggleEvent, ButtonPressEvent, BlockReplaceEvent, FurnaceFuelEvent]
+
+class EntityPressure(TypedDict, total=False):
+    mobs: int
+    items: int
+
+class SimRequest(TypedDict):
+    ticks: int
+    world: World
+    early_exit: NotRequired[bool]
+    events: NotRequired[list[tuple[int, TimedEvent]]]
+    vibrations: NotRequired[list[tuple[int, Pos, int]]]
+    minecart_events: NotRequired[list[tuple[int, Pos, bool]]]
+    entity_events: NotRequired[list[tuple[int, Pos, EntityPressure]]]
+    lightning_strikes: NotRequired[list[tuple[int, Pos]]]
+    parallel: NotRequired[bool]
+    quasi_connectivity: NotRequired[bool]
+    update_order: NotRequired[Literal["arbitrary", "mojang_java", "deterministic", "random"]]
+    time_of_day: NotRequired[int]
+    time_advance_per_tick: NotRequired[int]
+    seed: NotRequired[int | None]
+
+class TickDiff(TypedDict):
+    tick: int
+    changes: list[BlockChange]
+    removed: NotRequired[list[Pos]]
+
+class LaunchEvent(TypedDict):
+    tick: int
+    x: int
+    y: int
+    z: int
+    facing: Direction
+    kind: Literal["dispense", "drop"]
+
+class LightningEvent(TypedDict):
+    tick: int
+    x: int
+    y: int
+    z: int
+
+class CraftEvent(TypedDict):
+    tick: int
+    x: int
+    y: int
+    z: int
+
+class BellRingEvent(TypedDict):
+    tick: int
+    x: int
+    y: int
+    z: int
+    facing: Direction
+
+class SimResponse(TypedDict):
+    diffs: list[TickDiff]
+    terminated: Literal["stable", "max_ticks_reached", "predicate_met"]
+    notes: list[tuple[int, Pos, Instrument, int]]
+    launch_events: list[LaunchEvent]
+    lightning_events: list[LightningEvent]
+    craft_events: list[CraftEvent]
+    bell_events: list[BellRingEvent]
+    oscillation_period: int | None
+    seed_used: int
+
+class BlockConnections(TypedDict):
+    inputs: list[Pos]
+    outputs: list[Pos]
+
+class _BlockWarning(TypedDict):
+    kind: Literal["disconnected_block", "floating_dust", "repeater_facing_void", "comparator_facing_void"]
+    x: int
+    y: int
+    z: int
+
+class _CycleWarning(TypedDict):
+    kind: Literal["cyclic_instability"]
+    cycle: list[Pos]
+
+WiringWarning = Union[_BlockWarning, _CycleWarning]
+
+# ─── 関数 ───────────────────────────────────────────
+
+class SerializationFormat:
+    Json: ClassVar[SerializationFormat]
+    Binary: ClassVar[SerializationFormat]
+    def __int__(self) -> int: ...
+
+# Json なら SimRequest の JSON (str)、Binary なら bincode (bytes) を受け取り、同じ形式で返す
+@overload
+def simulate_py(request: str, fmt: SerializationFormat = ...) -> str: ...
+@overload
+def simulate_py(request: bytes, fmt: SerializationFormat) -> bytes: ...
+
+# SimRequest の JSON 配列 → SimResponse か {"error": ...} の JSON 配列
+def simulate_many_py(json_list: str) -> str: ...
+
+# msgpack フィーチャー付きでビルドしたときだけある
+def simulate_msgpack_py(data: bytes) -> bytes: ...
+
+# csv フィーチャー付きでビルドしたときだけある。CSV (x,y,z,type,facing,on_or_power,...) → World の JSON
+def world_from_csv_py(s: str) -> str: ...
+
+# PlacedBlock の JSON → BlockConnections の JSON
+def block_connections_py(json_text: str) -> str: ...
+
+# World の JSON → WiringWarning の JSON 配列
+def validate_world_py(json_text: str) -> str: ...
+
+# World の JSON → Pos の JSON 配列
+def dead_blocks_py(json_text: str) -> str: ...
+def sourceless_blocks_py(json_text: str) -> str: ...
+
+# simulate_until_py / simulate_while_py の条件
+class LampPredicate(TypedDict):
+    lamp_at: tuple[int, int, int]
+    on: bool
+
+class PowerPredicate(TypedDict):
+    power_at: tuple[int, int, int]
+    at_least: NotRequired[int]  # 省略時 1
+
+class AllPredicate(TypedDict):
+    all: list[Predicate]
+
+class AnyPredicate(TypedDict):
+    any: list[Predicate]
+
+Predicate = Union[LampPredicate, PowerPredicate, AllPredicate, AnyPredicate]
+
+# SimRequest の JSON と Predicate の JSON → SimResponse の JSON。
+# until は条件を満たした tick で、while は満たさなくなった tick で止まる (terminated = "predicate_met")
+def simulate_until_py(json_text: str, predicate_json: str) -> str: ...
+def simulate_while_py(json_text: str, predicate_json: str) -> str: ...
+
+# SimResponse の JSON → positions の各座標の波形を 1 行ずつ並べたタイミングチャート
+def timing_diagram_py(response_json: str, positions: list[tuple[int, int, int]], ticks: int) -> str: ...
+
+# python-async フィーチャー付きでビルドしたときだけある。simulate_py を await できる形にしたもの
+def simulate_async_py(json_text: str) -> Awaitable[str]: ...
+
+# 同じく python-async。`async for diff in simulate_streaming_py(req):` で TickDiff の JSON を受け取る
+def simulate_streaming_py(json_text: str) -> TickDiffStream: ...
+
+class TickDiffStream:
+    def __aiter__(self) -> TickDiffStream: ...
+    def __anext__(self) -> Awaitable[str]: ...
+
+# numpy フィーチャー付きでビルドしたときだけある。World の JSON の y 断面の電力レベルを
+# 形 (z の数, x の数) の uint8 配列で返す。範囲は (start, stop) の半開区間
+def power_grid_py(
+    world_json: str, y_level: int, x_range: tuple[int, int], z_range: tuple[int, int]
+) -> npt.NDArray[np.uint8]: ...
+
+# ─── クラス ─────────────────────────────────────────
+
+class PyWorld:
+    def __init__(self) -> None: ...
+    def add_lever(self, x: int, y: int, z: int, facing: Direction, on: bool = False) -> None: ...
+    def add_button(self, x: int, y: int, z: int, facing: Direction) -> None: ...
+    def add_dust(self, x: int, y: int, z: int) -> None: ...
+    def add_lamp(self, x: int, y: int, z: int) -> None: ...
+    def add_repeater(self, x: int, y: int, z: int, facing: Direction, delay: int = 1) -> None: ...
+    def add_comparator(self, x: int, y: int, z: int, facing: Direction, subtract: bool = False) -> None: ...
+    def add_torch(self, x: int, y: int, z: int, facing: Direction) -> None: ...
+    def add_piston(self, x: int, y: int, z: int, facing: Direction, sticky: bool = False) -> None: ...
+    def add_observer(self, x: int, y: int, z: int, facing: Direction) -> None: ...
+    def add_solid(self, x: int, y: int, z: int) -> None: ...
+    def add_redstone_block(self, x: int, y: int, z: int) -> None: ...
+    def add_block(self, x: int, y: int, z: int, kind_json: str) -> None: ...
+    def to_json(self) -> str: ...
+    @staticmethod
+    def from_json(s: str) -> PyWorld: ...
+    # 種類名 ("dust" など) → 個数
+    def count_by_kind(self) -> dict[str, int]: ...
+    def total_blocks(self) -> int: ...
+    def has_block_at(self, x: int, y: int, z: int) -> bool: ...
+    # 置いた順の座標
+    def blocks_of_kind(self, kind_name: str) -> list[tuple[int, int, int]]: ...
+    def __len__(self) -> int: ...
+
+class PySimRequest:
+    def __init__(self, world: PyWorld, ticks: int, early_exit: bool = True) -> None: ...
+    @property
+    def ticks(self) -> int: ...
+    @property
+    def early_exit(self) -> bool: ...
+    def to_json(self) -> str: ...
+    def simulate(self) -> PySimResponse: ...
+
+class PySimResponse:
+    @property
+    def diffs(self) -> list[PyTickDiff]: ...
+    @property
+    def terminated(self) -> Literal["stable", "max_ticks_reached", "predicate_met"]: ...
+    @property
+    def oscillation_period(self) -> int | None: ...
+    @property
+    def seed_used(self) -> int: ...
+    def to_json(self) -> str: ...
+    def __len__(self) -> int: ...
+
+class PyTickDiff:
+    @property
+    def tick(self) -> int: ...
+    @property
+    def changes(self) -> list[BlockChange]: ...
+    @property
+    def removed(self) -> list[tuple[int, int, int]]: ...
+    def __len__(self) -> int: ...
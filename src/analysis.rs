@@ -0,0 +1,1226 @@
+// src/analysis.rs
+
+// Static analysis of circuit topology (only oscillator_period and
+// equivalent_circuits / minimize_circuit run the simulator, and detect_period / timing_diagram
+// read the diffs of a finished run)
+
+use crate::{BlockKind, Connectable, Pos, SimError, SimRequest, SpatialGrid, SpatialSet, TickDiff, World};
+use serde::Serialize;
+use alloc::collections::VecDeque;
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::fmt::Write;
+
+// -------------------------------------------------
+// Connectivity
+// -------------------------------------------------
+/// Directed signal edges `(from, to)`: `from` outputs into `to` and `to`
+/// accepts input from `from`. Sorted for deterministic output.
+fn signal_edges(world: &SpatialGrid<BlockKind>) -> Vec<(Pos, Pos)> {
+    let mut edges = Vec::new();
+    for (pos, block) in world {
+        for out in block.output_positions(*pos) {
+            let Some(target) = world.get(&out) else { continue };
+            // solid blocks are only (strongly) powered by direct sources,
+            // as in the simulator; dust pointing into them does nothing
+            if matches!(target, BlockKind::OpaqueSolid { .. }) && !is_direct_source(block) {
+                continue;
+            }
+            if target.input_positions(out).contains(pos) {
+                edges.push((*pos, out));
+            }
+        }
+    }
+    edges.sort_by_key(|(a, b)| (pos_key(*a), pos_key(*b)));
+    edges.dedup();
+    edges
+}
+
+fn pos_key(p: Pos) -> (i32, i32, i32) {
+    (p.x, p.y, p.z)
+}
+
+fn is_direct_source(block: &BlockKind) -> bool {
+    matches!(
+        block,
+        BlockKind::Lever { .. }
+            | BlockKind::Button { .. }
+            | BlockKind::Torch { .. }
+            | BlockKind::Repeater { .. }
+            | BlockKind::Comparator { .. }
+            | BlockKind::Observer { .. }
+    )
+}
+
+// -------------------------------------------------
+// Feedback loops
+// -------------------------------------------------
+/// Connected dust is one wire, not a loop, so every dust network is
+/// collapsed to a single node (its smallest position) before looking for
+/// cycles. Returns the node each position maps to.
+fn dust_groups(world: &SpatialGrid<BlockKind>, edges: &[(Pos, Pos)]) -> SpatialGrid<Pos> {
+    fn find(parent: &mut SpatialGrid<Pos>, p: Pos) -> Pos {
+        let up = parent[&p];
+        if up == p {
+            return p;
+        }
+        let root = find(parent, up);
+        parent.insert(p, root);
+        root
+    }
+
+    let mut parent: SpatialGrid<Pos> = world.keys().map(|p| (*p, *p)).collect();
+    let is_dust = |p: &Pos| matches!(world.get(p), Some(BlockKind::Dust { .. }));
+    for (a, b) in edges.iter().filter(|(a, b)| is_dust(a) && is_dust(b)) {
+        let (ra, rb) = (find(&mut parent, *a), find(&mut parent, *b));
+        if ra != rb {
+            let (keep, join) = if pos_key(ra) <= pos_key(rb) { (ra, rb) } else { (rb, ra) };
+            parent.insert(join, keep);
+        }
+    }
+    let positions: Vec<Pos> = world.keys().copied().collect();
+    positions.into_iter().map(|p| (p, find(&mut parent, p))).collect()
+}
+
+/// Tarjan's strongly connected components over `adj`.
+struct Tarjan<'a> {
+    adj: &'a SpatialGrid<Vec<Pos>>,
+    index: SpatialGrid<usize>,
+    low: SpatialGrid<usize>,
+    stack: Vec<Pos>,
+    on_stack: SpatialGrid<bool>,
+    components: Vec<Vec<Pos>>,
+}
+
+impl Tarjan<'_> {
+    fn visit(&mut self, v: Pos) {
+        let i = self.index.len();
+        self.index.insert(v, i);
+        self.low.insert(v, i);
+        self.stack.push(v);
+        self.on_stack.insert(v, true);
+        for w in self.adj.get(&v).into_iter().flatten() {
+            if !self.index.contains_key(w) {
+                self.visit(*w);
+                let low = self.low[&v].min(self.low[w]);
+                self.low.insert(v, low);
+            } else if self.on_stack.get(w) == Some(&true) {
+                let low = self.low[&v].min(self.index[w]);
+                self.low.insert(v, low);
+            }
+        }
+        if self.low[&v] == self.index[&v] {
+            let mut component = Vec::new();
+            while let Some(w) = self.stack.pop() {
+                self.on_stack.insert(w, false);
+                component.push(w);
+                if w == v {
+                    break;
+                }
+            }
+            self.components.push(component);
+        }
+    }
+}
+
+/// Feedback loops in the circuit: every strongly connected component of two
+/// or more blocks in the signal graph, as sorted positions. Connected dust
+/// counts as a single block, so plain wires are not reported.
+pub fn detect_cycles(world: &SpatialGrid<BlockKind>) -> Vec<Vec<Pos>> {
+    let edges = signal_edges(world);
+    let group = dust_groups(world, &edges);
+    let mut adj: SpatialGrid<Vec<Pos>> = SpatialGrid::default();
+    for (a, b) in &edges {
+        let (ga, gb) = (group[a], group[b]);
+        if ga != gb {
+            adj.entry(ga).or_default().push(gb);
+        }
+    }
+
+    let mut nodes: Vec<Pos> = adj.keys().copied().collect();
+    nodes.sort_by_key(|p| pos_key(*p));
+    let mut tarjan = Tarjan {
+        adj: &adj,
+        index: SpatialGrid::default(),
+        low: SpatialGrid::default(),
+        stack: Vec::new(),
+        on_stack: SpatialGrid::default(),
+        components: Vec::new(),
+    };
+    for v in nodes {
+        if !tarjan.index.contains_key(&v) {
+            tarjan.visit(v);
+        }
+    }
+
+    let mut cycles: Vec<Vec<Pos>> = tarjan
+        .components
+        .into_iter()
+        .filter(|c| c.len() >= 2)
+        .map(|c| {
+            // expand collapsed dust networks back into their positions
+            let mut members: Vec<Pos> =
+                group.iter().filter(|(_, g)| c.contains(g)).map(|(p, _)| *p).collect();
+            members.sort_by_key(|p| pos_key(*p));
+            members
+        })
+        .collect();
+    cycles.sort_by_key(|c| pos_key(c[0]));
+    cycles
+}
+
+/// Whether the circuit contains any feedback loop.
+pub fn is_oscillator(world: &SpatialGrid<BlockKind>) -> bool {
+    !detect_cycles(world).is_empty()
+}
+
+/// Run `request` for up to `max_ticks` and return the number of ticks after
+/// which the world state first repeats, or `None` if it settles (or no
+/// repeat is seen in time).
+pub fn oscillator_period(request: SimRequest, max_ticks: u32) -> Option<u32> {
+    fn snapshot(world: &SpatialGrid<BlockKind>) -> Vec<(Pos, BlockKind)> {
+        let mut s: Vec<(Pos, BlockKind)> = world.iter().map(|(p, b)| (*p, b.clone())).collect();
+        s.sort_by_key(|(p, _)| pos_key(*p));
+        s
+    }
+
+    let mut sim = SimRequest { ticks: max_ticks, early_exit: true, ..request }.into_iter();
+    let mut history = vec![snapshot(sim.world())];
+    while sim.next().is_some() {
+        let now = snapshot(sim.world());
+        if let Some(seen) = history.iter().rposition(|s| *s == now) {
+            return Some((history.len() - seen) as u32);
+        }
+        history.push(now);
+    }
+    None
+}
+
+/// Smallest period `p` such that, over the last `window` ticks covered by
+/// `diffs`, the state at every tick `t` equals the state at `t + p`. Only
+/// positions some diff touched are compared, so the initial world is not
+/// needed. `None` if no period up to half the window fits.
+pub fn detect_period(diffs: &[TickDiff], window: u32) -> Option<u32> {
+    let last = diffs.last()?.tick;
+    let first = last.saturating_sub(window) + 1;
+    // `None` marks a removed block
+    let mut state: SpatialGrid<Option<BlockKind>> = SpatialGrid::default();
+    let mut states = Vec::new();
+    let mut pending = diffs.iter().peekable();
+    for tick in 1..=last {
+        while let Some(diff) = pending.next_if(|d| d.tick <= tick) {
+            for c in &diff.changes {
+                state.insert(c.pos, Some(c.kind.clone()));
+            }
+            for p in &diff.removed {
+                state.insert(*p, None);
+            }
+        }
+        if tick >= first {
+            states.push(state.clone());
+        }
+    }
+    (1..=states.len() / 2)
+        .find(|&p| (0..states.len() - p).all(|t| states[t] == states[t + p]))
+        .map(|p| p as u32)
+}
+
+// -------------------------------------------------
+// Signal paths
+// -------------------------------------------------
+fn adjacency(world: &SpatialGrid<BlockKind>) -> SpatialGrid<Vec<Pos>> {
+    let mut adj: SpatialGrid<Vec<Pos>> = SpatialGrid::default();
+    for (a, b) in signal_edges(world) {
+        adj.entry(a).or_default().push(b);
+    }
+    adj
+}
+
+/// Shortest chain of blocks (both ends included) along which a signal can
+/// travel from `from` to `to`, following outputs in their own direction
+/// only. `None` if `to` cannot be reached.
+pub fn find_signal_path(world: &SpatialGrid<BlockKind>, from: Pos, to: Pos) -> Option<Vec<Pos>> {
+    if !world.contains_key(&from) || !world.contains_key(&to) {
+        return None;
+    }
+    let adj = adjacency(world);
+    let mut came_from: SpatialGrid<Pos> = SpatialGrid::default();
+    let mut queue = VecDeque::from([from]);
+    let mut seen = SpatialSet::from_iter([from]);
+    while let Some(p) = queue.pop_front() {
+        if p == to {
+            let mut path = vec![to];
+            let mut cur = to;
+            while let Some(prev) = came_from.get(&cur) {
+                path.push(*prev);
+                cur = *prev;
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for next in adj.get(&p).into_iter().flatten() {
+            if seen.insert(*next) {
+                came_from.insert(*next, p);
+                queue.push_back(*next);
+            }
+        }
+    }
+    None
+}
+
+/// Whether a signal leaving `from` can reach `to` at all.
+pub fn signal_reaches(world: &SpatialGrid<BlockKind>, from: Pos, to: Pos) -> bool {
+    find_signal_path(world, from, to).is_some()
+}
+
+/// Every loop-free path from `from` to `to` of at most `max_depth` steps,
+/// shortest first. Useful for spotting redundant wiring.
+pub fn find_all_paths(world: &SpatialGrid<BlockKind>, from: Pos, to: Pos, max_depth: usize) -> Vec<Vec<Pos>> {
+    fn walk(
+        adj: &SpatialGrid<Vec<Pos>>,
+        to: Pos,
+        max_depth: usize,
+        path: &mut Vec<Pos>,
+        found: &mut Vec<Vec<Pos>>,
+    ) {
+        let here = *path.last().unwrap();
+        if here == to {
+            found.push(path.clone());
+            return;
+        }
+        if path.len() > max_depth {
+            return;
+        }
+        for next in adj.get(&here).into_iter().flatten() {
+            if !path.contains(next) {
+                path.push(*next);
+                walk(adj, to, max_depth, path, found);
+                path.pop();
+            }
+        }
+    }
+
+    if !world.contains_key(&from) {
+        return vec![];
+    }
+    let mut adj = adjacency(world);
+    for next in adj.values_mut() {
+        next.sort_by_key(|p| pos_key(*p));
+    }
+    let mut found = Vec::new();
+    walk(&adj, to, max_depth, &mut vec![from], &mut found);
+    found.sort_by_key(|p| p.len());
+    found
+}
+
+// -------------------------------------------------
+// Fanout
+// -------------------------------------------------
+/// Number of blocks each block outputs into (the signal edges leaving it),
+/// for every block in `world`; 0 for blocks that drive nothing.
+pub fn fanout_map(world: &SpatialGrid<BlockKind>) -> SpatialGrid<usize> {
+    let mut map: SpatialGrid<usize> = world.keys().map(|p| (*p, 0)).collect();
+    for (from, _) in signal_edges(world) {
+        *map.entry(from).or_default() += 1;
+    }
+    map
+}
+
+/// Number of blocks receiving output from `pos`; 0 if nothing is there.
+pub fn fanout(world: &SpatialGrid<BlockKind>, pos: Pos) -> usize {
+    fanout_map(world).get(&pos).copied().unwrap_or(0)
+}
+
+/// The block driving the most others, and how many; the smallest position
+/// wins a tie. `None` if no block drives anything.
+pub fn max_fanout_block(world: &SpatialGrid<BlockKind>) -> Option<(Pos, usize)> {
+    fanout_map(world)
+        .into_iter()
+        .filter(|(_, n)| *n > 0)
+        .max_by_key(|(p, n)| (*n, core::cmp::Reverse(pos_key(*p))))
+}
+
+// -------------------------------------------------
+// Range queries
+// -------------------------------------------------
+/// Every block no further than `radius` from `center` in a straight line
+/// (see [`Pos::within_radius`]), sorted by position.
+pub fn blocks_within_radius(world: &SpatialGrid<BlockKind>, center: Pos, radius: f64) -> Vec<Pos> {
+    let mut found: Vec<Pos> = world.keys().copied().filter(|p| p.within_radius(center, radius)).collect();
+    found.sort_by_key(|p| pos_key(*p));
+    found
+}
+
+// -------------------------------------------------
+// Reachability
+// -------------------------------------------------
+/// Blocks a signal from no power source can ever reach, sorted by position.
+/// Sources themselves are never dead; piston arms, containers and tripwire,
+/// which carry no signal, are left out.
+pub fn dead_blocks(world: &SpatialGrid<BlockKind>) -> Vec<Pos> {
+    let adj = adjacency(world);
+    let mut queue: VecDeque<Pos> = world.iter().filter(|(_, b)| b.is_power_source()).map(|(p, _)| *p).collect();
+    let mut reached: SpatialSet = queue.iter().copied().collect();
+    while let Some(p) = queue.pop_front() {
+        for next in adj.get(&p).into_iter().flatten() {
+            if reached.insert(*next) {
+                queue.push_back(*next);
+            }
+        }
+    }
+    let mut dead: Vec<Pos> = world
+        .iter()
+        .filter(|(p, b)| {
+            !reached.contains(*p)
+                && !matches!(
+                    b,
+                    BlockKind::PistonArm { .. }
+                        | BlockKind::Chest { .. }
+                        | BlockKind::Barrel { .. }
+                        | BlockKind::Furnace { .. }
+                        | BlockKind::Tripwire { .. }
+                )
+        })
+        .map(|(p, _)| *p)
+        .collect();
+    dead.sort_by_key(|p| pos_key(*p));
+    dead
+}
+
+/// The dead blocks that act when powered (lamps, pistons, repeaters, ...),
+/// leaving out dust and solid blocks: components that will never switch
+/// however the sources are set.
+pub fn sourceless_blocks(world: &SpatialGrid<BlockKind>) -> Vec<Pos> {
+    dead_blocks(world)
+        .into_iter()
+        .filter(|p| !matches!(world[p], BlockKind::Dust { .. } | BlockKind::OpaqueSolid { .. }))
+        .collect()
+}
+
+/// The blocks joined to `seed` by signal edges in either direction, with
+/// their state unchanged and in `world`'s order. Empty if nothing is at
+/// `seed`.
+pub fn extract_connected_component(world: &World, seed: Pos) -> World {
+    let map = world.clone().into_map();
+    if !map.contains_key(&seed) {
+        return World { blocks: Vec::new() };
+    }
+    let mut linked: SpatialGrid<Vec<Pos>> = SpatialGrid::default();
+    for (a, b) in signal_edges(&map) {
+        linked.entry(a).or_default().push(b);
+        linked.entry(b).or_default().push(a);
+    }
+    let mut queue = VecDeque::from([seed]);
+    let mut seen = SpatialSet::from_iter([seed]);
+    while let Some(p) = queue.pop_front() {
+        for next in linked.get(&p).into_iter().flatten() {
+            if seen.insert(*next) {
+                queue.push_back(*next);
+            }
+        }
+    }
+    World { blocks: world.blocks.iter().filter(|b| seen.contains(&b.pos)).cloned().collect() }
+}
+
+// -------------------------------------------------
+// Source attribution
+// -------------------------------------------------
+/// For every power source (see [`BlockKind::is_power_source`]) whose signal
+/// can reach `pos`, the shortest chain from it to `pos`, both ends included;
+/// found by a backward BFS from `pos`. The search stops at the first source
+/// on each branch, so a torch is credited rather than whatever switches it.
+/// Sources count whether or not they are on. Shortest first, then by source
+/// position.
+pub fn trace_sources(world: &SpatialGrid<BlockKind>, pos: Pos) -> Vec<Vec<Pos>> {
+    let mut inputs: SpatialGrid<Vec<Pos>> = SpatialGrid::default();
+    for (a, b) in signal_edges(world) {
+        inputs.entry(b).or_default().push(a);
+    }
+    // each visited block's next step towards `pos`
+    let mut towards: SpatialGrid<Pos> = SpatialGrid::default();
+    let mut queue = VecDeque::from([pos]);
+    let mut seen = SpatialSet::from_iter([pos]);
+    let mut sources = Vec::new();
+    while let Some(p) = queue.pop_front() {
+        for prev in inputs.get(&p).into_iter().flatten() {
+            if !seen.insert(*prev) {
+                continue;
+            }
+            towards.insert(*prev, p);
+            if world[prev].is_power_source() {
+                sources.push(*prev);
+            } else {
+                queue.push_back(*prev);
+            }
+        }
+    }
+    let mut paths: Vec<Vec<Pos>> = sources
+        .into_iter()
+        .map(|source| {
+            let mut path = vec![source];
+            while let Some(next) = towards.get(path.last().unwrap()) {
+                path.push(*next);
+            }
+            path
+        })
+        .collect();
+    paths.sort_by_key(|path| (path.len(), pos_key(path[0])));
+    paths
+}
+
+/// Just the sources of [`trace_sources`], sorted by position.
+pub fn power_source_of(world: &SpatialGrid<BlockKind>, pos: Pos) -> Vec<Pos> {
+    let mut sources: Vec<Pos> = trace_sources(world, pos).into_iter().map(|path| path[0]).collect();
+    sources.sort_by_key(|p| pos_key(*p));
+    sources
+}
+
+// -------------------------------------------------
+// Timing
+// -------------------------------------------------
+// ticks between a block's input changing and its output following, as
+// `simulate` runs it: dust settles within the tick
+fn step_delay(block: &BlockKind) -> u32 {
+    match block {
+        BlockKind::Dust { .. } => 0,
+        BlockKind::Repeater { delay, .. } => u32::from(*delay),
+        _ => 1,
+    }
+}
+
+/// The slowest chain from a power source, and its delay in ticks: the sum
+/// of the step delays of every block after the source (a repeater's
+/// configured delay, nothing for dust, one tick for anything else).
+/// Feedback loops are left out, since they have no longest path. Empty with
+/// delay 0 if the world has no source.
+pub fn critical_path(world: &SpatialGrid<BlockKind>) -> (Vec<Pos>, u32) {
+    let looped: SpatialSet = detect_cycles(world).into_iter().flatten().collect();
+    let adj = adjacency(world);
+    let mut best: SpatialGrid<(u32, Option<Pos>)> = SpatialGrid::default();
+    let mut queue = VecDeque::new();
+    for (p, b) in world {
+        if b.is_power_source() && !looped.contains(p) {
+            best.insert(*p, (0, None));
+            queue.push_back(*p);
+        }
+    }
+    // longest-path relaxation; connected dust forms the only cycles left,
+    // and those add no delay, so this settles
+    while let Some(p) = queue.pop_front() {
+        let here = best[&p].0;
+        for next in adj.get(&p).into_iter().flatten().filter(|n| !looped.contains(*n)) {
+            let delay = here + step_delay(&world[next]);
+            if best.get(next).is_none_or(|(d, _)| delay > *d) {
+                best.insert(*next, (delay, Some(p)));
+                queue.push_back(*next);
+            }
+        }
+    }
+
+    let slowest = best.iter().max_by_key(|(p, (d, _))| (*d, core::cmp::Reverse(pos_key(**p))));
+    let Some((&end, &(delay, _))) = slowest else {
+        return (Vec::new(), 0);
+    };
+    let mut path = vec![end];
+    while let Some((_, Some(prev))) = best.get(path.last().unwrap()) {
+        path.push(*prev);
+    }
+    path.reverse();
+    (path, delay)
+}
+
+/// Shortest period a clock driving the circuit can have: each half must
+/// outlast the critical path, so the output settles before the next edge.
+pub fn min_clock_period(world: &SpatialGrid<BlockKind>) -> u32 {
+    (2 * critical_path(world).1).max(2)
+}
+
+// -------------------------------------------------
+// Equivalence
+// -------------------------------------------------
+/// Most inputs `equivalent_circuits` will enumerate (2^20 runs per circuit).
+pub const MAX_EQUIVALENCE_INPUTS: usize = 20;
+
+/// Why two circuits could not be compared.
+#[derive(Clone, Debug, PartialEq)]
+pub enum EquivalenceError {
+    TooManyInputs(usize), // more than MAX_EQUIVALENCE_INPUTS levers to enumerate
+    NotALever(Pos),       // an input position holds no lever in one of the worlds
+    Simulation(SimError), // one of the worlds could not be simulated
+}
+
+impl core::fmt::Display for EquivalenceError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            EquivalenceError::TooManyInputs(n) => {
+                write!(f, "{} inputs is too many to enumerate (at most {})", n, MAX_EQUIVALENCE_INPUTS)
+            }
+            EquivalenceError::NotALever(p) => write!(f, "input at {} is not a lever", p),
+            EquivalenceError::Simulation(e) => e.fmt(f),
+        }
+    }
+}
+
+impl core::error::Error for EquivalenceError {}
+
+// `BlockKind::current_power` of every output at the end of each tick
+// 0..=ticks, with every input lever set from the bits of `mask`
+fn output_history(
+    world: &World,
+    inputs: &[Pos],
+    outputs: &[Pos],
+    mask: u32,
+    ticks: u32,
+) -> Result<Vec<Vec<u8>>, EquivalenceError> {
+    let mut world = world.clone().into_map();
+    for (i, pos) in inputs.iter().enumerate() {
+        match world.get_mut(pos) {
+            Some(BlockKind::Lever { on, .. }) => *on = mask & (1 << i) != 0,
+            _ => return Err(EquivalenceError::NotALever(*pos)),
+        }
+    }
+    let read =
+        |w: &SpatialGrid<BlockKind>| outputs.iter().map(|p| w.get(p).map_or(0, BlockKind::current_power)).collect();
+    let world: World = world.into_iter().collect();
+    let mut sim = SimRequest { ticks, world, early_exit: false, ..Default::default() }.into_iter();
+    if let Some(e) = sim.error() {
+        return Err(EquivalenceError::Simulation(e.clone()));
+    }
+    let mut history = vec![read(sim.world())];
+    while sim.next().is_some() {
+        history.push(read(sim.world()));
+    }
+    Ok(history)
+}
+
+/// Whether `w1` and `w2` give the same power at every output position on
+/// every tick up to `ticks`, for each of the `2^inputs.len()` ways to set
+/// the levers at `inputs`. Both worlds need a lever at every input; their
+/// other blocks may differ freely. Each combination is a fresh run, so state
+/// left over from one (a latch, say) does not carry into the next.
+pub fn equivalent_circuits(
+    w1: &World,
+    w2: &World,
+    inputs: Vec<Pos>,
+    outputs: Vec<Pos>,
+    ticks: u32,
+) -> Result<bool, EquivalenceError> {
+    if inputs.len() > MAX_EQUIVALENCE_INPUTS {
+        return Err(EquivalenceError::TooManyInputs(inputs.len()));
+    }
+    for mask in 0..1u32 << inputs.len() {
+        let h1 = output_history(w1, &inputs, &outputs, mask, ticks)?;
+        if h1 != output_history(w2, &inputs, &outputs, mask, ticks)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Ticks `minimize_circuit` compares outputs over; enough for the small
+/// circuits it is practical on to settle after every input combination.
+pub const MINIMIZE_TICKS: u32 = 50;
+
+/// Why a circuit could not be minimized: the same checks as
+/// `equivalent_circuits`, run on the original world.
+pub type MinimizeError = EquivalenceError;
+
+/// `world` without the blocks its outputs do not depend on. Greedy: each
+/// block other than the inputs and outputs is tried once, in position order,
+/// and dropped if the world without it stays equivalent (over
+/// `MINIMIZE_TICKS`) to the original. A smaller world may exist; a removal
+/// that leaves the world unable to simulate keeps the block.
+pub fn minimize_circuit(world: &World, inputs: Vec<Pos>, outputs: Vec<Pos>) -> Result<World, MinimizeError> {
+    // surfaces the original's own errors, so any later one is the candidate's
+    equivalent_circuits(world, world, inputs.clone(), outputs.clone(), MINIMIZE_TICKS)?;
+    let mut candidates: Vec<Pos> =
+        world.blocks.iter().map(|b| b.pos).filter(|p| !inputs.contains(p) && !outputs.contains(p)).collect();
+    candidates.sort_by_key(|p| pos_key(*p));
+
+    let mut minimal = world.clone();
+    for pos in candidates {
+        let mut smaller = minimal.clone();
+        smaller.remove_block(pos);
+        if let Ok(true) = equivalent_circuits(world, &smaller, inputs.clone(), outputs.clone(), MINIMIZE_TICKS) {
+            minimal = smaller;
+        }
+    }
+    Ok(minimal)
+}
+
+// -------------------------------------------------
+// Wiring warnings
+// -------------------------------------------------
+/// A likely wiring mistake. Unlike `WorldError`, the world still simulates.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ValidationWarning {
+    DisconnectedBlock(Pos),                // neither feeds nor reads any other block
+    FloatingDust(Pos),                     // dust with no block on any face
+    RepeaterFacingVoid(Pos),               // nothing in front of the repeater
+    ComparatorFacingVoid(Pos),             // nothing in front of the comparator
+    CyclicInstability { cycle: Vec<Pos> }, // loop through an odd number of torches
+}
+
+impl core::fmt::Display for ValidationWarning {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ValidationWarning::DisconnectedBlock(p) => write!(f, "block at {} is not connected", p),
+            ValidationWarning::FloatingDust(p) => write!(f, "dust at {} has no neighbours", p),
+            ValidationWarning::RepeaterFacingVoid(p) => write!(f, "repeater at {} faces nothing", p),
+            ValidationWarning::ComparatorFacingVoid(p) => write!(f, "comparator at {} faces nothing", p),
+            ValidationWarning::CyclicInstability { cycle } => {
+                write!(f, "odd torch loop of {} blocks at {}", cycle.len(), cycle[0])
+            }
+        }
+    }
+}
+
+/// Every wiring warning for the world, ordered by position with unstable
+/// loops last.
+pub fn wiring_warnings(world: &SpatialGrid<BlockKind>) -> Vec<ValidationWarning> {
+    let edges = signal_edges(world);
+    let connected: SpatialSet = edges.iter().flat_map(|(a, b)| [*a, *b]).collect();
+    let mut positions: Vec<&Pos> = world.keys().collect();
+    positions.sort_by_key(|p| pos_key(**p));
+
+    let mut warnings = Vec::new();
+    for pos in positions {
+        let block = &world[pos];
+        match block {
+            BlockKind::Dust { .. } if pos.neighbors().iter().all(|(n, _)| !world.contains_key(n)) => {
+                warnings.push(ValidationWarning::FloatingDust(*pos));
+                continue;
+            }
+            BlockKind::Repeater { facing, .. } if !world.contains_key(&(*pos + *facing)) => {
+                warnings.push(ValidationWarning::RepeaterFacingVoid(*pos));
+            }
+            BlockKind::Comparator { facing, .. } if !world.contains_key(&(*pos + *facing)) => {
+                warnings.push(ValidationWarning::ComparatorFacingVoid(*pos));
+            }
+            _ => {}
+        }
+        // solids, piston arms and containers take part without signal edges
+        let passive = matches!(
+            block,
+            BlockKind::OpaqueSolid { .. }
+                | BlockKind::PistonArm { .. }
+                | BlockKind::Chest { .. }
+                | BlockKind::Barrel { .. }
+                | BlockKind::Furnace { .. }
+        );
+        if !passive && !connected.contains(pos) {
+            warnings.push(ValidationWarning::DisconnectedBlock(*pos));
+        }
+    }
+    for cycle in detect_cycles(world) {
+        let torches = cycle.iter().filter(|p| matches!(world[*p], BlockKind::Torch { .. })).count();
+        if torches % 2 == 1 {
+            warnings.push(ValidationWarning::CyclicInstability { cycle });
+        }
+    }
+    warnings
+}
+
+// -------------------------------------------------
+// Graphviz export
+// -------------------------------------------------
+fn node_shape(block: &BlockKind) -> &'static str {
+    match block {
+        BlockKind::Lamp { .. } | BlockKind::CopperBulb { .. } => "box",
+        BlockKind::Comparator { .. } => "diamond",
+        BlockKind::Repeater { .. } => "triangle",
+        BlockKind::Torch { .. } => "invtriangle",
+        BlockKind::Lever { .. }
+        | BlockKind::Button { .. }
+        | BlockKind::PressurePlate { .. }
+        | BlockKind::TripwireHook { .. } => "house",
+        BlockKind::Dust { .. } => "circle",
+        BlockKind::OpaqueSolid { .. } | BlockKind::RedstoneBlock => "box3d",
+        BlockKind::Piston { .. } | BlockKind::PistonArm { .. } => "cds",
+        BlockKind::Observer { .. } => "hexagon",
+        BlockKind::NoteBlock { .. } => "note",
+        BlockKind::Hopper { .. }
+        | BlockKind::Chest { .. }
+        | BlockKind::Barrel { .. }
+        | BlockKind::Furnace { .. }
+        | BlockKind::Dropper { .. }
+        | BlockKind::Dispenser { .. }
+        | BlockKind::Crafter { .. } => "cylinder",
+        BlockKind::PoweredRail { .. } | BlockKind::DetectorRail { .. } | BlockKind::ActivatorRail { .. } => {
+            "parallelogram"
+        }
+        _ => "ellipse",
+    }
+}
+
+fn node_id(p: Pos) -> String {
+    format!("\"{},{},{}\"", p.x, p.y, p.z)
+}
+
+/// Render the world as a Graphviz `digraph`. Nodes are labelled with the
+/// block type and position, shaped by type and filled from white (power 0)
+/// to red (power 15); edges follow the direction signals travel.
+pub fn world_to_dot(world: &SpatialGrid<BlockKind>) -> String {
+    let mut positions: Vec<&Pos> = world.keys().collect();
+    positions.sort_by_key(|p| pos_key(**p));
+
+    let mut out = String::from("digraph redstone {\n");
+    for pos in positions {
+        let block = &world[pos];
+        let fade = 255 - u32::from(block.current_power()) * 17;
+        let _ = writeln!(
+            out,
+            "    {} [label=\"{}\\n{}\", shape={}, style=filled, fillcolor=\"#ff{:02x}{:02x}\"];",
+            node_id(*pos),
+            block.type_name(),
+            pos,
+            node_shape(block),
+            fade,
+            fade
+        );
+    }
+    for (from, to) in signal_edges(world) {
+        let _ = writeln!(out, "    {} -> {};", node_id(from), node_id(to));
+    }
+    out.push_str("}\n");
+    out
+}
+
+// -------------------------------------------------
+// Timing diagrams
+// -------------------------------------------------
+// one column of the diagram: `#`/`_` for a lamp or copper bulb (they are lit
+// or not, they carry no power), otherwise the power level in hex
+fn wave_char(block: Option<&BlockKind>) -> char {
+    match block {
+        Some(BlockKind::Lamp { on: true } | BlockKind::CopperBulb { lit: true, .. }) => '#',
+        Some(BlockKind::Lamp { .. } | BlockKind::CopperBulb { .. }) => '_',
+        Some(b) => match crate::power_level(b) {
+            0 => '_',
+            p => char::from_digit(u32::from(p.min(15)), 16).unwrap().to_ascii_uppercase(),
+        },
+        None => '_',
+    }
+}
+
+/// ASCII timing diagram of `positions` over ticks `1..=ticks`, one line per
+/// position and one column per tick: `_` for power 0, `1`–`F` for power
+/// 1–15, and `#` for a lit lamp or copper bulb. Lines are labelled
+/// `(x,y,z) type:` and padded to a common width. The diffs only record
+/// changes, so a position reads `_` until its first change, and its type
+/// is `?` if it never changes.
+pub fn timing_diagram(diffs: &[TickDiff], positions: &[Pos], ticks: u32) -> String {
+    let mut state: SpatialGrid<Option<BlockKind>> = SpatialGrid::default();
+    let mut waves: Vec<String> = vec![String::new(); positions.len()];
+    let mut pending = diffs.iter().peekable();
+    for tick in 1..=ticks {
+        while let Some(diff) = pending.next_if(|d| d.tick <= tick) {
+            for c in &diff.changes {
+                state.insert(c.pos, Some(c.kind.clone()));
+            }
+            for p in &diff.removed {
+                state.insert(*p, None);
+            }
+        }
+        for (wave, p) in waves.iter_mut().zip(positions) {
+            wave.push(wave_char(state.get(p).and_then(Option::as_ref)));
+        }
+    }
+
+    // the type comes from the last change seen; a removed block keeps it
+    let labels: Vec<String> = positions
+        .iter()
+        .map(|p| {
+            let kind = diffs
+                .iter()
+                .rev()
+                .flat_map(|d| d.changes.iter())
+                .find(|c| c.pos == *p)
+                .map_or("?", |c| c.kind.type_name());
+            format!("{p} {kind}:")
+        })
+        .collect();
+    let width = labels.iter().map(String::len).max().unwrap_or(0);
+    let mut out = String::new();
+    for (label, wave) in labels.iter().zip(&waves) {
+        let _ = writeln!(out, "{label:<width$} {wave}");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{simulate, Direction, Termination, TimedEvent, WorldBuilder};
+
+    fn repeater_loop() -> World {
+        // repeater feeding a U of dust that comes back round into its back
+        [(1, 0, 0), (1, 0, 1), (0, 0, 1), (-1, 0, 1), (-1, 0, 0)]
+            .into_iter()
+            .fold(WorldBuilder::new(), |b, p| b.dust(p))
+            .repeater((0, 0, 0), Direction::East, 1)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn finds_repeater_dust_loop_but_not_a_wire() {
+        let cycles = detect_cycles(&repeater_loop().into_map());
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].len(), 6);
+
+        let wire = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, true)
+            .dust((1, 0, 0))
+            .dust((2, 0, 0))
+            .lamp((3, 0, 0))
+            .build()
+            .unwrap();
+        assert!(!is_oscillator(&wire.into_map()));
+    }
+
+    #[test]
+    fn observer_clock_period() {
+        let clock = WorldBuilder::new()
+            .block((0, 0, 0), BlockKind::Observer { facing: Direction::East, powered: true })
+            .observer((1, 0, 0), Direction::West)
+            .build()
+            .unwrap();
+        let request = |world| SimRequest { ticks: 0, world, ..Default::default() };
+        assert!(matches!(oscillator_period(request(clock), 50), Some(p) if p >= 2));
+        let lamp = WorldBuilder::new().lever((0, 0, 0), Direction::East, true).lamp((1, 0, 0)).build().unwrap();
+        assert_eq!(oscillator_period(request(lamp), 50), None);
+    }
+
+    #[test]
+    fn clocks_report_oscillation_period() {
+        let request = |world, ticks| SimRequest { ticks, world, ..Default::default() };
+        // two observers watching each other pulse every other tick
+        let observers = WorldBuilder::new()
+            .block((0, 0, 0), BlockKind::Observer { facing: Direction::East, powered: true })
+            .observer((1, 0, 0), Direction::West)
+            .build()
+            .unwrap();
+        let res = simulate(request(observers, 20)).unwrap();
+        assert!(matches!(res.terminated, Termination::MaxTicksReached));
+        assert_eq!(res.oscillation_period, Some(2));
+        assert_eq!(detect_period(&res.diffs, 8), Some(2));
+        assert_eq!(detect_period(&res.diffs[..1], 8), None);
+
+        // each torch feeds the other through one dust and both start lit, so
+        // they switch together every tick until they burn out; the cycle is
+        // 9 ticks of blinking and 30 dark
+        let torches = WorldBuilder::new()
+            .torch((1, 0, 0), Direction::West)
+            .torch((0, 0, 1), Direction::East)
+            .dust((0, 0, 0))
+            .dust((1, 0, 1))
+            .build()
+            .unwrap();
+        let res = simulate(request(torches, 200)).unwrap();
+        assert_eq!(res.oscillation_period, Some(39));
+    }
+
+    #[test]
+    fn traces_torch_through_dust_to_lamp() {
+        let world = WorldBuilder::new()
+            .torch((0, 0, 0), Direction::Down)
+            .dust((1, 0, 0))
+            .dust((2, 0, 0))
+            .lamp((3, 0, 0))
+            .build()
+            .unwrap()
+            .into_map();
+        let path = find_signal_path(&world, Pos::from((0, 0, 0)), Pos::from((3, 0, 0))).unwrap();
+        let expected: Vec<Pos> = [(0, 0, 0), (1, 0, 0), (2, 0, 0), (3, 0, 0)].map(Pos::from).to_vec();
+        assert_eq!(path, expected);
+        assert_eq!(find_all_paths(&world, Pos::from((0, 0, 0)), Pos::from((3, 0, 0)), 8), vec![expected]);
+        assert!(find_all_paths(&world, Pos::from((0, 0, 0)), Pos::from((3, 0, 0)), 2).is_empty());
+    }
+
+    #[test]
+    fn torch_fans_out_to_surrounding_dust() {
+        let mut builder = WorldBuilder::new().torch((0, 0, 0), Direction::Down);
+        for p in [(1, 0, 0), (-1, 0, 0), (0, 0, 1), (0, 0, -1), (0, 1, 0)] {
+            builder = builder.dust(p);
+        }
+        let world = builder.build().unwrap().into_map();
+        let torch = Pos::from((0, 0, 0));
+        assert_eq!(fanout(&world, torch), 5);
+        assert_eq!(fanout(&world, Pos::from((5, 0, 0))), 0);
+        assert_eq!(max_fanout_block(&world), Some((torch, 5)));
+        let map = fanout_map(&world);
+        assert_eq!(map.len(), 6);
+        assert!(map.iter().all(|(p, n)| *p == torch || *n < 5));
+
+        let lamp = WorldBuilder::new().lamp((0, 0, 0)).build().unwrap().into_map();
+        assert_eq!(max_fanout_block(&lamp), None);
+    }
+
+    #[test]
+    fn inverter_matches_nor_with_a_grounded_input() {
+        let tail = |b: WorldBuilder| b.dust((0, 0, 0)).torch((1, 0, 0), Direction::West).lamp((2, 0, 0));
+        let input = Pos::from((-1, 0, 0));
+        let inverter = tail(WorldBuilder::new().lever(input, Direction::East, false)).build().unwrap();
+        let nor = |b_on| {
+            tail(WorldBuilder::new().lever(input, Direction::East, false).lever((0, 0, 1), Direction::North, b_on))
+                .build()
+                .unwrap()
+        };
+        let lamp = vec![Pos::from((2, 0, 0))];
+        assert_eq!(equivalent_circuits(&inverter, &nor(false), vec![input], lamp.clone(), 10), Ok(true));
+        // with the other input high the NOR never lights
+        assert_eq!(equivalent_circuits(&inverter, &nor(true), vec![input], lamp.clone(), 10), Ok(false));
+        // a plain wire is the inverse
+        let wire = WorldBuilder::new().lever(input, Direction::East, false).dust((0, 0, 0)).dust((1, 0, 0)).lamp((2, 0, 0));
+        assert_eq!(equivalent_circuits(&inverter, &wire.build().unwrap(), vec![input], lamp.clone(), 10), Ok(false));
+
+        assert_eq!(
+            equivalent_circuits(&inverter, &nor(false), vec![Pos::from((2, 0, 0))], lamp.clone(), 10),
+            Err(EquivalenceError::NotALever(Pos::from((2, 0, 0))))
+        );
+        let many: Vec<Pos> = (0..21).map(|x| Pos::from((x, 9, 0))).collect();
+        assert_eq!(equivalent_circuits(&inverter, &inverter, many, lamp, 10), Err(EquivalenceError::TooManyInputs(21)));
+    }
+
+    #[test]
+    fn minimizing_drops_a_dead_lamp() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, false)
+            .dust((1, 0, 0))
+            .dust((2, 0, 0))
+            .lamp((3, 0, 0))
+            .lamp((6, 4, 0)) // reached by nothing
+            .build()
+            .unwrap();
+        let (input, output) = (Pos::from((0, 0, 0)), Pos::from((3, 0, 0)));
+        let minimal = minimize_circuit(&world, vec![input], vec![output]).unwrap();
+        assert!(!minimal.contains(Pos::from((6, 4, 0))));
+        // the wire itself carries the signal and stays
+        assert_eq!(minimal.blocks.len(), 4);
+        assert_eq!(equivalent_circuits(&world, &minimal, vec![input], vec![output], MINIMIZE_TICKS), Ok(true));
+
+        assert_eq!(
+            minimize_circuit(&world, vec![Pos::from((1, 0, 0))], vec![output]).unwrap_err(),
+            EquivalenceError::NotALever(Pos::from((1, 0, 0)))
+        );
+    }
+
+    #[test]
+    fn finds_blocks_in_a_sphere() {
+        let world = WorldBuilder::new()
+            .lamp((0, 0, 0))
+            .lamp((3, 0, 4))
+            .lamp((3, 1, 4))
+            .lamp((-2, 2, 1))
+            .build()
+            .unwrap()
+            .into_map();
+        let center = Pos::default();
+        assert_eq!(blocks_within_radius(&world, center, 5.0), vec![Pos::from((-2, 2, 1)), center, Pos::from((3, 0, 4))]);
+        assert_eq!(blocks_within_radius(&world, center, 0.0), vec![center]);
+        assert!(blocks_within_radius(&world, Pos::from((50, 0, 0)), 8.0).is_empty());
+    }
+
+    #[test]
+    fn repeater_blocks_reverse_flow() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, true)
+            .repeater((1, 0, 0), Direction::East, 1)
+            .lamp((2, 0, 0))
+            .build()
+            .unwrap()
+            .into_map();
+        assert!(signal_reaches(&world, Pos::from((0, 0, 0)), Pos::from((2, 0, 0))));
+        assert!(!signal_reaches(&world, Pos::from((2, 0, 0)), Pos::from((0, 0, 0))));
+        assert_eq!(find_signal_path(&world, Pos::from((2, 0, 0)), Pos::from((1, 0, 0))), None);
+    }
+
+    #[test]
+    fn warns_about_wiring_mistakes() {
+        let world = WorldBuilder::new()
+            .repeater((0, 0, 0), Direction::East, 1)
+            .comparator((0, 0, 3), Direction::East, Default::default())
+            .dust((5, 0, 5))
+            .lamp((10, 0, 0))
+            .build()
+            .unwrap();
+        let at = |x, z| Pos::from((x, 0, z));
+        assert_eq!(
+            world.validate_wiring(),
+            vec![
+                ValidationWarning::RepeaterFacingVoid(at(0, 0)),
+                ValidationWarning::DisconnectedBlock(at(0, 0)),
+                ValidationWarning::ComparatorFacingVoid(at(0, 3)),
+                ValidationWarning::DisconnectedBlock(at(0, 3)),
+                ValidationWarning::FloatingDust(at(5, 5)),
+                ValidationWarning::DisconnectedBlock(at(10, 0)),
+            ]
+        );
+        let fine = WorldBuilder::new().lever((0, 0, 0), Direction::East, true).lamp((1, 0, 0)).build().unwrap();
+        assert!(fine.validate_wiring().is_empty());
+    }
+
+    #[test]
+    fn warns_about_single_torch_loop() {
+        // torch on a solid block, fed back into that block through a
+        // repeater, two dust and another repeater
+        let world = WorldBuilder::new()
+            .solid((0, 0, 0))
+            .torch((1, 0, 0), Direction::West)
+            .repeater((1, 0, -1), Direction::North, 1)
+            .dust((1, 0, -2))
+            .dust((0, 0, -2))
+            .repeater((0, 0, -1), Direction::South, 1)
+            .build()
+            .unwrap();
+        let warnings = world.validate_wiring();
+        assert_eq!(warnings.len(), 1);
+        assert!(matches!(&warnings[0], ValidationWarning::CyclicInstability { cycle } if cycle.len() == 6));
+    }
+
+    #[test]
+    fn unpowered_lamp_is_dead() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, false)
+            .dust((1, 0, 0))
+            .lamp((2, 0, 0))
+            // no lever anywhere near this one
+            .dust((0, 0, 5))
+            .lamp((1, 0, 5))
+            .build()
+            .unwrap()
+            .into_map();
+        let at = |x, z| Pos { x, y: 0, z };
+        assert_eq!(dead_blocks(&world), vec![at(0, 5), at(1, 5)]);
+        assert_eq!(sourceless_blocks(&world), vec![at(1, 5)]);
+    }
+
+    #[test]
+    fn traces_each_lever_powering_a_lamp() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, true)
+            .dust((1, 0, 0))
+            .dust((2, 0, 0))
+            .lamp((3, 0, 0))
+            // a second lever reaching the lamp from the south
+            .lever((3, 0, 3), Direction::North, false)
+            .dust((3, 0, 2))
+            .dust((3, 0, 1))
+            .build()
+            .unwrap()
+            .into_map();
+        let at = |x, z| Pos { x, y: 0, z };
+        assert_eq!(
+            trace_sources(&world, at(3, 0)),
+            vec![vec![at(0, 0), at(1, 0), at(2, 0), at(3, 0)], vec![at(3, 3), at(3, 2), at(3, 1), at(3, 0)]]
+        );
+        assert_eq!(power_source_of(&world, at(3, 0)), vec![at(0, 0), at(3, 3)]);
+
+        // the torch is credited, not the lever switching it
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, false)
+            .solid((1, 0, 0))
+            .torch((2, 0, 0), Direction::West)
+            .lamp((3, 0, 0))
+            .build()
+            .unwrap()
+            .into_map();
+        assert_eq!(trace_sources(&world, at(3, 0)), vec![vec![at(2, 0), at(3, 0)]]);
+        assert!(power_source_of(&world, at(9, 9)).is_empty());
+    }
+
+    #[test]
+    fn critical_path_sums_repeater_delays() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, true)
+            .dust((1, 0, 0))
+            .repeater((2, 0, 0), Direction::East, 1)
+            .repeater((3, 0, 0), Direction::East, 3)
+            .dust((4, 0, 0))
+            .repeater((5, 0, 0), Direction::East, 2)
+            .lamp((6, 0, 0))
+            // a quicker branch off the same lever
+            .lamp((0, 0, 1))
+            .build()
+            .unwrap();
+        let (path, delay) = critical_path(&world.clone().into_map());
+        assert_eq!(path, (0..=6).map(|x| Pos { x, y: 0, z: 0 }).collect::<Vec<_>>());
+        // 1 + 3 + 2 for the repeaters, 1 for the lamp
+        assert_eq!(delay, 7);
+        assert_eq!(min_clock_period(&world.clone().into_map()), 14);
+
+        // the estimate matches the tick the simulator lights the lamp
+        let request = SimRequest { ticks: 20, world, ..Default::default() };
+        let res = simulate(request).unwrap();
+        let lit = res.diffs.iter().find(|d| d.changed_lamps().any(|(p, on)| p.x == 6 && on));
+        assert_eq!(lit.map(|d| d.tick), Some(7));
+        assert_eq!(critical_path(&SpatialGrid::default()), (vec![], 0));
+    }
+
+    #[test]
+    fn extracts_connected_component() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, true)
+            .dust((1, 0, 0))
+            .lamp((2, 0, 0))
+            // a second circuit beside the first
+            .lever((0, 0, 3), Direction::East, false)
+            .lamp((1, 0, 3))
+            .build()
+            .unwrap();
+        let component = extract_connected_component(&world, Pos { x: 2, y: 0, z: 0 });
+        assert_eq!(component.blocks, world.blocks[..3].to_vec());
+        assert_eq!(extract_connected_component(&world, Pos { x: 1, y: 0, z: 3 }).blocks, world.blocks[3..].to_vec());
+        assert!(extract_connected_component(&world, Pos { x: 9, y: 0, z: 9 }).blocks.is_empty());
+    }
+
+    #[test]
+    fn dot_has_nodes_edges_and_power_colors() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, true)
+            .lamp((1, 0, 0))
+            // separate component, no edges
+            .comparator((5, 0, 5), Direction::North, Default::default())
+            .build()
+            .unwrap();
+        let dot = world_to_dot(&world.into_map());
+        assert!(dot.starts_with("digraph redstone {\n") && dot.ends_with("}\n"));
+        assert!(dot.contains("\"0,0,0\" [label=\"lever\\n(0,0,0)\", shape=house, style=filled, fillcolor=\"#ff0000\"]"));
+        assert!(dot.contains("\"1,0,0\" [label=\"lamp\\n(1,0,0)\", shape=box, style=filled, fillcolor=\"#ffffff\"]"));
+        assert!(dot.contains("shape=diamond"));
+        assert!(dot.contains("\"0,0,0\" -> \"1,0,0\";"));
+        assert_eq!(dot.matches("->").count(), 1);
+    }
+
+    #[test]
+    fn timing_diagram_staggers_a_repeater_chain() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, false)
+            .repeater((1, 0, 0), Direction::East, 4)
+            .repeater((2, 0, 0), Direction::East, 4)
+            .lamp((3, 0, 0))
+            .build()
+            .unwrap();
+        let res = simulate(SimRequest {
+            ticks: 14,
+            world,
+            early_exit: false,
+            events: vec![(1, TimedEvent::LeverToggle(Pos { x: 0, y: 0, z: 0 }))],
+            ..Default::default()
+        })
+        .unwrap();
+        let positions: Vec<Pos> = (0..5).map(|x| Pos { x, y: 0, z: 0 }).collect();
+        let diagram = timing_diagram(&res.diffs, &positions, 14);
+        assert_eq!(
+            diagram,
+            "(0,0,0) lever:    FFFFFFFFFFFFFF\n\
+             (1,0,0) repeater: ___FFFFFFFFFFF\n\
+             (2,0,0) repeater: _______FFFFFFF\n\
+             (3,0,0) lamp:     ________######\n\
+             (4,0,0) ?:        ______________\n"
+        );
+    }
+}
@@ -0,0 +1,42 @@
+// src/async_sim.rs
+
+// Async entry points for servers running on tokio. The simulation itself
+// is CPU-bound, so it runs on tokio's blocking thread pool and never stalls
+// the executor. Only built with the `async` feature.
+
+use crate::{simulate, SimError, SimRequest, SimResponse, TickDiff};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::Stream;
+
+/// Diffs buffered before the simulation waits for the consumer.
+const STREAM_BUFFER: usize = 64;
+
+/// `simulate` on the blocking thread pool. Must be called from within a
+/// tokio runtime.
+pub async fn simulate_async(request: SimRequest) -> Result<SimResponse, SimError> {
+    match tokio::task::spawn_blocking(move || simulate(request)).await {
+        Ok(result) => result,
+        Err(e) => std::panic::resume_unwind(e.into_panic()),
+    }
+}
+
+/// Run the simulation on the blocking thread pool and yield each non-empty
+/// diff as soon as its tick is done, the same diffs `simulate` collects.
+/// An invalid world ends the stream with its error. Dropping the stream
+/// stops the simulation at the next tick.
+pub async fn simulate_streaming(request: SimRequest) -> impl Stream<Item = Result<TickDiff, SimError>> {
+    let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+    tokio::task::spawn_blocking(move || {
+        let mut sim = request.into_iter();
+        for diff in sim.by_ref().filter(|d| !d.changes.is_empty() || !d.removed.is_empty()) {
+            if tx.blocking_send(Ok(diff)).is_err() {
+                return; // receiver dropped
+            }
+        }
+        if let Some(e) = sim.error().cloned() {
+            let _ = tx.blocking_send(Err(e));
+        }
+    });
+    ReceiverStream::new(rx)
+}
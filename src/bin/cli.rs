@@ -0,0 +1,484 @@
+// src/bin/cli.rs
+
+// redstonesim-cli: run and inspect circuits from the shell, on the same
+// JSON the Python functions take. Built with the `cli` feature:
+//   cargo run --no-default-features --features cli -- simulate --input world.json --ticks 100
+// Every `--input` / `--block` accepts `-` for stdin. Summaries are for
+// people; `--json` prints the machine-readable form instead. `repl` steps
+// through a circuit interactively. With the `yaml` feature, `--format yaml`
+// reads and writes YAML in place of JSON, and `--compact` reads requests in
+// the array-per-block form of `redstonesim::compact`.
+
+use clap::{Parser, Subcommand, ValueEnum};
+use redstonesim::analysis::{timing_diagram, world_to_dot};
+use redstonesim::compact::from_compact_json;
+use redstonesim::{
+    simulate, BlockKind, Connectable, PlacedBlock, Pos, SimRequest, SimResponse, SimulationIterator, Termination,
+    TickDiff, TimedEvent, World,
+};
+use rustyline::error::ReadlineError;
+use serde_json::Value;
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(name = "redstonesim-cli", version, about = "Simulate Minecraft redstone circuits given as JSON")]
+struct Cli {
+    /// Print JSON instead of a human-readable summary
+    #[arg(long, global = true)]
+    json: bool,
+    /// Format of input files, `--output` files and `--json` output
+    #[arg(long, global = true, value_enum, default_value_t = Format::Json)]
+    format: Format,
+    /// Read requests given to `--input` as compact JSON (`to_compact_json`)
+    #[arg(long, global = true, conflicts_with = "format")]
+    compact: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run a world (or a whole SimRequest) and report what changed
+    Simulate {
+        /// World or SimRequest JSON file, `-` for stdin
+        #[arg(long)]
+        input: String,
+        /// Ticks to run; overrides the request's own `ticks`
+        #[arg(long)]
+        ticks: Option<u32>,
+        /// Write the SimResponse JSON to this file
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Check a world for errors and likely wiring mistakes
+    Validate {
+        /// World JSON file, `-` for stdin
+        #[arg(long)]
+        input: String,
+    },
+    /// Show where a single block reads input from and outputs to
+    Connections {
+        /// PlacedBlock JSON file, `-` for stdin
+        #[arg(long)]
+        block: String,
+    },
+    /// Simulate and draw the power at some positions, one column per tick
+    TimingDiagram {
+        /// World or SimRequest JSON file, `-` for stdin
+        #[arg(long)]
+        input: String,
+        /// Ticks to simulate and draw
+        #[arg(long, default_value_t = 20)]
+        ticks: u32,
+        /// Positions as `x,y,z` separated by `;`, e.g. "0,0,0;1,0,0"
+        #[arg(long)]
+        positions: String,
+    },
+    /// Export the circuit as a Graphviz digraph
+    Graph {
+        /// World JSON file, `-` for stdin
+        #[arg(long)]
+        input: String,
+        /// Write the DOT here instead of stdout
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Step through a circuit interactively (type `help` inside)
+    Repl {
+        /// World or SimRequest JSON file to load at start
+        #[arg(long)]
+        input: Option<String>,
+    },
+}
+
+#[derive(Clone, Copy, Default, ValueEnum)]
+enum Format {
+    #[default]
+    Json,
+    #[cfg(feature = "yaml")]
+    Yaml,
+}
+
+impl Format {
+    fn name(self) -> &'static str {
+        match self {
+            Format::Json => "JSON",
+            #[cfg(feature = "yaml")]
+            Format::Yaml => "YAML",
+        }
+    }
+
+    fn parse<T: serde::de::DeserializeOwned>(self, text: &str, what: &str) -> Result<T, String> {
+        match self {
+            Format::Json => serde_json::from_str(text).map_err(|e| format!("invalid {what}: {e}")),
+            #[cfg(feature = "yaml")]
+            Format::Yaml => serde_yaml::from_str(text).map_err(|e| format!("invalid {what}: {e}")),
+        }
+    }
+
+    fn render<T: serde::Serialize>(self, value: &T) -> Result<String, String> {
+        match self {
+            Format::Json => serde_json::to_string_pretty(value).map_err(|e| e.to_string()),
+            // enums as one-key maps, as in JSON, so --format yaml reads its own
+            // output back; serde_yaml ends documents with a newline and
+            // println! adds another
+            #[cfg(feature = "yaml")]
+            Format::Yaml => {
+                let mut out = Vec::new();
+                serde_yaml::with::singleton_map_recursive::serialize(value, &mut serde_yaml::Serializer::new(&mut out))
+                    .map_err(|e| e.to_string())?;
+                Ok(String::from_utf8_lossy(&out).trim_end().to_owned())
+            }
+        }
+    }
+}
+
+// ticks to run a bare world for when neither the file nor --ticks says
+const DEFAULT_TICKS: u32 = 100;
+
+fn read_input(path: &str) -> Result<String, String> {
+    if path == "-" {
+        let mut text = String::new();
+        std::io::stdin().read_to_string(&mut text).map_err(|e| format!("stdin: {e}"))?;
+        Ok(text)
+    } else {
+        std::fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))
+    }
+}
+
+fn write_output(path: &PathBuf, text: &str) -> Result<(), String> {
+    std::fs::write(path, text).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+// a file holding just a world (`{"blocks": [...]}`) is wrapped into a
+// request; `ticks` overrides whatever the file says. A compact file is always
+// a whole request.
+fn load_request(path: &str, ticks: Option<u32>, format: Format, compact: bool) -> Result<SimRequest, String> {
+    if compact {
+        let mut req = from_compact_json(&read_input(path)?).map_err(|e| e.to_string())?;
+        req.ticks = ticks.unwrap_or(req.ticks);
+        return Ok(req);
+    }
+    let mut value: Value = format.parse(&read_input(path)?, format.name())?;
+    if value.get("blocks").is_some() {
+        value = serde_json::json!({ "world": value, "ticks": DEFAULT_TICKS });
+    }
+    if let (Some(ticks), Some(obj)) = (ticks, value.as_object_mut()) {
+        obj.insert("ticks".into(), ticks.into());
+    }
+    serde_json::from_value(value).map_err(|e| format!("invalid request: {e}"))
+}
+
+fn load_world(path: &str, format: Format) -> Result<World, String> {
+    let world: World = format.parse(&read_input(path)?, "world")?;
+    world.validate().map_err(|e| e.to_string())?;
+    Ok(world)
+}
+
+fn parse_positions(list: &str) -> Result<Vec<Pos>, String> {
+    list.split(';')
+        .filter(|p| !p.trim().is_empty())
+        .map(|p| p.parse::<Pos>().map_err(|e| format!("bad position `{}`: {e}", p.trim())))
+        .collect()
+}
+
+fn summary(ticks: u32, resp: &SimResponse) -> String {
+    let end = match resp.terminated {
+        Termination::Stable => "settled",
+        Termination::MaxTicksReached => "hit the tick limit",
+        Termination::PredicateMet => "stopped on its condition",
+    };
+    let changes: usize = resp.diffs.iter().map(|d| d.changes.len() + d.removed.len()).sum();
+    let mut out = format!(
+        "{} of {ticks} ticks had changes ({changes} block changes); {end}\n",
+        resp.diffs.len()
+    );
+    if let Some(period) = resp.oscillation_period {
+        out += &format!("oscillating with period {period}\n");
+    }
+    for diff in &resp.diffs {
+        let blocks: Vec<String> = diff.changes.iter().map(|c| format!("{} {}", c.kind.type_name(), c.pos)).collect();
+        out += &format!("tick {}: {}", diff.tick, blocks.join(", "));
+        if !diff.removed.is_empty() {
+            let removed: Vec<String> = diff.removed.iter().map(Pos::to_string).collect();
+            out += &format!(" (removed {})", removed.join(", "));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+// -------------------------------------------------
+// REPL
+// -------------------------------------------------
+const REPL_HELP: &str = "\
+load <file>     load a world or SimRequest JSON file
+step [N] [--lookahead]
+                advance N ticks (default 1); with --lookahead, show each
+                tick's changes before running it
+state [x,y,z]   show one block, or every block
+toggle <x,y,z>  flip a lever or press a button on the next tick
+watch <x,y,z>   print whenever the block changes
+diff            show the last tick's changes
+quit            leave";
+
+#[derive(Default)]
+struct Repl {
+    sim: Option<SimulationIterator>,
+    last: Option<TickDiff>,
+    watches: Vec<Pos>,
+    format: Format, // of files given to `load`
+    compact: bool,
+}
+
+fn block_json(kind: &BlockKind) -> String {
+    serde_json::to_string(kind).unwrap_or_else(|e| e.to_string())
+}
+
+fn parse_pos(arg: Option<&str>) -> Result<Pos, String> {
+    match parse_positions(arg.ok_or("expected a position x,y,z")?)?[..] {
+        [pos] => Ok(pos),
+        _ => Err("expected a single position x,y,z".into()),
+    }
+}
+
+impl Repl {
+    fn sim(&mut self) -> Result<&mut SimulationIterator, String> {
+        self.sim.as_mut().ok_or_else(|| "nothing loaded; use `load <file>`".into())
+    }
+
+    fn load(&mut self, path: &str) -> Result<(), String> {
+        let mut req = load_request(path, None, self.format, self.compact)?;
+        // the user decides when to stop
+        req.ticks = u32::MAX;
+        req.early_exit = false;
+        let blocks = req.world.total_blocks();
+        let sim = req.into_iter();
+        if let Some(e) = sim.error() {
+            return Err(e.to_string());
+        }
+        *self = Repl { sim: Some(sim), last: None, watches: std::mem::take(&mut self.watches), ..*self };
+        println!("loaded {blocks} blocks");
+        Ok(())
+    }
+
+    fn step(&mut self, ticks: u32, lookahead: bool) -> Result<(), String> {
+        for _ in 0..ticks {
+            let sim = self.sim()?;
+            if let Some(next) = lookahead.then(|| sim.peek_next_changes()).flatten() {
+                println!("tick {} will change:", next.tick);
+                for c in &next.changes {
+                    println!("  {} {}", c.pos, block_json(&c.kind));
+                }
+                for p in &next.removed {
+                    println!("  {p} removed");
+                }
+            }
+            let Some(diff) = sim.next() else {
+                return Err(sim.error().map_or("the simulation has ended".into(), |e| e.to_string()));
+            };
+            for c in diff.changes.iter().filter(|c| self.watches.contains(&c.pos)) {
+                println!("tick {}: {} {}", diff.tick, c.pos, block_json(&c.kind));
+            }
+            for p in diff.removed.iter().filter(|p| self.watches.contains(p)) {
+                println!("tick {}: {p} removed", diff.tick);
+            }
+            self.last = Some(diff);
+        }
+        println!("tick {}", self.sim()?.tick());
+        Ok(())
+    }
+
+    fn state(&mut self, arg: Option<&str>) -> Result<(), String> {
+        let world = self.sim()?.world();
+        if arg.is_some() {
+            let pos = parse_pos(arg)?;
+            match world.get(&pos) {
+                Some(kind) => println!("{pos} {}", block_json(kind)),
+                None => println!("{pos} empty"),
+            }
+        } else {
+            let mut blocks: Vec<(&Pos, &BlockKind)> = world.iter().collect();
+            blocks.sort_by_key(|(p, _)| (p.x, p.y, p.z));
+            for (pos, kind) in blocks {
+                println!("{pos} {}", block_json(kind));
+            }
+        }
+        Ok(())
+    }
+
+    fn toggle(&mut self, arg: Option<&str>) -> Result<(), String> {
+        let pos = parse_pos(arg)?;
+        let sim = self.sim()?;
+        let event = match sim.world().get(&pos) {
+            Some(BlockKind::Lever { .. }) => TimedEvent::LeverToggle(pos),
+            Some(BlockKind::Button { .. }) => TimedEvent::ButtonPress(pos),
+            _ => return Err(format!("no lever or button at {pos}")),
+        };
+        sim.push_event(event);
+        println!("takes effect on tick {}", sim.tick() + 1);
+        Ok(())
+    }
+
+    fn diff(&self) {
+        match &self.last {
+            None => println!("no tick run yet"),
+            Some(diff) if diff.changes.is_empty() && diff.removed.is_empty() => {
+                println!("tick {}: no changes", diff.tick)
+            }
+            Some(diff) => {
+                println!("tick {}:", diff.tick);
+                for c in &diff.changes {
+                    println!("  {} {}", c.pos, block_json(&c.kind));
+                }
+                for p in &diff.removed {
+                    println!("  {p} removed");
+                }
+            }
+        }
+    }
+
+    // false once the user asks to leave
+    fn command(&mut self, line: &str) -> Result<bool, String> {
+        let mut words = line.split_whitespace();
+        let Some(cmd) = words.next() else {
+            return Ok(true);
+        };
+        let arg = words.next();
+        match cmd {
+            "load" => self.load(arg.ok_or("expected a file name")?)?,
+            "step" => {
+                let args: Vec<&str> = arg.into_iter().chain(words).collect();
+                let lookahead = args.contains(&"--lookahead");
+                let ticks = match args.iter().find(|a| **a != "--lookahead") {
+                    Some(n) => n.parse().map_err(|_| "expected a number of ticks")?,
+                    None => 1,
+                };
+                self.step(ticks, lookahead)?
+            }
+            "state" => self.state(arg)?,
+            "toggle" => self.toggle(arg)?,
+            "watch" => {
+                let pos = parse_pos(arg)?;
+                if !self.watches.contains(&pos) {
+                    self.watches.push(pos);
+                }
+                println!("watching {}", self.watches.iter().map(Pos::to_string).collect::<Vec<_>>().join(" "));
+            }
+            "diff" => self.diff(),
+            "help" => println!("{REPL_HELP}"),
+            "quit" | "exit" => return Ok(false),
+            _ => return Err(format!("unknown command `{cmd}`; try `help`")),
+        }
+        Ok(true)
+    }
+}
+
+fn repl(input: Option<String>, format: Format, compact: bool) -> Result<(), String> {
+    let mut editor = rustyline::DefaultEditor::new().map_err(|e| e.to_string())?;
+    let mut repl = Repl { format, compact, ..Repl::default() };
+    if let Some(path) = input {
+        repl.load(&path)?;
+    }
+    loop {
+        let line = match editor.readline("redstone> ") {
+            Ok(line) => line,
+            // Ctrl-C / Ctrl-D
+            Err(ReadlineError::Interrupted | ReadlineError::Eof) => return Ok(()),
+            Err(e) => return Err(e.to_string()),
+        };
+        let _ = editor.add_history_entry(line.as_str());
+        match repl.command(&line) {
+            Ok(true) => {}
+            Ok(false) => return Ok(()),
+            Err(e) => println!("error: {e}"),
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<ExitCode, String> {
+    let (format, compact) = (cli.format, cli.compact);
+    match cli.command {
+        Command::Simulate { input, ticks, output } => {
+            let req = load_request(&input, ticks, format, compact)?;
+            let ticks = req.ticks;
+            let resp = simulate(req).map_err(|e| e.to_string())?;
+            let text = format.render(&resp)?;
+            if let Some(path) = &output {
+                write_output(path, &text)?;
+            }
+            if !cli.json {
+                print!("{}", summary(ticks, &resp));
+            } else if output.is_none() {
+                println!("{text}");
+            }
+        }
+        Command::Validate { input } => {
+            let world: World = format.parse(&read_input(&input)?, "world")?;
+            if let Err(e) = world.validate() {
+                if cli.json {
+                    println!("{}", format.render(&serde_json::json!({ "error": e.to_string() }))?);
+                } else {
+                    println!("invalid: {e}");
+                }
+                return Ok(ExitCode::FAILURE);
+            }
+            let warnings = world.validate_wiring();
+            if cli.json {
+                println!("{}", format.render(&warnings)?);
+            } else if warnings.is_empty() {
+                println!("ok: {} blocks, no warnings", world.total_blocks());
+            } else {
+                println!("{} blocks, {} warnings:", world.total_blocks(), warnings.len());
+                for w in &warnings {
+                    println!("  {w}");
+                }
+            }
+        }
+        Command::Connections { block } => {
+            let block: PlacedBlock = format.parse(&read_input(&block)?, "block")?;
+            let inputs = block.kind.input_positions(block.pos);
+            let outputs = block.kind.output_positions(block.pos);
+            if cli.json {
+                println!("{}", format.render(&serde_json::json!({ "inputs": inputs, "outputs": outputs }))?);
+            } else {
+                let list = |ps: &[Pos]| ps.iter().map(Pos::to_string).collect::<Vec<_>>().join(" ");
+                println!("{} at {}", block.kind.type_name(), block.pos);
+                println!("  inputs:  {}", list(&inputs));
+                println!("  outputs: {}", list(&outputs));
+            }
+        }
+        Command::TimingDiagram { input, ticks, positions } => {
+            let positions = parse_positions(&positions)?;
+            let resp = simulate(load_request(&input, Some(ticks), format, compact)?).map_err(|e| e.to_string())?;
+            let diagram = timing_diagram(&resp.diffs, &positions, ticks);
+            if cli.json {
+                let lines: Vec<&str> = diagram.lines().collect();
+                println!("{}", format.render(&serde_json::json!({ "ticks": ticks, "lines": lines }))?);
+            } else {
+                print!("{diagram}");
+            }
+        }
+        Command::Graph { input, output } => {
+            let dot = world_to_dot(&load_world(&input, format)?.into_map());
+            match &output {
+                Some(path) => write_output(path, &dot)?,
+                None => print!("{dot}"),
+            }
+        }
+        Command::Repl { input } => repl(input, format, compact)?,
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
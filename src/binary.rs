@@ -0,0 +1,111 @@
+// src/binary.rs
+
+// Compact binary encoding (bincode) of worlds, requests and responses.
+// Only built with the `bincode` feature; JSON stays the default format.
+
+use crate::{SimRequest, SimResponse, World};
+use bincode::{Decode, Encode};
+
+/// Error from decoding a binary-encoded value.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BinError(String);
+
+impl std::fmt::Display for BinError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid binary data: {}", self.0)
+    }
+}
+
+impl std::error::Error for BinError {}
+
+fn encode<T: Encode>(value: &T) -> Vec<u8> {
+    // encoding into a Vec cannot run out of space, and every type here
+    // encodes without failing
+    bincode::encode_to_vec(value, bincode::config::standard()).expect("bincode encoding failed")
+}
+
+fn decode<T: Decode<()>>(bytes: &[u8]) -> Result<T, BinError> {
+    let (value, read) = bincode::decode_from_slice(bytes, bincode::config::standard())
+        .map_err(|e| BinError(e.to_string()))?;
+    if read != bytes.len() {
+        return Err(BinError(format!("{} trailing bytes", bytes.len() - read)));
+    }
+    Ok(value)
+}
+
+pub fn serialize_world_bin(world: &World) -> Vec<u8> {
+    encode(world)
+}
+
+pub fn deserialize_world_bin(bytes: &[u8]) -> Result<World, BinError> {
+    decode(bytes)
+}
+
+pub fn serialize_request_bin(request: &SimRequest) -> Vec<u8> {
+    encode(request)
+}
+
+pub fn deserialize_request_bin(bytes: &[u8]) -> Result<SimRequest, BinError> {
+    decode(bytes)
+}
+
+pub fn serialize_response_bin(response: &SimResponse) -> Vec<u8> {
+    encode(response)
+}
+
+pub fn deserialize_response_bin(bytes: &[u8]) -> Result<SimResponse, BinError> {
+    decode(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{simulate, Direction, WorldBuilder};
+
+    fn request(world: World) -> SimRequest {
+        SimRequest { ticks: 20, world, ..Default::default() }
+    }
+
+    /// A lever driving a 999-block dust line.
+    fn big_world() -> World {
+        (1..1000)
+            .fold(WorldBuilder::new().lever((0, 0, 0), Direction::East, true), |b, x| b.dust((x, 0, 0)))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn round_trips() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, true)
+            .dust((1, 0, 0))
+            .lamp((2, 0, 0))
+            .build()
+            .unwrap();
+        assert_eq!(deserialize_world_bin(&serialize_world_bin(&world)).unwrap(), world);
+
+        let req = request(world);
+        let decoded = deserialize_request_bin(&serialize_request_bin(&req)).unwrap();
+        assert_eq!(decoded.world, req.world);
+        assert_eq!(decoded.ticks, req.ticks);
+
+        let res = simulate(req).unwrap();
+        let decoded = deserialize_response_bin(&serialize_response_bin(&res)).unwrap();
+        assert_eq!(format!("{:?}", decoded.diffs), format!("{:?}", res.diffs));
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert!(deserialize_world_bin(&[0xff, 0xff, 0xff]).is_err());
+        let mut bytes = serialize_world_bin(&big_world());
+        bytes.push(0);
+        assert!(deserialize_world_bin(&bytes).is_err());
+    }
+
+
+    #[test]
+    fn smaller_than_json() {
+        let world = big_world();
+        assert!(serialize_world_bin(&world).len() < serde_json::to_string(&world).unwrap().len());
+    }
+}
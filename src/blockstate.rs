@@ -0,0 +1,585 @@
+// src/blockstate.rs
+
+// Mapping between BlockKind and Minecraft block states: a block id such as
+// `minecraft:repeater` plus its string properties. Used by the importers
+// for real Minecraft structure files.
+
+use crate::{
+    BellAttachment, BlockKind, ComparatorMode, Direction, DoorHalf, HingePosition, Instrument, PlateKind, RailShape,
+    TrapdoorHalf, OxidationLevel, BUTTON_PRESS_TICKS, CRAFTER_COOLDOWN, FURNACE_MAX_FUEL, LIGHTNING_ROD_TICKS,
+    SCULK_ACTIVE_TICKS, TARGET_HIT_TICKS,
+};
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::format;
+
+pub type Properties = BTreeMap<String, String>;
+
+const INSTRUMENTS: [(Instrument, &str); 16] = [
+    (Instrument::Harp, "harp"),
+    (Instrument::Basedrum, "basedrum"),
+    (Instrument::Snare, "snare"),
+    (Instrument::Hat, "hat"),
+    (Instrument::Bass, "bass"),
+    (Instrument::Flute, "flute"),
+    (Instrument::Bell, "bell"),
+    (Instrument::Guitar, "guitar"),
+    (Instrument::Chime, "chime"),
+    (Instrument::Xylophone, "xylophone"),
+    (Instrument::IronXylophone, "iron_xylophone"),
+    (Instrument::CowBell, "cow_bell"),
+    (Instrument::Didgeridoo, "didgeridoo"),
+    (Instrument::Bit, "bit"),
+    (Instrument::Banjo, "banjo"),
+    (Instrument::Pling, "pling"),
+];
+
+const RAIL_SHAPES: [(RailShape, &str); 10] = [
+    (RailShape::NorthSouth, "north_south"),
+    (RailShape::EastWest, "east_west"),
+    (RailShape::AscendingEast, "ascending_east"),
+    (RailShape::AscendingWest, "ascending_west"),
+    (RailShape::AscendingNorth, "ascending_north"),
+    (RailShape::AscendingSouth, "ascending_south"),
+    (RailShape::SouthEast, "south_east"),
+    (RailShape::SouthWest, "south_west"),
+    (RailShape::NorthWest, "north_west"),
+    (RailShape::NorthEast, "north_east"),
+];
+
+const BELL_ATTACHMENTS: [(BellAttachment, &str); 4] = [
+    (BellAttachment::Floor, "floor"),
+    (BellAttachment::Ceiling, "ceiling"),
+    (BellAttachment::SingleWall, "single_wall"),
+    (BellAttachment::DoubleWall, "double_wall"),
+];
+
+/// Full, opaque building blocks that conduct power like `OpaqueSolid`.
+fn is_opaque_solid(name: &str) -> bool {
+    const NAMES: [&str; 16] = [
+        "stone",
+        "cobblestone",
+        "smooth_stone",
+        "stone_bricks",
+        "bricks",
+        "dirt",
+        "grass_block",
+        "sandstone",
+        "deepslate",
+        "cobbled_deepslate",
+        "netherrack",
+        "end_stone",
+        "obsidian",
+        "quartz_block",
+        "iron_block",
+        "gold_block",
+    ];
+    const SUFFIXES: [&str; 4] = ["_planks", "_wool", "_concrete", "_terracotta"];
+    NAMES.contains(&name) || (SUFFIXES.iter().any(|s| name.ends_with(s)) && !name.ends_with("glazed_terracotta"))
+}
+
+/// Split a block state string such as
+/// `minecraft:repeater[delay=2,facing=north]` into its id and properties.
+pub fn parse_block_state(state: &str) -> (String, Properties) {
+    let Some((name, rest)) = state.split_once('[') else {
+        return (state.to_string(), Properties::new());
+    };
+    let props = rest
+        .trim_end_matches(']')
+        .split(',')
+        .filter_map(|kv| kv.split_once('='))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect();
+    (name.to_string(), props)
+}
+
+/// Inverse of `parse_block_state`; properties come out sorted by key.
+pub fn format_block_state(name: &str, props: &Properties) -> String {
+    if props.is_empty() {
+        return name.to_string();
+    }
+    let props: Vec<String> = props.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+    format!("{}[{}]", name, props.join(","))
+}
+
+/// The `BlockKind` for a Minecraft block state, or `None` for blocks the
+/// simulator does not model (air, glass, decoration, ...). `name` may carry
+/// the `minecraft:` namespace or not.
+pub fn from_block_state(name: &str, props: &Properties) -> Option<BlockKind> {
+    let name = name.strip_prefix("minecraft:").unwrap_or(name);
+    let get = |key: &str| props.get(key).map(String::as_str);
+    let flag = |key: &str| get(key) == Some("true");
+    let number = |key: &str| get(key).and_then(|v| v.parse::<u8>().ok()).unwrap_or(0);
+    let dir = |key: &str| get(key).and_then(|v| v.parse::<Direction>().ok());
+    // levers and buttons point at the block they are attached to
+    let attached = || match get("face") {
+        Some("floor") => Some(Direction::Down),
+        Some("ceiling") => Some(Direction::Up),
+        _ => dir("facing").map(|d| d.opposite()),
+    };
+    let torch = |lit, facing| BlockKind::Torch { lit, facing, burnout_counter: 0, burnout_cooldown: 0, burned_out: false };
+
+    let kind = match name {
+        "lever" => BlockKind::Lever { on: flag("powered"), facing: attached()? },
+        n if n.ends_with("_button") => BlockKind::Button {
+            ticks_remaining: if flag("powered") { BUTTON_PRESS_TICKS } else { 0 },
+            facing: attached()?,
+        },
+        "redstone_wire" => BlockKind::Dust {
+            power: number("power"),
+            connections: ["north", "east", "south", "west"].map(|side| get(side).is_some_and(|v| v != "none")),
+        },
+        "redstone_lamp" => BlockKind::Lamp { on: flag("lit") },
+        // repeaters and comparators name the side their input comes from
+        "repeater" => BlockKind::Repeater {
+            delay: number("delay").max(1),
+            ticks_remaining: 0,
+            powered: flag("powered"),
+            facing: dir("facing")?.opposite(),
+            locked: flag("locked"),
+        },
+        "comparator" => BlockKind::Comparator {
+            output: 0,
+            facing: dir("facing")?.opposite(),
+            mode: if get("mode") == Some("subtract") { ComparatorMode::Subtract } else { ComparatorMode::Compare },
+        },
+        "redstone_torch" => torch(flag("lit"), Direction::Down),
+        "redstone_wall_torch" => torch(flag("lit"), dir("facing")?.opposite()),
+        "piston" | "sticky_piston" => BlockKind::Piston {
+            extended: flag("extended"),
+            facing: dir("facing")?,
+            sticky: name == "sticky_piston",
+        },
+        "piston_head" => BlockKind::PistonArm { facing: dir("facing")? },
+        "hopper" => BlockKind::Hopper { enabled: get("enabled") != Some("false"), facing: dir("facing")?, items: 0 },
+        "observer" => BlockKind::Observer { facing: dir("facing")?, powered: flag("powered") },
+        "redstone_block" => BlockKind::RedstoneBlock,
+        "target" => BlockKind::Target {
+            power: number("power"),
+            ticks_remaining: if number("power") > 0 { TARGET_HIT_TICKS } else { 0 },
+        },
+        "sculk_sensor" | "calibrated_sculk_sensor" => {
+            let power = number("power");
+            let ticks_remaining = if get("sculk_sensor_phase") == Some("active") { SCULK_ACTIVE_TICKS } else { 0 };
+            let waterlogged = flag("waterlogged");
+            if name == "sculk_sensor" {
+                BlockKind::SculkSensor { power, ticks_remaining, waterlogged }
+            } else {
+                BlockKind::CalibratedSculkSensor { power, ticks_remaining, waterlogged, facing: dir("facing")? }
+            }
+        }
+        n if n.ends_with("_trapdoor") => BlockKind::Trapdoor {
+            open: flag("open"),
+            facing: dir("facing")?,
+            half: if get("half") == Some("top") { TrapdoorHalf::Top } else { TrapdoorHalf::Bottom },
+        },
+        n if n.ends_with("_door") => BlockKind::Door {
+            open: flag("open"),
+            hinge: if get("hinge") == Some("right") { HingePosition::Right } else { HingePosition::Left },
+            facing: dir("facing")?,
+            half: if get("half") == Some("upper") { DoorHalf::Upper } else { DoorHalf::Lower },
+        },
+        "note_block" => BlockKind::NoteBlock {
+            instrument: INSTRUMENTS
+                .iter()
+                .find(|(_, n)| get("instrument") == Some(*n))
+                .map_or(Instrument::Harp, |(i, _)| *i),
+            pitch: number("note"),
+            triggered: false,
+            powered: flag("powered"),
+        },
+        "powered_rail" | "detector_rail" | "activator_rail" => {
+            let shape = RAIL_SHAPES.iter().find(|(_, n)| get("shape") == Some(*n))?.0;
+            let powered = flag("powered");
+            match name {
+                "powered_rail" => BlockKind::PoweredRail { powered, shape },
+                "detector_rail" => BlockKind::DetectorRail { powered, shape },
+                _ => BlockKind::ActivatorRail { powered, shape },
+            }
+        }
+        n if n.ends_with("_pressure_plate") => {
+            let kind = match n {
+                "light_weighted_pressure_plate" => PlateKind::LightWeighted,
+                "heavy_weighted_pressure_plate" => PlateKind::HeavyWeighted,
+                "stone_pressure_plate" | "polished_blackstone_pressure_plate" => PlateKind::Stone,
+                _ => PlateKind::Wood,
+            };
+            let power = match kind {
+                PlateKind::LightWeighted | PlateKind::HeavyWeighted => number("power"),
+                _ if flag("powered") => 15,
+                _ => 0,
+            };
+            BlockKind::PressurePlate { powered: power > 0, kind, power }
+        }
+        "dropper" => BlockKind::Dropper { facing: dir("facing")?, powered: flag("triggered"), cooldown: 0 },
+        "dispenser" => BlockKind::Dispenser { facing: dir("facing")?, powered: flag("triggered"), cooldown: 0 },
+        "chest" => {
+            // either half of a double chest reads the whole chest
+            let double = get("type").is_some_and(|t| t != "single");
+            BlockKind::Chest { items: 0, max_items: if double { 54 } else { 27 } }
+        }
+        "barrel" => BlockKind::Barrel { items: 0, max_items: 27 },
+        // a lit furnace is taken to have just burnt into a fresh piece of coal
+        "furnace" if flag("lit") => BlockKind::Furnace { fuel_ticks: FURNACE_MAX_FUEL, cooking: true },
+        "furnace" => BlockKind::Furnace { fuel_ticks: 0, cooking: false },
+        "tripwire_hook" => {
+            BlockKind::TripwireHook { attached: flag("attached"), powered: flag("powered"), facing: dir("facing")? }
+        }
+        "tripwire" => BlockKind::Tripwire { powered: flag("powered") },
+        "daylight_detector" => BlockKind::DaylightSensor { inverted: flag("inverted"), power: number("power") },
+        n if n.ends_with("copper_bulb") => BlockKind::CopperBulb {
+            lit: flag("lit"),
+            powered: flag("powered"),
+            // waxing only stops weathering, which is not simulated
+            oxidation: match n.trim_start_matches("waxed_") {
+                "exposed_copper_bulb" => OxidationLevel::Exposed,
+                "weathered_copper_bulb" => OxidationLevel::Weathered,
+                "oxidized_copper_bulb" => OxidationLevel::Oxidized,
+                _ => OxidationLevel::Unoxidized,
+            },
+        },
+        "crafter" => BlockKind::Crafter {
+            powered: flag("triggered"),
+            cooldown: if flag("crafting") { CRAFTER_COOLDOWN } else { 0 },
+            craft_count: 0,
+        },
+        "bell" => BlockKind::Bell {
+            attachment: BELL_ATTACHMENTS.iter().find(|(_, n)| get("attachment") == Some(*n))?.0,
+            facing: dir("facing")?,
+            ringing: false,
+            powered: flag("powered"),
+            ticks_remaining: 0,
+        },
+        n if n.ends_with("_fence_gate") => {
+            BlockKind::FenceGate { open: flag("open"), facing: dir("facing")?, in_wall: flag("in_wall") }
+        }
+        "lightning_rod" => BlockKind::LightningRod {
+            powered: flag("powered"),
+            ticks_remaining: if flag("powered") { LIGHTNING_ROD_TICKS } else { 0 },
+        },
+        n if is_opaque_solid(n) => BlockKind::OpaqueSolid { strong_power: 0 },
+        _ => return None,
+    };
+    Some(kind)
+}
+
+/// The Minecraft block state (namespaced id and properties) for `kind`.
+/// Blocks with no single in-game counterpart use a representative one:
+/// `OpaqueSolid` becomes stone, doors and trapdoors are oak.
+pub fn to_block_state(kind: &BlockKind) -> (String, Properties) {
+    // inverse of `attached` in `from_block_state`
+    fn attach(facing: Direction, set: &mut dyn FnMut(&str, String)) {
+        let (face, facing) = match facing {
+            Direction::Down => ("floor", Direction::North),
+            Direction::Up => ("ceiling", Direction::North),
+            d => ("wall", d.opposite()),
+        };
+        set("face", face.into());
+        set("facing", facing.to_string());
+    }
+
+    let mut props = Properties::new();
+    let mut set = |key: &str, value: String| {
+        props.insert(key.to_string(), value);
+    };
+
+    let name = match kind {
+        BlockKind::Lever { on, facing } => {
+            attach(*facing, &mut set);
+            set("powered", on.to_string());
+            "lever"
+        }
+        BlockKind::Button { ticks_remaining, facing } => {
+            attach(*facing, &mut set);
+            set("powered", (*ticks_remaining > 0).to_string());
+            "stone_button"
+        }
+        BlockKind::Dust { power, connections } => {
+            set("power", power.to_string());
+            for (side, on) in ["north", "east", "south", "west"].into_iter().zip(connections) {
+                set(side, if *on { "side" } else { "none" }.into());
+            }
+            "redstone_wire"
+        }
+        BlockKind::Lamp { on } => {
+            set("lit", on.to_string());
+            "redstone_lamp"
+        }
+        BlockKind::Repeater { delay, powered, facing, locked, .. } => {
+            set("delay", delay.to_string());
+            set("facing", facing.opposite().to_string());
+            set("locked", locked.to_string());
+            set("powered", powered.to_string());
+            "repeater"
+        }
+        BlockKind::Comparator { output, facing, mode } => {
+            set("facing", facing.opposite().to_string());
+            set("mode", if *mode == ComparatorMode::Subtract { "subtract" } else { "compare" }.into());
+            set("powered", (*output > 0).to_string());
+            "comparator"
+        }
+        BlockKind::Torch { lit, facing, .. } => {
+            set("lit", lit.to_string());
+            if facing.is_horizontal() {
+                set("facing", facing.opposite().to_string());
+                "redstone_wall_torch"
+            } else {
+                "redstone_torch"
+            }
+        }
+        BlockKind::Piston { extended, facing, sticky } => {
+            set("extended", extended.to_string());
+            set("facing", facing.to_string());
+            if *sticky { "sticky_piston" } else { "piston" }
+        }
+        BlockKind::PistonArm { facing } => {
+            set("facing", facing.to_string());
+            set("short", "false".into());
+            set("type", "normal".into());
+            "piston_head"
+        }
+        BlockKind::Hopper { enabled, facing, .. } => {
+            set("enabled", enabled.to_string());
+            set("facing", facing.to_string());
+            "hopper"
+        }
+        BlockKind::Observer { facing, powered } => {
+            set("facing", facing.to_string());
+            set("powered", powered.to_string());
+            "observer"
+        }
+        BlockKind::OpaqueSolid { .. } => "stone",
+        BlockKind::RedstoneBlock => "redstone_block",
+        BlockKind::Target { power, .. } => {
+            set("power", power.to_string());
+            "target"
+        }
+        BlockKind::SculkSensor { power, ticks_remaining, waterlogged }
+        | BlockKind::CalibratedSculkSensor { power, ticks_remaining, waterlogged, .. } => {
+            set("power", power.to_string());
+            set("sculk_sensor_phase", if *ticks_remaining > 0 { "active" } else { "inactive" }.into());
+            set("waterlogged", waterlogged.to_string());
+            if let BlockKind::CalibratedSculkSensor { facing, .. } = kind {
+                set("facing", facing.to_string());
+                "calibrated_sculk_sensor"
+            } else {
+                "sculk_sensor"
+            }
+        }
+        BlockKind::Door { open, hinge, facing, half } => {
+            set("facing", facing.to_string());
+            set("half", if *half == DoorHalf::Upper { "upper" } else { "lower" }.into());
+            set("hinge", if *hinge == HingePosition::Right { "right" } else { "left" }.into());
+            set("open", open.to_string());
+            set("powered", "false".into());
+            "oak_door"
+        }
+        BlockKind::Trapdoor { open, facing, half } => {
+            set("facing", facing.to_string());
+            set("half", if *half == TrapdoorHalf::Top { "top" } else { "bottom" }.into());
+            set("open", open.to_string());
+            set("powered", "false".into());
+            set("waterlogged", "false".into());
+            "oak_trapdoor"
+        }
+        BlockKind::NoteBlock { instrument, pitch, powered, .. } => {
+            let name = INSTRUMENTS.iter().find(|(i, _)| i == instrument).map_or("harp", |(_, n)| *n);
+            set("instrument", name.into());
+            set("note", pitch.to_string());
+            set("powered", powered.to_string());
+            "note_block"
+        }
+        BlockKind::PoweredRail { powered, shape }
+        | BlockKind::DetectorRail { powered, shape }
+        | BlockKind::ActivatorRail { powered, shape } => {
+            let shape_name = RAIL_SHAPES.iter().find(|(s, _)| s == shape).map_or("north_south", |(_, n)| *n);
+            set("powered", powered.to_string());
+            set("shape", shape_name.into());
+            match kind {
+                BlockKind::PoweredRail { .. } => "powered_rail",
+                BlockKind::DetectorRail { .. } => "detector_rail",
+                _ => "activator_rail",
+            }
+        }
+        BlockKind::PressurePlate { powered, kind: plate, power } => match plate {
+            PlateKind::LightWeighted | PlateKind::HeavyWeighted => {
+                set("power", power.to_string());
+                if *plate == PlateKind::LightWeighted {
+                    "light_weighted_pressure_plate"
+                } else {
+                    "heavy_weighted_pressure_plate"
+                }
+            }
+            PlateKind::Stone | PlateKind::Wood => {
+                set("powered", powered.to_string());
+                if *plate == PlateKind::Stone { "stone_pressure_plate" } else { "oak_pressure_plate" }
+            }
+        },
+        BlockKind::Dropper { facing, powered, .. } | BlockKind::Dispenser { facing, powered, .. } => {
+            set("facing", facing.to_string());
+            set("triggered", powered.to_string());
+            if matches!(kind, BlockKind::Dropper { .. }) { "dropper" } else { "dispenser" }
+        }
+        BlockKind::Chest { max_items, .. } => {
+            set("facing", "north".into());
+            set("type", if *max_items > 27 { "left" } else { "single" }.into());
+            set("waterlogged", "false".into());
+            "chest"
+        }
+        BlockKind::Barrel { .. } => {
+            set("facing", "up".into());
+            set("open", "false".into());
+            "barrel"
+        }
+        BlockKind::Furnace { cooking, .. } => {
+            set("facing", "north".into());
+            set("lit", cooking.to_string());
+            "furnace"
+        }
+        BlockKind::TripwireHook { attached, powered, facing } => {
+            set("attached", attached.to_string());
+            set("facing", facing.to_string());
+            set("powered", powered.to_string());
+            "tripwire_hook"
+        }
+        BlockKind::Tripwire { powered } => {
+            set("attached", "false".into());
+            set("disarmed", "false".into());
+            set("powered", powered.to_string());
+            "tripwire"
+        }
+        BlockKind::DaylightSensor { inverted, power } => {
+            set("inverted", inverted.to_string());
+            set("power", power.to_string());
+            "daylight_detector"
+        }
+        BlockKind::CopperBulb { lit, powered, oxidation } => {
+            set("lit", lit.to_string());
+            set("powered", powered.to_string());
+            match oxidation {
+                OxidationLevel::Unoxidized => "copper_bulb",
+                OxidationLevel::Exposed => "exposed_copper_bulb",
+                OxidationLevel::Weathered => "weathered_copper_bulb",
+                OxidationLevel::Oxidized => "oxidized_copper_bulb",
+            }
+        }
+        BlockKind::Crafter { powered, cooldown, .. } => {
+            set("orientation", "north_up".into());
+            set("triggered", powered.to_string());
+            set("crafting", (*cooldown > 0).to_string());
+            "crafter"
+        }
+        BlockKind::Bell { attachment, facing, powered, .. } => {
+            let attachment_name = BELL_ATTACHMENTS.iter().find(|(a, _)| a == attachment).map_or("floor", |(_, n)| *n);
+            set("attachment", attachment_name.into());
+            set("facing", facing.to_string());
+            set("powered", powered.to_string());
+            "bell"
+        }
+        BlockKind::FenceGate { open, facing, in_wall } => {
+            set("facing", facing.to_string());
+            set("in_wall", in_wall.to_string());
+            set("open", open.to_string());
+            set("powered", open.to_string());
+            "oak_fence_gate"
+        }
+        BlockKind::LightningRod { powered, .. } => {
+            set("facing", "up".into());
+            set("powered", powered.to_string());
+            set("waterlogged", "false".into());
+            "lightning_rod"
+        }
+    };
+    (format!("minecraft:{}", name), props)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WorldBuilder;
+
+    #[test]
+    fn round_trips_every_builder_block() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, true)
+            .lever((1, 0, 0), Direction::Down, false)
+            .button((2, 0, 0), Direction::North)
+            .dust((3, 0, 0))
+            .lamp((4, 0, 0))
+            .repeater((5, 0, 0), Direction::West, 3)
+            .comparator((6, 0, 0), Direction::South, ComparatorMode::Subtract)
+            .torch((7, 0, 0), Direction::Down)
+            .torch((8, 0, 0), Direction::East)
+            .piston((9, 0, 0), Direction::Up, true)
+            .hopper((10, 0, 0), Direction::Down, 0)
+            .observer((11, 0, 0), Direction::North)
+            .solid((12, 0, 0))
+            .redstone_block((13, 0, 0))
+            .target((14, 0, 0))
+            .sculk_sensor((15, 0, 0))
+            .calibrated_sculk_sensor((16, 0, 0), Direction::West)
+            .door((17, 0, 0), Direction::South, HingePosition::Right)
+            .trapdoor((18, 0, 0), Direction::East, TrapdoorHalf::Top)
+            .note_block((19, 0, 0), Instrument::IronXylophone, 12)
+            .powered_rail((20, 0, 0), RailShape::AscendingNorth)
+            .detector_rail((21, 0, 0), RailShape::SouthEast)
+            .activator_rail((22, 0, 0), RailShape::EastWest)
+            .pressure_plate((23, 0, 0), PlateKind::HeavyWeighted)
+            .pressure_plate((24, 0, 0), PlateKind::Wood)
+            .dropper((25, 0, 0), Direction::Up)
+            .dispenser((26, 0, 0), Direction::South)
+            .chest((27, 0, 0), 0)
+            .block((27, 1, 0), BlockKind::Chest { items: 0, max_items: 54 })
+            .barrel((27, 2, 0), 0)
+            .furnace((27, 3, 0), 0)
+            .furnace((27, 4, 0), FURNACE_MAX_FUEL)
+            .tripwire_hook((28, 0, 0), Direction::West)
+            .tripwire((29, 0, 0))
+            .daylight_sensor((30, 0, 0), true)
+            .lightning_rod((31, 0, 0))
+            .copper_bulb((32, 0, 0), OxidationLevel::Unoxidized)
+            .copper_bulb((33, 0, 0), OxidationLevel::Exposed)
+            .copper_bulb((34, 0, 0), OxidationLevel::Weathered)
+            .copper_bulb((35, 0, 0), OxidationLevel::Oxidized)
+            .crafter((36, 0, 0))
+            .bell((37, 0, 0), BellAttachment::Floor, Direction::North)
+            .bell((38, 0, 0), BellAttachment::DoubleWall, Direction::East)
+            .fence_gate((39, 0, 0), Direction::South)
+            .build()
+            .unwrap();
+        for b in &world.blocks {
+            let (name, props) = to_block_state(&b.kind);
+            assert_eq!(from_block_state(&name, &props).as_ref(), Some(&b.kind), "{}", name);
+        }
+    }
+
+    #[test]
+    fn reads_minecraft_states() {
+        let props = |pairs: &[(&str, &str)]| -> Properties {
+            pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+        };
+        // a repeater's `facing` is the side its input comes from
+        let repeater = from_block_state("minecraft:repeater", &props(&[("facing", "west"), ("delay", "2")]));
+        assert!(matches!(repeater, Some(BlockKind::Repeater { facing: Direction::East, delay: 2, .. })));
+        let torch = from_block_state("redstone_wall_torch", &props(&[("facing", "north"), ("lit", "true")]));
+        assert!(matches!(torch, Some(BlockKind::Torch { lit: true, facing: Direction::South, burned_out: false, .. })));
+        assert_eq!(from_block_state("oak_planks", &Properties::new()), Some(BlockKind::OpaqueSolid { strong_power: 0 }));
+        assert_eq!(from_block_state("minecraft:glass", &Properties::new()), None);
+        assert_eq!(from_block_state("minecraft:repeater", &Properties::new()), None);
+    }
+
+    #[test]
+    fn parses_state_strings() {
+        let s = "minecraft:repeater[facing=north,delay=2,locked=false,powered=true]";
+        let (name, props) = parse_block_state(s);
+        assert_eq!(name, "minecraft:repeater");
+        assert_eq!(props.get("delay").map(String::as_str), Some("2"));
+        assert_eq!(
+            format_block_state(&name, &props),
+            "minecraft:repeater[delay=2,facing=north,locked=false,powered=true]"
+        );
+        assert_eq!(parse_block_state("minecraft:stone"), ("minecraft:stone".to_string(), Properties::new()));
+        assert_eq!(format_block_state("minecraft:stone", &Properties::new()), "minecraft:stone");
+    }
+}
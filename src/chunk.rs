@@ -0,0 +1,198 @@
+// src/chunk.rs
+
+// Chunked block storage: blocks live in dense 16×16×16 arrays so that a
+// block and its neighbours are usually in the same small allocation.
+
+use crate::{BlockKind, BlockLookup, PlacedBlock, Pos};
+use serde::{Deserialize, Serialize};
+use crate::grid::{BuildPosHasher, HashMap};
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+
+const CHUNK_SIZE: i32 = 16;
+const CHUNK_VOLUME: usize = (CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE) as usize;
+
+/// One 16×16×16 region of a `ChunkWorld`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Chunk {
+    blocks: Box<[Option<BlockKind>]>, // CHUNK_VOLUME slots, x fastest, then z, then y
+    len: usize,
+}
+
+impl Chunk {
+    fn new() -> Self {
+        Chunk { blocks: vec![None; CHUNK_VOLUME].into_boxed_slice(), len: 0 }
+    }
+
+    /// Block at chunk-local coordinates (each 0‑15).
+    pub fn get(&self, x: usize, y: usize, z: usize) -> Option<&BlockKind> {
+        let size = CHUNK_SIZE as usize;
+        if x >= size || y >= size || z >= size {
+            return None;
+        }
+        self.blocks[(y * size + z) * size + x].as_ref()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// Chunk coordinates and the slot index within the chunk for `pos`.
+fn split(pos: Pos) -> ((i32, i32, i32), usize) {
+    let chunk = (pos.x.div_euclid(CHUNK_SIZE), pos.y.div_euclid(CHUNK_SIZE), pos.z.div_euclid(CHUNK_SIZE));
+    let (x, y, z) = (pos.x.rem_euclid(CHUNK_SIZE), pos.y.rem_euclid(CHUNK_SIZE), pos.z.rem_euclid(CHUNK_SIZE));
+    (chunk, ((y * CHUNK_SIZE + z) * CHUNK_SIZE + x) as usize)
+}
+
+fn join(chunk: (i32, i32, i32), index: usize) -> Pos {
+    let i = index as i32;
+    Pos {
+        x: chunk.0 * CHUNK_SIZE + i % CHUNK_SIZE,
+        y: chunk.1 * CHUNK_SIZE + i / (CHUNK_SIZE * CHUNK_SIZE),
+        z: chunk.2 * CHUNK_SIZE + i / CHUNK_SIZE % CHUNK_SIZE,
+    }
+}
+
+/// A world stored as chunks, with the same lookups as a
+/// `HashMap<Pos, BlockKind>`. Serializes as a list of placed blocks, like
+/// `World`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "Vec<PlacedBlock>", into = "Vec<PlacedBlock>")]
+pub struct ChunkWorld {
+    chunks: HashMap<(i32, i32, i32), Chunk, BuildPosHasher>,
+    len: usize,
+}
+
+impl ChunkWorld {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, pos: &Pos) -> Option<&BlockKind> {
+        let (chunk, index) = split(*pos);
+        self.chunks.get(&chunk)?.blocks[index].as_ref()
+    }
+
+    pub fn get_mut(&mut self, pos: &Pos) -> Option<&mut BlockKind> {
+        let (chunk, index) = split(*pos);
+        self.chunks.get_mut(&chunk)?.blocks[index].as_mut()
+    }
+
+    pub fn contains_key(&self, pos: &Pos) -> bool {
+        self.get(pos).is_some()
+    }
+
+    /// Place `kind` at `pos`, returning the block it replaced.
+    pub fn insert(&mut self, pos: Pos, kind: BlockKind) -> Option<BlockKind> {
+        let (chunk, index) = split(pos);
+        let chunk = self.chunks.entry(chunk).or_insert_with(Chunk::new);
+        let old = chunk.blocks[index].replace(kind);
+        if old.is_none() {
+            chunk.len += 1;
+            self.len += 1;
+        }
+        old
+    }
+
+    /// Remove the block at `pos`; chunks that become empty are dropped.
+    pub fn remove(&mut self, pos: &Pos) -> Option<BlockKind> {
+        let (key, index) = split(*pos);
+        let chunk = self.chunks.get_mut(&key)?;
+        let old = chunk.blocks[index].take()?;
+        chunk.len -= 1;
+        self.len -= 1;
+        if chunk.is_empty() {
+            self.chunks.remove(&key);
+        }
+        Some(old)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Every block with its position, chunk by chunk.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &BlockKind)> {
+        self.chunks.iter().flat_map(|(key, chunk)| {
+            chunk.blocks.iter().enumerate().filter_map(|(i, b)| b.as_ref().map(|b| (join(*key, i), b)))
+        })
+    }
+
+    /// The chunk containing `pos`, if it holds any blocks.
+    pub fn chunk_at(&self, pos: Pos) -> Option<&Chunk> {
+        self.chunks.get(&split(pos).0)
+    }
+}
+
+impl BlockLookup for ChunkWorld {
+    fn get(&self, pos: &Pos) -> Option<&BlockKind> {
+        ChunkWorld::get(self, pos)
+    }
+}
+
+impl FromIterator<(Pos, BlockKind)> for ChunkWorld {
+    fn from_iter<I: IntoIterator<Item = (Pos, BlockKind)>>(iter: I) -> Self {
+        let mut world = ChunkWorld::new();
+        for (pos, kind) in iter {
+            world.insert(pos, kind);
+        }
+        world
+    }
+}
+
+impl From<Vec<PlacedBlock>> for ChunkWorld {
+    fn from(blocks: Vec<PlacedBlock>) -> Self {
+        blocks.into_iter().map(|b| (b.pos, b.kind)).collect()
+    }
+}
+
+impl From<ChunkWorld> for Vec<PlacedBlock> {
+    fn from(world: ChunkWorld) -> Self {
+        world.iter().map(|(pos, kind)| PlacedBlock { pos, kind: kind.clone() }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stores_blocks_across_chunk_borders() {
+        let mut world = ChunkWorld::new();
+        let lamp = BlockKind::Lamp { on: false };
+        let positions = [(0, 0, 0), (15, 15, 15), (16, 0, 0), (-1, -64, -1), (-17, 5, 40)].map(Pos::from);
+        for p in positions {
+            assert_eq!(world.insert(p, lamp.clone()), None);
+        }
+        assert_eq!(world.len(), 5);
+        assert_eq!(world.insert(positions[0], BlockKind::RedstoneBlock), Some(lamp.clone()));
+        assert_eq!(world.len(), 5);
+
+        for p in &positions[1..] {
+            assert_eq!(world.get(p), Some(&lamp));
+        }
+        assert!(!world.contains_key(&Pos::from((1, 0, 0))));
+        assert_eq!(world.chunk_at(Pos::from((3, 3, 3))).map(Chunk::len), Some(2));
+
+        let mut seen: Vec<Pos> = world.iter().map(|(p, _)| p).collect();
+        seen.sort_by_key(|p| (p.x, p.y, p.z));
+        let mut expected = positions.to_vec();
+        expected.sort_by_key(|p| (p.x, p.y, p.z));
+        assert_eq!(seen, expected);
+
+        assert_eq!(world.remove(&positions[4]), Some(lamp));
+        assert_eq!(world.remove(&positions[4]), None);
+        assert!(world.chunk_at(positions[4]).is_none());
+        assert_eq!(world.len(), 4);
+    }
+}
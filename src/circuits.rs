@@ -0,0 +1,286 @@
+// src/circuits.rs
+
+// Ready-made circuits. Each factory returns a self-contained `World` with
+// the circuit's input (a lever or button) and output (a lamp or copper
+// bulb) included; positions below are offsets from the `pos` passed in,
+// with signals flowing east (+x).
+
+use crate::{BlockKind, ComparatorMode, Direction, OxidationLevel, Pos, World, WorldBuilder};
+use alloc::vec::Vec;
+
+/// Error from a circuit factory whose parameters cannot be built.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CircuitError {
+    InvalidPeriod(u32), // clock period outside CLOCK_PERIODS
+}
+
+impl core::fmt::Display for CircuitError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CircuitError::InvalidPeriod(p) => write!(
+                f,
+                "clock period must be {}‑{} ticks, got {}",
+                CLOCK_PERIODS.start(),
+                CLOCK_PERIODS.end(),
+                p
+            ),
+        }
+    }
+}
+
+impl core::error::Error for CircuitError {}
+
+/// Periods `clock` can build, one tick per repeater in its ring.
+pub const CLOCK_PERIODS: core::ops::RangeInclusive<u32> = 4..=16;
+
+/// Ticks an `rs_latch` input must stay on for the latch to hold.
+pub const RS_LATCH_HOLD: u32 = 6;
+
+/// Longest pulse `monostable` can make. Its timing path is fed by a dust
+/// line that runs out of power past 12 repeaters.
+pub const MAX_MONOSTABLE_DURATION: u8 = 48;
+
+// the layouts never overlap, so building cannot fail
+fn finish(builder: WorldBuilder) -> World {
+    builder.build().expect("circuit layout places two blocks at one position")
+}
+
+// `total` ticks split as evenly as possible over `count` repeaters, longest first
+fn split_delay(total: u32, count: u32) -> Vec<u8> {
+    (0..count).map(|i| (total / count + u32::from(i < total % count)) as u8).collect()
+}
+
+// `total` ticks over as few 1‑4 tick repeaters as possible
+fn repeater_delays(total: u32) -> Vec<u8> {
+    split_delay(total, total.div_ceil(4).max(1))
+}
+
+/// RS latch (two cross-coupled torches).
+///
+/// - inputs: set lever at `pos + (4, 0, 0)`, reset lever at `pos + (-1, 0, 0)`.
+///   Turn a lever on for at least `RS_LATCH_HOLD` ticks, then off again;
+///   holding both on is undefined.
+/// - output: lamp at `pos + (1, 0, -1)`, lit after set and dark after reset.
+///
+/// Starts reset. Each torch's block is driven through a repeater, since
+/// dust alone does not strongly power a block.
+pub fn rs_latch(pos: Pos) -> World {
+    let off = |dx, dy, dz| pos + (dx, dy, dz);
+    let torch = |facing, lit| BlockKind::Torch { lit, facing, burnout_counter: 0, burnout_cooldown: 0, burned_out: false };
+    let repeater = |facing, powered| BlockKind::Repeater { delay: 1, ticks_remaining: 0, powered, facing, locked: false };
+    let dust = |power| BlockKind::Dust { power, connections: Default::default() };
+    finish(
+        WorldBuilder::new()
+            .lever(off(-1, 0, 0), Direction::East, false)
+            .block(off(0, 0, 0), BlockKind::OpaqueSolid { strong_power: 15 })
+            .block(off(1, 0, 0), torch(Direction::West, false))
+            .block(off(2, 0, 0), repeater(Direction::East, false))
+            .block(off(3, 0, 0), BlockKind::OpaqueSolid { strong_power: 0 })
+            .block(off(3, 0, 1), torch(Direction::North, true))
+            .block(off(3, 0, 2), dust(15))
+            .block(off(2, 0, 2), dust(14))
+            .block(off(1, 0, 2), dust(13))
+            .block(off(0, 0, 2), dust(12))
+            .block(off(0, 0, 1), repeater(Direction::North, true))
+            .lever(off(4, 0, 0), Direction::West, false)
+            .lamp(off(1, 0, -1)),
+    )
+}
+
+/// T flip-flop: every press of the input toggles the output.
+///
+/// - input: button at `pos`.
+/// - output: copper bulb at `pos + (2, 0, 0)`, which toggles on each rising
+///   edge of its input (a comparator behind it reads 15 while lit).
+pub fn t_flip_flop(pos: Pos) -> World {
+    finish(
+        WorldBuilder::new()
+            .button(pos, Direction::East)
+            .dust(pos + (1, 0, 0))
+            .copper_bulb(pos + (2, 0, 0), OxidationLevel::Unoxidized),
+    )
+}
+
+/// Repeater clock: a one-tick pulse circling a ring of `period` one-tick
+/// repeaters (a repeater swallows pulses shorter than its delay, so longer
+/// delays would stop the clock). Runs from the first tick; there is no input.
+///
+/// - output: lamp at `pos + (0, -1, 0)`, under a corner of the ring, lit one
+///   tick in every `period`.
+///
+/// The ring is `(period - 1) / 2 + 2` blocks along x and 3 along z. Fails
+/// unless `period` is in `CLOCK_PERIODS`.
+pub fn clock(pos: Pos, period: u32) -> Result<World, CircuitError> {
+    if !CLOCK_PERIODS.contains(&period) {
+        return Err(CircuitError::InvalidPeriod(period));
+    }
+    let k = ((period - 1) / 2) as i32;
+    let repeater = |facing, powered| BlockKind::Repeater { delay: 1, ticks_remaining: 0, powered, facing, locked: false };
+    // the pulse starts out leaving the first repeater
+    let mut builder = WorldBuilder::new().block(pos + (1, 0, 0), repeater(Direction::East, true));
+    for x in 2..=k {
+        builder = builder.block(pos + (x, 0, 0), repeater(Direction::East, false));
+    }
+    builder = builder.block(pos + (k + 1, 0, 1), repeater(Direction::South, false));
+    for x in 1..=k {
+        // an odd period has one repeater too few for the ring; dust fills the gap
+        builder = if x == 1 && period % 2 == 1 {
+            builder.dust(pos + (x, 0, 2))
+        } else {
+            builder.block(pos + (x, 0, 2), repeater(Direction::West, false))
+        };
+    }
+    builder = builder.block(pos + (0, 0, 1), repeater(Direction::North, false));
+    for corner in [(0, 0, 0), (k + 1, 0, 0), (k + 1, 0, 2), (0, 0, 2)] {
+        builder = builder.dust(pos + corner);
+    }
+    Ok(finish(builder.lamp(pos + (0, -1, 0))))
+}
+
+/// Delay line: the output follows the input `ticks` later (rounded up to
+/// one repeater for 0).
+///
+/// - input: lever at `pos`.
+/// - output: lamp at `pos + (n + 1, 0, 0)`, where `n = ticks.div_ceil(4)`
+///   is the number of repeaters (at least 1).
+pub fn delay_line(pos: Pos, ticks: u8) -> World {
+    let mut builder = WorldBuilder::new().lever(pos, Direction::East, false);
+    let delays = repeater_delays(u32::from(ticks).max(1));
+    for (i, delay) in delays.iter().enumerate() {
+        builder = builder.repeater(pos + (i as i32 + 1, 0, 0), Direction::East, *delay);
+    }
+    finish(builder.lamp(pos + (delays.len() as i32 + 1, 0, 0)))
+}
+
+/// Pulse shortener: the output pulses briefly when the input turns on, and
+/// not at all when it turns off. The same circuit as `monostable(pos, 1)`.
+pub fn pulse_shortener(pos: Pos) -> World {
+    monostable(pos, 1)
+}
+
+/// Monostable (pulse limiter): when the input turns on, the output turns on
+/// for `duration` ticks, then off until the input turns off and on again. A
+/// shorter input pulse gives a shorter output pulse. `duration` is clamped
+/// to `1..=MAX_MONOSTABLE_DURATION`.
+///
+/// - input: lever at `pos`.
+/// - output: lamp at `pos + (3, 0, 0)`.
+///
+/// The input reaches a subtracting comparator both directly and through a
+/// repeater chain into its side; the output is on until the delayed copy
+/// arrives and cancels it.
+pub fn monostable(pos: Pos, duration: u8) -> World {
+    let delays = repeater_delays(u32::from(duration.clamp(1, MAX_MONOSTABLE_DURATION)));
+    let n = delays.len() as i32;
+    let mut builder = WorldBuilder::new()
+        .lever(pos, Direction::East, false)
+        .dust(pos + (1, 0, 0))
+        .comparator(pos + (2, 0, 0), Direction::East, ComparatorMode::Subtract)
+        .lamp(pos + (3, 0, 0));
+    // dust down x + 1, across, and repeaters back up x + 2 into the comparator's side
+    for dz in 1..=n + 1 {
+        builder = builder.dust(pos + (1, 0, dz));
+    }
+    builder = builder.dust(pos + (2, 0, n + 1));
+    for (i, delay) in delays.iter().enumerate() {
+        builder = builder.repeater(pos + (2, 0, n - i as i32), Direction::North, *delay);
+    }
+    finish(builder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{simulate, SimRequest, TimedEvent};
+
+    fn run(world: World, ticks: u32, events: Vec<(u32, TimedEvent)>) -> crate::SimResponse {
+        simulate(SimRequest { ticks, world, early_exit: false, events, ..Default::default() })
+        .unwrap()
+    }
+
+    // ticks at which the block at `pos` changed to a lit/powered output
+    fn rising_ticks(res: &crate::SimResponse, pos: Pos) -> Vec<u32> {
+        let mut on = false;
+        let mut out = Vec::new();
+        for d in &res.diffs {
+            if let Some(c) = d.changes.iter().find(|c| c.pos == pos) {
+                let now = c.kind.current_power() > 0;
+                if now && !on {
+                    out.push(d.tick);
+                }
+                on = now;
+            }
+        }
+        out
+    }
+
+    const P: Pos = Pos { x: 10, y: 0, z: 10 };
+
+    #[test]
+    fn rs_latch_sets_and_resets() {
+        let hold = RS_LATCH_HOLD;
+        let set = TimedEvent::LeverToggle(P + (4, 0, 0));
+        let reset = TimedEvent::LeverToggle(P + (-1, 0, 0));
+        let lamp = P + (1, 0, -1);
+        let world = rs_latch(P);
+        let res = run(world.clone(), 40, vec![(2, set.clone()), (2 + hold, set), (20, reset.clone()), (20 + hold, reset)]);
+        assert_eq!(res.power_at(&world, lamp, 0), 0);
+        assert_eq!(res.power_at(&world, lamp, 19), 15);
+        assert_eq!(res.power_at(&world, lamp, 40), 0);
+        assert_eq!(rising_ticks(&res, lamp).len(), 1);
+    }
+
+    #[test]
+    fn t_flip_flop_toggles_on_each_press() {
+        let bulb = P + (2, 0, 0);
+        let press = TimedEvent::ButtonPress(P);
+        let world = t_flip_flop(P);
+        let res = run(world.clone(), 40, vec![(2, press.clone()), (20, press)]);
+        assert_eq!(res.power_at(&world, bulb, 1), 0);
+        assert_eq!(res.power_at(&world, bulb, 19), 15);
+        assert_eq!(res.power_at(&world, bulb, 40), 0);
+    }
+
+    #[test]
+    fn clock_lamp_pulses_once_per_period() {
+        for period in [4, 7, 16] {
+            let res = run(clock(P, period).unwrap(), 60, vec![]);
+            let ticks = rising_ticks(&res, P + (0, -1, 0));
+            assert!(ticks.len() >= 3, "period {period}: {ticks:?}");
+            assert!(ticks.windows(2).all(|w| w[1] - w[0] == period), "period {period}: {ticks:?}");
+        }
+        assert_eq!(clock(P, 3).unwrap_err(), CircuitError::InvalidPeriod(3));
+        assert_eq!(clock(P, 17).unwrap_err(), CircuitError::InvalidPeriod(17));
+    }
+
+    #[test]
+    fn delay_line_lags_by_its_ticks() {
+        let lit_at = |ticks: u8| {
+            let n = u32::from(ticks.max(1)).div_ceil(4) as i32;
+            let world = delay_line(P, ticks);
+            let res = run(world.clone(), 30, vec![(1, TimedEvent::LeverToggle(P))]);
+            res.first_powered_tick(&world, P + (n + 1, 0, 0)).unwrap()
+        };
+        assert_eq!(lit_at(5) - lit_at(1), 4);
+        assert_eq!(lit_at(13) - lit_at(1), 12);
+        assert_eq!(lit_at(0), lit_at(1));
+    }
+
+    #[test]
+    fn monostable_pulse_lasts_its_duration() {
+        let lamp = P + (3, 0, 0);
+        let lever = TimedEvent::LeverToggle(P);
+        for duration in [1, 6, MAX_MONOSTABLE_DURATION] {
+            let res = run(monostable(P, duration), 80, vec![(1, lever.clone()), (70, lever.clone())]);
+            let on = rising_ticks(&res, lamp);
+            assert_eq!(on.len(), 1, "duration {duration}");
+            let off = res
+                .diffs
+                .iter()
+                .find(|d| d.tick > on[0] && d.changes.iter().any(|c| c.pos == lamp))
+                .map(|d| d.tick);
+            assert_eq!(off, Some(on[0] + u32::from(duration)), "duration {duration}");
+        }
+        assert_eq!(pulse_shortener(P), monostable(P, 1));
+    }
+}
@@ -0,0 +1,191 @@
+// src/compact.rs
+
+// Compact JSON for requests with large worlds. The verbose form repeats
+// `"x"`, `"y"`, `"z"`, `"type"` and every field name on each block; here a
+// block is one array `[x, y, z, type_index, field1, field2, ...]` and the
+// names are written once, in a `types` table the index points into:
+//
+//   {"version": 1, "ticks": 100, ...,
+//    "world": {"types": [["dust", ["connections", "power"]], ["lamp", ["on"]]],
+//              "blocks": [[1, 0, 0, 0, [false, false, false, false], 0], [2, 0, 0, 1, false]]}}
+//
+// Everything but the world keeps its usual shape. Only built with the
+// `compact` feature.
+
+use crate::{PlacedBlock, Pos, SimRequest};
+use serde_json::{json, Map, Value};
+
+/// `version` written by [`to_compact_json`]; [`from_compact_json`] refuses
+/// any other.
+pub const COMPACT_VERSION: u64 = 1;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DeserError {
+    Json(String),         // not JSON, or the request around the world is not the expected shape
+    Version(Option<u64>), // missing, or a version this build cannot read
+    BadBlock(usize),      // blocks[i] is too short or names a type not in the table
+}
+
+impl std::fmt::Display for DeserError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeserError::Json(e) => write!(f, "invalid compact request: {}", e),
+            DeserError::Version(Some(v)) => {
+                write!(f, "compact request version {} is not supported (expected {})", v, COMPACT_VERSION)
+            }
+            DeserError::Version(None) => write!(f, "compact request has no version"),
+            DeserError::BadBlock(i) => write!(f, "malformed block at index {}", i),
+        }
+    }
+}
+
+impl std::error::Error for DeserError {}
+
+// the verbose fields of a block kind, minus its "type"; serde_json's map keeps
+// them sorted by name, so kinds of one type share a field order
+fn kind_fields(block: &PlacedBlock) -> (String, Map<String, Value>) {
+    // every BlockKind serializes to a tagged object of plain values
+    let Value::Object(mut fields) = serde_json::to_value(&block.kind).expect("block kinds serialize to JSON") else {
+        unreachable!("BlockKind is an internally tagged enum");
+    };
+    let name = match fields.remove("type") {
+        Some(Value::String(name)) => name,
+        _ => unreachable!("BlockKind is tagged with `type`"),
+    };
+    (name, fields)
+}
+
+/// Encode `req` with its world as a type table and one array per block.
+pub fn to_compact_json(req: &SimRequest) -> String {
+    // (type name, field names) in order of first use
+    let mut types: Vec<(String, Vec<String>)> = Vec::new();
+    let blocks: Vec<Value> = req
+        .world
+        .blocks
+        .iter()
+        .map(|block| {
+            let (name, fields) = kind_fields(block);
+            let names: Vec<String> = fields.keys().cloned().collect();
+            let index = types.iter().position(|t| t.0 == name && t.1 == names).unwrap_or_else(|| {
+                types.push((name, names));
+                types.len() - 1
+            });
+            let Pos { x, y, z } = block.pos;
+            let mut row = vec![json!(x), json!(y), json!(z), json!(index)];
+            row.extend(fields.into_iter().map(|(_, v)| v));
+            Value::Array(row)
+        })
+        .collect();
+
+    let mut out = match serde_json::to_value(req).expect("requests serialize to JSON") {
+        Value::Object(map) => map,
+        _ => unreachable!("SimRequest serializes to an object"),
+    };
+    out.insert("version".into(), COMPACT_VERSION.into());
+    let types: Vec<Value> = types.into_iter().map(|(name, names)| json!([name, names])).collect();
+    out.insert("world".into(), json!({ "types": types, "blocks": blocks }));
+    Value::Object(out).to_string()
+}
+
+// rebuild the verbose `{"x", "y", "z", "type", ...}` object of blocks[i]
+fn expand_block(i: usize, row: &Value, types: &[(String, Vec<String>)]) -> Result<Value, DeserError> {
+    let bad = || DeserError::BadBlock(i);
+    let row = row.as_array().ok_or_else(bad)?;
+    if row.len() < 4 {
+        return Err(bad());
+    }
+    let (name, names) = row[3].as_u64().and_then(|t| types.get(t as usize)).ok_or_else(bad)?;
+    if row.len() != 4 + names.len() {
+        return Err(bad());
+    }
+    let mut block = Map::new();
+    for (axis, v) in ["x", "y", "z"].into_iter().zip(&row[..3]) {
+        block.insert(axis.into(), v.clone());
+    }
+    block.insert("type".into(), name.as_str().into());
+    block.extend(names.iter().cloned().zip(row[4..].iter().cloned()));
+    Ok(Value::Object(block))
+}
+
+/// Decode what [`to_compact_json`] wrote.
+pub fn from_compact_json(s: &str) -> Result<SimRequest, DeserError> {
+    let json_err = |e: serde_json::Error| DeserError::Json(e.to_string());
+    let shape = |what: &str| DeserError::Json(format!("expected {}", what));
+    let mut req: Map<String, Value> = serde_json::from_str(s).map_err(json_err)?;
+    match req.remove("version").map(|v| v.as_u64()) {
+        Some(Some(COMPACT_VERSION)) => {}
+        Some(v) => return Err(DeserError::Version(v)),
+        None => return Err(DeserError::Version(None)),
+    }
+
+    let mut world = match req.remove("world") {
+        Some(Value::Object(world)) => world,
+        _ => return Err(shape("`world` to be an object")),
+    };
+    let types: Vec<(String, Vec<String>)> = serde_json::from_value(world.remove("types").unwrap_or_default())
+        .map_err(|_| shape("`types` of [name, [fields]]"))?;
+    let rows = match world.remove("blocks") {
+        Some(Value::Array(rows)) => rows,
+        _ => return Err(shape("`blocks` to be an array")),
+    };
+    let blocks = rows.iter().enumerate().map(|(i, row)| expand_block(i, row, &types)).collect::<Result<Vec<_>, _>>()?;
+    req.insert("world".into(), json!({ "blocks": blocks }));
+    serde_json::from_value(Value::Object(req)).map_err(json_err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{simulate, Direction, TimedEvent, WorldBuilder};
+
+    fn request() -> SimRequest {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, false)
+            .dust((1, 0, 0))
+            .dust((2, 0, 0))
+            .repeater((3, 0, 0), Direction::East, 2)
+            .lamp((4, 0, 0))
+            .build()
+            .unwrap();
+        SimRequest {
+            ticks: 20,
+            world,
+            events: vec![(1, TimedEvent::LeverToggle(Pos { x: 0, y: 0, z: 0 }))],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn round_trips_and_is_smaller() {
+        let req = request();
+        let compact = to_compact_json(&req);
+        let verbose = serde_json::to_string(&req).unwrap();
+        assert!(compact.len() < verbose.len(), "{} >= {}", compact.len(), verbose.len());
+
+        let value: Value = serde_json::from_str(&compact).unwrap();
+        assert_eq!(value["version"], COMPACT_VERSION);
+        // the two dust blocks share one table entry
+        assert_eq!(value["world"]["types"].as_array().unwrap().len(), 4);
+        assert_eq!(value["world"]["blocks"][2][3], value["world"]["blocks"][1][3]);
+
+        let back = from_compact_json(&compact).unwrap();
+        assert_eq!(back.world, req.world);
+        assert_eq!(simulate(back).unwrap(), simulate(req).unwrap());
+    }
+
+    #[test]
+    fn rejects_other_versions_and_bad_blocks() {
+        let mut value: Value = serde_json::from_str(&to_compact_json(&request())).unwrap();
+        value["version"] = 2.into();
+        assert_eq!(from_compact_json(&value.to_string()).unwrap_err(), DeserError::Version(Some(2)));
+        value.as_object_mut().unwrap().remove("version");
+        assert_eq!(from_compact_json(&value.to_string()).unwrap_err(), DeserError::Version(None));
+
+        value["version"] = COMPACT_VERSION.into();
+        value["world"]["blocks"][3][3] = 99.into();
+        assert_eq!(from_compact_json(&value.to_string()).unwrap_err(), DeserError::BadBlock(3));
+        value["world"]["blocks"][3] = json!([3, 0, 0]);
+        assert_eq!(from_compact_json(&value.to_string()).unwrap_err(), DeserError::BadBlock(3));
+        assert!(matches!(from_compact_json("[1, 2"), Err(DeserError::Json(_))));
+    }
+}
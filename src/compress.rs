@@ -0,0 +1,185 @@
+// src/compress.rs
+
+// Run-length form of a `SimResponse` for clocks and other oscillators,
+// whose diffs repeat the same unit for most of a long run. The period comes
+// from `analysis::detect_period`; the repeats themselves are matched diff by
+// diff, so decompressing always gives back the original response.
+
+use crate::{
+    analysis, BellRingEvent, CraftEvent, Instrument, LaunchEvent, LightningEvent, Pos, SimResponse, Termination,
+    TickDiff,
+};
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+/// A `SimResponse` whose diffs are `initial_diffs`, then `periodic_diffs`
+/// `repetitions` times (each copy `period` ticks after the one before), then
+/// `trailing_diffs`. With no repeating unit `period` and `repetitions` are 0
+/// and every diff is in `initial_diffs`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+pub struct SimResponseCompressed {
+    pub period: u32,
+    pub initial_diffs: Vec<TickDiff>,  // start-up transient before the first repeat
+    pub periodic_diffs: Vec<TickDiff>, // the first copy of the unit, at its own ticks
+    pub repetitions: u32,              // copies of the unit, the first one included
+    #[serde(default)]
+    pub trailing_diffs: Vec<TickDiff>, // after the last whole copy, e.g. a cut-off one
+    pub terminated: Termination,
+    // passed through unchanged
+    #[serde(default)]
+    pub notes: Vec<(u32 /*tick*/, Pos, Instrument, u8 /*pitch*/)>,
+    #[serde(default)]
+    pub launch_events: Vec<LaunchEvent>,
+    #[serde(default)]
+    pub lightning_events: Vec<LightningEvent>,
+    #[serde(default)]
+    pub craft_events: Vec<CraftEvent>,
+    #[serde(default)]
+    pub bell_events: Vec<BellRingEvent>,
+    #[serde(default)]
+    pub oscillation_period: Option<u32>,
+    #[serde(default)]
+    pub seed_used: u64,
+}
+
+impl SimResponseCompressed {
+    /// Number of diffs the decompressed response will hold.
+    pub fn total_diffs(&self) -> usize {
+        self.initial_diffs.len() + self.periodic_diffs.len() * self.repetitions as usize + self.trailing_diffs.len()
+    }
+}
+
+fn shifted(diff: &TickDiff, by: u32) -> TickDiff {
+    TickDiff { tick: diff.tick + by, ..diff.clone() }
+}
+
+// whether `copy` is `unit` moved `by` ticks later
+fn is_copy(unit: &[TickDiff], copy: &[TickDiff], by: u32) -> bool {
+    unit.len() == copy.len()
+        && unit.iter().zip(copy).all(|(u, c)| u.tick + by == c.tick && u.changes == c.changes && u.removed == c.removed)
+}
+
+// (start index, unit length in diffs, repetitions) of the earliest unit of
+// `period` ticks that repeats at least twice
+fn find_repeats(diffs: &[TickDiff], period: u32) -> Option<(usize, usize, u32)> {
+    let window = |from: usize, end_tick: u32| diffs[from..].iter().take_while(|d| d.tick < end_tick).count();
+    (0..diffs.len()).find_map(|start| {
+        let first_tick = diffs[start].tick;
+        let len = window(start, first_tick + period);
+        let unit = &diffs[start..start + len];
+        let mut reps = 1;
+        let mut next = start + len;
+        loop {
+            let by = period * reps;
+            let copy = &diffs[next..next + window(next, first_tick + by + period)];
+            if copy.is_empty() || !is_copy(unit, copy, by) {
+                break;
+            }
+            reps += 1;
+            next += len;
+        }
+        (reps >= 2).then_some((start, len, reps))
+    })
+}
+
+/// Split `resp.diffs` into a transient, a repeating unit and a remainder.
+/// Uses `resp.oscillation_period` when the run reported one, and otherwise
+/// looks for a period over the second half of the run.
+pub fn compress_response(resp: SimResponse) -> SimResponseCompressed {
+    let last = resp.diffs.last().map_or(0, |d| d.tick);
+    let repeats = resp
+        .oscillation_period
+        .or_else(|| analysis::detect_period(&resp.diffs, last / 2))
+        .and_then(|p| find_repeats(&resp.diffs, p).map(|r| (p, r)));
+    let mut diffs = resp.diffs;
+    let (period, initial_diffs, periodic_diffs, repetitions, trailing_diffs) = match repeats {
+        Some((period, (start, len, reps))) => {
+            let trailing = diffs.split_off(start + len * reps as usize);
+            diffs.truncate(start + len);
+            let unit = diffs.split_off(start);
+            (period, diffs, unit, reps, trailing)
+        }
+        None => (0, diffs, Vec::new(), 0, Vec::new()),
+    };
+    SimResponseCompressed {
+        period,
+        initial_diffs,
+        periodic_diffs,
+        repetitions,
+        trailing_diffs,
+        terminated: resp.terminated,
+        notes: resp.notes,
+        launch_events: resp.launch_events,
+        lightning_events: resp.lightning_events,
+        craft_events: resp.craft_events,
+        bell_events: resp.bell_events,
+        oscillation_period: resp.oscillation_period,
+        seed_used: resp.seed_used,
+    }
+}
+
+/// Expand a [`compress_response`] result back into the original response.
+pub fn decompress_response(resp: SimResponseCompressed) -> SimResponse {
+    let mut diffs = Vec::with_capacity(resp.total_diffs());
+    diffs.extend(resp.initial_diffs);
+    for rep in 0..resp.repetitions {
+        diffs.extend(resp.periodic_diffs.iter().map(|d| shifted(d, resp.period * rep)));
+    }
+    diffs.extend(resp.trailing_diffs);
+    SimResponse {
+        diffs,
+        terminated: resp.terminated,
+        notes: resp.notes,
+        launch_events: resp.launch_events,
+        lightning_events: resp.lightning_events,
+        craft_events: resp.craft_events,
+        bell_events: resp.bell_events,
+        oscillation_period: resp.oscillation_period,
+        seed_used: resp.seed_used,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{circuits, simulate, BlockKind, Direction, SimRequest, WorldBuilder};
+
+    #[test]
+    fn observer_clock_compresses_to_one_period() {
+        let observers = WorldBuilder::new()
+            .block((0, 0, 0), BlockKind::Observer { facing: Direction::East, powered: true })
+            .observer((1, 0, 0), Direction::West)
+            .build()
+            .unwrap();
+        let resp = simulate(SimRequest { ticks: 100, world: observers, ..Default::default() }).unwrap();
+        assert_eq!(resp.oscillation_period, Some(2));
+
+        let compressed = compress_response(resp.clone());
+        assert_eq!(compressed.period, 2);
+        assert!(compressed.repetitions >= 45, "{}", compressed.repetitions);
+        assert!(compressed.periodic_diffs.len() <= 2);
+        assert_eq!(compressed.total_diffs(), resp.diffs.len());
+        assert_eq!(decompress_response(compressed), resp);
+    }
+
+    #[test]
+    fn finds_the_period_of_a_full_length_run() {
+        // no early exit, so the simulator reports no period of its own
+        let clock = circuits::clock(Pos::default(), 7).unwrap();
+        let resp = simulate(SimRequest { ticks: 100, world: clock, early_exit: false, ..Default::default() }).unwrap();
+        assert_eq!(resp.oscillation_period, None);
+        let compressed = compress_response(resp.clone());
+        assert_eq!(compressed.period, 7);
+        assert!(compressed.repetitions >= 13, "{}", compressed.repetitions);
+        assert_eq!(decompress_response(compressed), resp);
+
+        // nothing repeats: everything stays in the transient
+        let wire = WorldBuilder::new().lever((0, 0, 0), Direction::East, true).lamp((1, 0, 0)).build().unwrap();
+        let resp = simulate(SimRequest { world: wire, ..Default::default() }).unwrap();
+        let compressed = compress_response(resp.clone());
+        assert_eq!((compressed.period, compressed.repetitions), (0, 0));
+        assert_eq!(compressed.initial_diffs, resp.diffs);
+        assert_eq!(decompress_response(compressed), resp);
+    }
+}
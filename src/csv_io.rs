@@ -0,0 +1,623 @@
+// src/csv_io.rs
+
+// Worlds as CSV tables, one block per row, for circuits laid out in a
+// spreadsheet. Only built with the `csv` feature.
+//
+// Columns: x, y, z, type, facing, on_or_power, then one column per
+// remaining BlockKind field (see COLUMNS). `on_or_power` holds the block's
+// main state: `on`, `lit`, `powered`, `power`, `output`, `open` and so on,
+// whichever the kind has. A cell a kind has no field for is left empty.
+
+use crate::{
+    BellAttachment, BlockKind, ComparatorMode, Direction, DoorHalf, HingePosition, Instrument, OxidationLevel,
+    PlacedBlock, PlateKind, Pos, RailShape, TrapdoorHalf, World,
+};
+
+const ON_OR_POWER: &str = "on_or_power";
+
+/// Header written by `World::to_csv`. `World::from_csv` looks columns up by
+/// name, so they may come in any order and all but x, y, z and type may be
+/// left out.
+pub const COLUMNS: [&str; 35] = [
+    "x",
+    "y",
+    "z",
+    "type",
+    "facing",
+    ON_OR_POWER,
+    "delay",
+    "ticks_remaining",
+    "powered",
+    "power",
+    "locked",
+    "mode",
+    "burnout_counter",
+    "burnout_cooldown",
+    "burned_out",
+    "sticky",
+    "items",
+    "max_items",
+    "fuel_ticks",
+    "cooking",
+    "waterlogged",
+    "hinge",
+    "half",
+    "instrument",
+    "pitch",
+    "shape",
+    "kind",
+    "cooldown",
+    "craft_count",
+    "attached",
+    "inverted",
+    "oxidation",
+    "attachment",
+    "in_wall",
+    "connections",
+];
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CsvError {
+    Csv(String),                                                     // not well-formed CSV
+    MissingColumn(&'static str),                                     // header lacks x, y, z or type
+    UnknownType { row: usize, name: String },                        // `type` names no BlockKind
+    InvalidField { row: usize, column: &'static str, value: String }, // empty where required, or unparsable
+}
+
+impl std::fmt::Display for CsvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CsvError::Csv(e) => write!(f, "malformed CSV: {}", e),
+            CsvError::MissingColumn(c) => write!(f, "CSV header has no `{}` column", c),
+            CsvError::UnknownType { row, name } => write!(f, "row {}: unknown block type `{}`", row, name),
+            CsvError::InvalidField { row, column, value } if value.is_empty() => {
+                write!(f, "row {}: `{}` is required", row, column)
+            }
+            CsvError::InvalidField { row, column, value } => {
+                write!(f, "row {}: invalid {} `{}`", row, column, value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+// A value's text in a cell; enums use the same names as the JSON form.
+trait Cell: Sized {
+    fn to_cell(&self) -> String;
+    fn from_cell(s: &str) -> Option<Self>;
+}
+
+macro_rules! parsed_cell {
+    ($($t:ty),*) => {$(
+        impl Cell for $t {
+            fn to_cell(&self) -> String {
+                self.to_string()
+            }
+            fn from_cell(s: &str) -> Option<Self> {
+                s.parse().ok()
+            }
+        }
+    )*};
+}
+
+parsed_cell!(bool, u8, u32, i32, Direction);
+
+macro_rules! named_cell {
+    ($t:ident { $($variant:ident => $name:literal),* $(,)? }) => {
+        impl Cell for $t {
+            fn to_cell(&self) -> String {
+                match self {
+                    $($t::$variant => $name,)*
+                }
+                .to_string()
+            }
+            fn from_cell(s: &str) -> Option<Self> {
+                match s {
+                    $($name => Some($t::$variant),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+named_cell!(ComparatorMode { Compare => "compare", Subtract => "subtract" });
+named_cell!(HingePosition { Left => "left", Right => "right" });
+named_cell!(DoorHalf { Upper => "upper", Lower => "lower" });
+named_cell!(TrapdoorHalf { Top => "top", Bottom => "bottom" });
+named_cell!(PlateKind {
+    Stone => "stone",
+    Wood => "wood",
+    LightWeighted => "light_weighted",
+    HeavyWeighted => "heavy_weighted",
+});
+named_cell!(OxidationLevel {
+    Unoxidized => "unoxidized",
+    Exposed => "exposed",
+    Weathered => "weathered",
+    Oxidized => "oxidized",
+});
+named_cell!(BellAttachment {
+    Floor => "floor",
+    Ceiling => "ceiling",
+    SingleWall => "single_wall",
+    DoubleWall => "double_wall",
+});
+named_cell!(Instrument {
+    Harp => "harp",
+    Basedrum => "basedrum",
+    Snare => "snare",
+    Hat => "hat",
+    Bass => "bass",
+    Flute => "flute",
+    Bell => "bell",
+    Guitar => "guitar",
+    Chime => "chime",
+    Xylophone => "xylophone",
+    IronXylophone => "iron_xylophone",
+    CowBell => "cow_bell",
+    Didgeridoo => "didgeridoo",
+    Bit => "bit",
+    Banjo => "banjo",
+    Pling => "pling",
+});
+named_cell!(RailShape {
+    NorthSouth => "north_south",
+    EastWest => "east_west",
+    AscendingEast => "ascending_east",
+    AscendingWest => "ascending_west",
+    AscendingNorth => "ascending_north",
+    AscendingSouth => "ascending_south",
+    SouthEast => "south_east",
+    SouthWest => "south_west",
+    NorthWest => "north_west",
+    NorthEast => "north_east",
+});
+
+/// Dust connections as four 0/1 digits, north, east, south, west.
+impl Cell for [bool; 4] {
+    fn to_cell(&self) -> String {
+        self.iter().map(|&c| if c { '1' } else { '0' }).collect()
+    }
+    fn from_cell(s: &str) -> Option<Self> {
+        let mut out = [false; 4];
+        let bytes = s.as_bytes();
+        if bytes.len() != 4 {
+            return None;
+        }
+        for (slot, b) in out.iter_mut().zip(bytes) {
+            *slot = match b {
+                b'0' => false,
+                b'1' => true,
+                _ => return None,
+            };
+        }
+        Some(out)
+    }
+}
+
+fn column_index(name: &str) -> usize {
+    COLUMNS.iter().position(|c| *c == name).expect("not a CSV column")
+}
+
+struct Row(Vec<String>);
+
+impl Row {
+    fn set(&mut self, column: &str, value: impl Cell) {
+        self.0[column_index(column)] = value.to_cell();
+    }
+}
+
+fn write_kind(row: &mut Row, kind: &BlockKind) {
+    match kind {
+        BlockKind::Lever { on, facing } => {
+            row.set(ON_OR_POWER, *on);
+            row.set("facing", *facing);
+        }
+        BlockKind::Button { ticks_remaining, facing } => {
+            row.set("ticks_remaining", *ticks_remaining);
+            row.set("facing", *facing);
+        }
+        BlockKind::Dust { power, connections } => {
+            row.set(ON_OR_POWER, *power);
+            row.set("connections", *connections);
+        }
+        BlockKind::Lamp { on } => row.set(ON_OR_POWER, *on),
+        BlockKind::Repeater { delay, ticks_remaining, powered, facing, locked } => {
+            row.set(ON_OR_POWER, *powered);
+            row.set("facing", *facing);
+            row.set("delay", *delay);
+            row.set("ticks_remaining", *ticks_remaining);
+            row.set("locked", *locked);
+        }
+        BlockKind::Comparator { output, facing, mode } => {
+            row.set(ON_OR_POWER, *output);
+            row.set("facing", *facing);
+            row.set("mode", *mode);
+        }
+        BlockKind::Torch { lit, facing, burnout_counter, burnout_cooldown, burned_out } => {
+            row.set(ON_OR_POWER, *lit);
+            row.set("facing", *facing);
+            row.set("burnout_counter", *burnout_counter);
+            row.set("burnout_cooldown", *burnout_cooldown);
+            row.set("burned_out", *burned_out);
+        }
+        BlockKind::Piston { extended, facing, sticky } => {
+            row.set(ON_OR_POWER, *extended);
+            row.set("facing", *facing);
+            row.set("sticky", *sticky);
+        }
+        BlockKind::PistonArm { facing } => row.set("facing", *facing),
+        BlockKind::Hopper { enabled, facing, items } => {
+            row.set(ON_OR_POWER, *enabled);
+            row.set("facing", *facing);
+            row.set("items", *items);
+        }
+        BlockKind::Observer { facing, powered } => {
+            row.set(ON_OR_POWER, *powered);
+            row.set("facing", *facing);
+        }
+        BlockKind::OpaqueSolid { strong_power } => row.set(ON_OR_POWER, *strong_power),
+        BlockKind::RedstoneBlock => {}
+        BlockKind::Target { power, ticks_remaining } => {
+            row.set(ON_OR_POWER, *power);
+            row.set("ticks_remaining", *ticks_remaining);
+        }
+        BlockKind::SculkSensor { power, ticks_remaining, waterlogged } => {
+            row.set(ON_OR_POWER, *power);
+            row.set("ticks_remaining", *ticks_remaining);
+            row.set("waterlogged", *waterlogged);
+        }
+        BlockKind::CalibratedSculkSensor { power, ticks_remaining, waterlogged, facing } => {
+            row.set(ON_OR_POWER, *power);
+            row.set("facing", *facing);
+            row.set("ticks_remaining", *ticks_remaining);
+            row.set("waterlogged", *waterlogged);
+        }
+        BlockKind::Door { open, hinge, facing, half } => {
+            row.set(ON_OR_POWER, *open);
+            row.set("facing", *facing);
+            row.set("hinge", *hinge);
+            row.set("half", *half);
+        }
+        BlockKind::Trapdoor { open, facing, half } => {
+            row.set(ON_OR_POWER, *open);
+            row.set("facing", *facing);
+            row.set("half", *half);
+        }
+        BlockKind::NoteBlock { instrument, pitch, triggered, powered } => {
+            row.set(ON_OR_POWER, *triggered);
+            row.set("instrument", *instrument);
+            row.set("pitch", *pitch);
+            row.set("powered", *powered);
+        }
+        BlockKind::PoweredRail { powered, shape }
+        | BlockKind::DetectorRail { powered, shape }
+        | BlockKind::ActivatorRail { powered, shape } => {
+            row.set(ON_OR_POWER, *powered);
+            row.set("shape", *shape);
+        }
+        BlockKind::PressurePlate { powered, kind, power } => {
+            row.set(ON_OR_POWER, *powered);
+            row.set("kind", *kind);
+            row.set("power", *power);
+        }
+        BlockKind::Dropper { facing, powered, cooldown } | BlockKind::Dispenser { facing, powered, cooldown } => {
+            row.set(ON_OR_POWER, *powered);
+            row.set("facing", *facing);
+            row.set("cooldown", *cooldown);
+        }
+        BlockKind::Chest { items, max_items } | BlockKind::Barrel { items, max_items } => {
+            row.set("items", *items);
+            row.set("max_items", *max_items);
+        }
+        BlockKind::Furnace { fuel_ticks, cooking } => {
+            row.set("fuel_ticks", *fuel_ticks);
+            row.set("cooking", *cooking);
+        }
+        BlockKind::TripwireHook { attached, powered, facing } => {
+            row.set(ON_OR_POWER, *powered);
+            row.set("facing", *facing);
+            row.set("attached", *attached);
+        }
+        BlockKind::Tripwire { powered } => row.set(ON_OR_POWER, *powered),
+        BlockKind::DaylightSensor { inverted, power } => {
+            row.set(ON_OR_POWER, *power);
+            row.set("inverted", *inverted);
+        }
+        BlockKind::LightningRod { powered, ticks_remaining } => {
+            row.set(ON_OR_POWER, *powered);
+            row.set("ticks_remaining", *ticks_remaining);
+        }
+        BlockKind::CopperBulb { lit, powered, oxidation } => {
+            row.set(ON_OR_POWER, *lit);
+            row.set("powered", *powered);
+            row.set("oxidation", *oxidation);
+        }
+        BlockKind::Crafter { powered, cooldown, craft_count } => {
+            row.set(ON_OR_POWER, *powered);
+            row.set("cooldown", *cooldown);
+            row.set("craft_count", *craft_count);
+        }
+        BlockKind::Bell { attachment, facing, ringing, powered, ticks_remaining } => {
+            row.set(ON_OR_POWER, *ringing);
+            row.set("facing", *facing);
+            row.set("attachment", *attachment);
+            row.set("powered", *powered);
+            row.set("ticks_remaining", *ticks_remaining);
+        }
+        BlockKind::FenceGate { open, facing, in_wall } => {
+            row.set(ON_OR_POWER, *open);
+            row.set("facing", *facing);
+            row.set("in_wall", *in_wall);
+        }
+    }
+}
+
+// One data row being read; `columns[i]` is where COLUMNS[i] sits in it.
+struct Record<'a> {
+    fields: &'a csv::StringRecord,
+    columns: &'a [Option<usize>],
+    row: usize,
+}
+
+impl Record<'_> {
+    fn text(&self, column: &'static str) -> &str {
+        self.columns[column_index(column)].and_then(|i| self.fields.get(i)).unwrap_or("")
+    }
+
+    fn parse<T: Cell>(&self, column: &'static str, text: &str) -> Result<T, CsvError> {
+        T::from_cell(text).ok_or_else(|| CsvError::InvalidField { row: self.row, column, value: text.to_string() })
+    }
+
+    // a field the JSON form requires
+    fn get<T: Cell>(&self, column: &'static str) -> Result<T, CsvError> {
+        self.parse(column, self.text(column))
+    }
+
+    // a `#[serde(default)]` field: an empty cell gives the default
+    fn get_or_default<T: Cell + Default>(&self, column: &'static str) -> Result<T, CsvError> {
+        match self.text(column) {
+            "" => Ok(T::default()),
+            text => self.parse(column, text),
+        }
+    }
+
+    fn block(&self) -> Result<PlacedBlock, CsvError> {
+        let pos = Pos { x: self.get("x")?, y: self.get("y")?, z: self.get("z")? };
+        Ok(PlacedBlock { pos, kind: self.kind()? })
+    }
+
+    fn kind(&self) -> Result<BlockKind, CsvError> {
+        Ok(match self.text("type") {
+            "lever" => BlockKind::Lever { on: self.get(ON_OR_POWER)?, facing: self.get("facing")? },
+            "button" => BlockKind::Button { ticks_remaining: self.get("ticks_remaining")?, facing: self.get("facing")? },
+            "dust" => BlockKind::Dust { power: self.get(ON_OR_POWER)?, connections: self.get_or_default("connections")? },
+            "lamp" => BlockKind::Lamp { on: self.get(ON_OR_POWER)? },
+            "repeater" => BlockKind::Repeater {
+                delay: self.get("delay")?,
+                ticks_remaining: self.get("ticks_remaining")?,
+                powered: self.get(ON_OR_POWER)?,
+                facing: self.get("facing")?,
+                locked: self.get_or_default("locked")?,
+            },
+            "comparator" => BlockKind::Comparator {
+                output: self.get(ON_OR_POWER)?,
+                facing: self.get("facing")?,
+                mode: self.get_or_default("mode")?,
+            },
+            "torch" => BlockKind::Torch {
+                lit: self.get(ON_OR_POWER)?,
+                facing: self.get("facing")?,
+                burnout_counter: self.get_or_default("burnout_counter")?,
+                burnout_cooldown: self.get_or_default("burnout_cooldown")?,
+                burned_out: self.get_or_default("burned_out")?,
+            },
+            "piston" => BlockKind::Piston {
+                extended: self.get(ON_OR_POWER)?,
+                facing: self.get("facing")?,
+                sticky: self.get_or_default("sticky")?,
+            },
+            "pistonarm" => BlockKind::PistonArm { facing: self.get("facing")? },
+            "hopper" => BlockKind::Hopper {
+                enabled: self.get(ON_OR_POWER)?,
+                facing: self.get("facing")?,
+                items: self.get_or_default("items")?,
+            },
+            "observer" => BlockKind::Observer { facing: self.get("facing")?, powered: self.get(ON_OR_POWER)? },
+            "opaquesolid" => BlockKind::OpaqueSolid { strong_power: self.get(ON_OR_POWER)? },
+            "redstoneblock" => BlockKind::RedstoneBlock,
+            "target" => BlockKind::Target { power: self.get(ON_OR_POWER)?, ticks_remaining: self.get("ticks_remaining")? },
+            "sculksensor" => BlockKind::SculkSensor {
+                power: self.get(ON_OR_POWER)?,
+                ticks_remaining: self.get("ticks_remaining")?,
+                waterlogged: self.get("waterlogged")?,
+            },
+            "calibratedsculksensor" => BlockKind::CalibratedSculkSensor {
+                power: self.get(ON_OR_POWER)?,
+                ticks_remaining: self.get("ticks_remaining")?,
+                waterlogged: self.get("waterlogged")?,
+                facing: self.get("facing")?,
+            },
+            "door" => BlockKind::Door {
+                open: self.get(ON_OR_POWER)?,
+                hinge: self.get("hinge")?,
+                facing: self.get("facing")?,
+                half: self.get("half")?,
+            },
+            "trapdoor" => BlockKind::Trapdoor {
+                open: self.get(ON_OR_POWER)?,
+                facing: self.get("facing")?,
+                half: self.get("half")?,
+            },
+            "noteblock" => BlockKind::NoteBlock {
+                instrument: self.get("instrument")?,
+                pitch: self.get("pitch")?,
+                triggered: self.get(ON_OR_POWER)?,
+                powered: self.get_or_default("powered")?,
+            },
+            "poweredrail" => BlockKind::PoweredRail { powered: self.get(ON_OR_POWER)?, shape: self.get("shape")? },
+            "detectorrail" => BlockKind::DetectorRail { powered: self.get(ON_OR_POWER)?, shape: self.get("shape")? },
+            "activatorrail" => BlockKind::ActivatorRail { powered: self.get(ON_OR_POWER)?, shape: self.get("shape")? },
+            "pressureplate" => BlockKind::PressurePlate {
+                powered: self.get(ON_OR_POWER)?,
+                kind: self.get("kind")?,
+                power: self.get_or_default("power")?,
+            },
+            "dropper" => BlockKind::Dropper {
+                facing: self.get("facing")?,
+                powered: self.get(ON_OR_POWER)?,
+                cooldown: self.get_or_default("cooldown")?,
+            },
+            "dispenser" => BlockKind::Dispenser {
+                facing: self.get("facing")?,
+                powered: self.get(ON_OR_POWER)?,
+                cooldown: self.get_or_default("cooldown")?,
+            },
+            "chest" => BlockKind::Chest {
+                items: self.get("items")?,
+                max_items: match self.text("max_items") {
+                    "" => crate::default_chest_slots(),
+                    text => self.parse("max_items", text)?,
+                },
+            },
+            "barrel" => BlockKind::Barrel {
+                items: self.get("items")?,
+                max_items: match self.text("max_items") {
+                    "" => crate::default_chest_slots(),
+                    text => self.parse("max_items", text)?,
+                },
+            },
+            "furnace" => BlockKind::Furnace { fuel_ticks: self.get("fuel_ticks")?, cooking: self.get("cooking")? },
+            "tripwirehook" => BlockKind::TripwireHook {
+                attached: self.get("attached")?,
+                powered: self.get(ON_OR_POWER)?,
+                facing: self.get("facing")?,
+            },
+            "tripwire" => BlockKind::Tripwire { powered: self.get(ON_OR_POWER)? },
+            "daylightsensor" => BlockKind::DaylightSensor { inverted: self.get("inverted")?, power: self.get(ON_OR_POWER)? },
+            "lightningrod" => BlockKind::LightningRod {
+                powered: self.get(ON_OR_POWER)?,
+                ticks_remaining: self.get_or_default("ticks_remaining")?,
+            },
+            "copperbulb" => BlockKind::CopperBulb {
+                lit: self.get(ON_OR_POWER)?,
+                powered: self.get("powered")?,
+                oxidation: self.get_or_default("oxidation")?,
+            },
+            "crafter" => BlockKind::Crafter {
+                powered: self.get(ON_OR_POWER)?,
+                cooldown: self.get_or_default("cooldown")?,
+                craft_count: self.get_or_default("craft_count")?,
+            },
+            "bell" => BlockKind::Bell {
+                attachment: self.get("attachment")?,
+                facing: self.get("facing")?,
+                ringing: self.get(ON_OR_POWER)?,
+                powered: self.get_or_default("powered")?,
+                ticks_remaining: self.get_or_default("ticks_remaining")?,
+            },
+            "fencegate" => BlockKind::FenceGate {
+                open: self.get(ON_OR_POWER)?,
+                facing: self.get("facing")?,
+                in_wall: self.get("in_wall")?,
+            },
+            name => return Err(CsvError::UnknownType { row: self.row, name: name.to_string() }),
+        })
+    }
+}
+
+impl World {
+    /// Read a world from CSV with a header row (see `COLUMNS`). Cells are
+    /// trimmed; fields the JSON form defaults may be left empty.
+    pub fn from_csv(s: &str) -> Result<World, CsvError> {
+        let mut reader = csv::ReaderBuilder::new().trim(csv::Trim::All).from_reader(s.as_bytes());
+        let header = reader.headers().map_err(|e| CsvError::Csv(e.to_string()))?;
+        let columns: Vec<Option<usize>> = COLUMNS.iter().map(|c| header.iter().position(|h| h == *c)).collect();
+        for required in ["x", "y", "z", "type"] {
+            if columns[column_index(required)].is_none() {
+                return Err(CsvError::MissingColumn(required));
+            }
+        }
+
+        let mut blocks = Vec::new();
+        for (i, fields) in reader.records().enumerate() {
+            let fields = fields.map_err(|e| CsvError::Csv(e.to_string()))?;
+            blocks.push(Record { fields: &fields, columns: &columns, row: i + 1 }.block()?);
+        }
+        Ok(World { blocks })
+    }
+
+    /// Write the world as CSV with every column in `COLUMNS`, one row per
+    /// block in `blocks` order. `from_csv` reads it back unchanged.
+    pub fn to_csv(&self) -> String {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer.write_record(COLUMNS).expect("writing to a Vec cannot fail");
+        for block in &self.blocks {
+            let mut row = Row(vec![String::new(); COLUMNS.len()]);
+            row.set("x", block.pos.x);
+            row.set("y", block.pos.y);
+            row.set("z", block.pos.z);
+            row.0[column_index("type")] = block.kind.type_name().to_string();
+            write_kind(&mut row, &block.kind);
+            writer.write_record(&row.0).expect("writing to a Vec cannot fail");
+        }
+        String::from_utf8(writer.into_inner().expect("writing to a Vec cannot fail")).expect("CSV cells are UTF-8")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WorldBuilder;
+
+    #[test]
+    fn round_trips_every_field() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, true)
+            .block((1, 0, 0), BlockKind::Dust { power: 14, connections: [false, true, false, true] })
+            .block((2, 0, 0), BlockKind::Repeater { delay: 3, ticks_remaining: 2, powered: false, facing: Direction::East, locked: true })
+            .comparator((3, 0, 0), Direction::East, ComparatorMode::Subtract)
+            .block((4, 0, 0), BlockKind::Torch { lit: false, facing: Direction::Up, burnout_counter: 7, burnout_cooldown: 3, burned_out: true })
+            .block((5, 0, 0), BlockKind::NoteBlock { instrument: Instrument::IronXylophone, pitch: 24, triggered: true, powered: true })
+            .block((6, 0, 0), BlockKind::Door { open: true, hinge: HingePosition::Right, facing: Direction::South, half: DoorHalf::Upper })
+            .block((7, 0, 0), BlockKind::PressurePlate { powered: true, kind: PlateKind::LightWeighted, power: 6 })
+            .block((8, 0, 0), BlockKind::Bell {
+                attachment: BellAttachment::DoubleWall,
+                facing: Direction::West,
+                ringing: true,
+                powered: true,
+                ticks_remaining: 12,
+            })
+            .block((9, -4, 2), BlockKind::CopperBulb { lit: true, powered: false, oxidation: OxidationLevel::Weathered })
+            .build()
+            .unwrap();
+        assert_eq!(world.blocks.len(), 10);
+
+        let text = world.to_csv();
+        assert_eq!(text.lines().count(), 11);
+        assert_eq!(World::from_csv(&text).unwrap(), world);
+    }
+
+    #[test]
+    fn reads_a_hand_written_table() {
+        let text = "x, y, z, type, facing, on_or_power\n\
+                    0, 0, 0, lever, east, false\n\
+                    1, 0, 0, dust, , 0\n\
+                    2, 0, 0, lamp, , false\n";
+        let world = World::from_csv(text).unwrap();
+        assert_eq!(world.blocks.len(), 3);
+        assert_eq!(world.blocks[1].kind, BlockKind::Dust { power: 0, connections: [false; 4] });
+
+        assert_eq!(World::from_csv("x,y,type\n"), Err(CsvError::MissingColumn("z")));
+        assert_eq!(
+            World::from_csv("x,y,z,type\n0,0,0,glass\n"),
+            Err(CsvError::UnknownType { row: 1, name: "glass".into() })
+        );
+        assert_eq!(
+            World::from_csv("x,y,z,type,facing,on_or_power\n0,0,0,lever,,true\n"),
+            Err(CsvError::InvalidField { row: 1, column: "facing", value: String::new() })
+        );
+    }
+}
@@ -0,0 +1,149 @@
+// src/ffi.rs
+
+// C ABI for embedding the simulator in other toolchains (C, C# P/Invoke,
+// Go cgo, ...). Only built with the `ffi` feature; `redstonesim.h` is
+// generated from this file by cbindgen (`make header`). Strings in and out
+// are NUL-terminated UTF-8 JSON, the same JSON the Python functions use.
+
+use crate::{simulate, SimError, SimRequest, World};
+use std::ffi::{c_char, c_int, CStr, CString};
+
+/// The call succeeded and the output holds the result.
+pub const REDSTONESIM_OK: c_int = 0;
+/// A pointer was null or the input was not UTF-8. No output is written.
+pub const REDSTONESIM_ERR_ARGUMENT: c_int = 1;
+/// The input JSON could not be parsed. The output holds `{"error": "..."}`.
+pub const REDSTONESIM_ERR_PARSE: c_int = 2;
+/// The world cannot be simulated. The output holds `{"error": "..."}`.
+pub const REDSTONESIM_ERR_SIMULATION: c_int = 3;
+
+unsafe fn read_input<'a>(ptr: *const c_char) -> Option<&'a str> {
+    if ptr.is_null() {
+        return None;
+    }
+    CStr::from_ptr(ptr).to_str().ok()
+}
+
+// serde_json escapes control characters, so the JSON never contains a NUL
+unsafe fn write_output(out: *mut *mut c_char, json: String) {
+    *out = CString::new(json).map_or(std::ptr::null_mut(), CString::into_raw);
+}
+
+unsafe fn write_error(out: *mut *mut c_char, e: SimError) -> c_int {
+    let code = match e {
+        SimError::DeserializationFailed(_) => REDSTONESIM_ERR_PARSE,
+        _ => REDSTONESIM_ERR_SIMULATION,
+    };
+    write_output(out, serde_json::json!({ "error": e.to_string() }).to_string());
+    code
+}
+
+/// Simulate a `SimRequest` given as JSON. On success `*out_json` receives
+/// the `SimResponse` as JSON; on a parse or simulation error it receives
+/// `{"error": "..."}`. Returns one of the `REDSTONESIM_*` codes.
+///
+/// # Safety
+/// `request_json` must be null or a valid NUL-terminated string, and
+/// `out_json` must be null or valid for a write. A string written to
+/// `*out_json` must be released with `redstonesim_free`.
+#[no_mangle]
+pub unsafe extern "C" fn redstonesim_simulate(request_json: *const c_char, out_json: *mut *mut c_char) -> c_int {
+    let Some(input) = read_input(request_json).filter(|_| !out_json.is_null()) else {
+        return REDSTONESIM_ERR_ARGUMENT;
+    };
+    let result = serde_json::from_str::<SimRequest>(input)
+        .map_err(|e| SimError::DeserializationFailed(e.to_string()))
+        .and_then(simulate);
+    match result {
+        Ok(resp) => match serde_json::to_string(&resp) {
+            Ok(json) => {
+                write_output(out_json, json);
+                REDSTONESIM_OK
+            }
+            Err(e) => write_error(out_json, SimError::DeserializationFailed(e.to_string())),
+        },
+        Err(e) => write_error(out_json, e),
+    }
+}
+
+/// Check a `World` given as JSON. On success `*out_warnings` receives a JSON
+/// array of wiring warnings (see `World::validate_wiring`), empty if none.
+/// A world that cannot be simulated at all returns
+/// `REDSTONESIM_ERR_SIMULATION`.
+///
+/// # Safety
+/// Same as `redstonesim_simulate`.
+#[no_mangle]
+pub unsafe extern "C" fn redstonesim_validate(world_json: *const c_char, out_warnings: *mut *mut c_char) -> c_int {
+    let Some(input) = read_input(world_json).filter(|_| !out_warnings.is_null()) else {
+        return REDSTONESIM_ERR_ARGUMENT;
+    };
+    let world = match serde_json::from_str::<World>(input) {
+        Ok(world) => world,
+        Err(e) => return write_error(out_warnings, SimError::DeserializationFailed(e.to_string())),
+    };
+    if let Err(e) = world.validate() {
+        return write_error(out_warnings, SimError::from(e));
+    }
+    match serde_json::to_string(&world.validate_wiring()) {
+        Ok(json) => {
+            write_output(out_warnings, json);
+            REDSTONESIM_OK
+        }
+        Err(e) => write_error(out_warnings, SimError::DeserializationFailed(e.to_string())),
+    }
+}
+
+/// Release a string returned by this library. Null is ignored.
+///
+/// # Safety
+/// `ptr` must be null or a string written by a `redstonesim_*` function
+/// that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn redstonesim_free(ptr: *mut c_char) {
+    if !ptr.is_null() {
+        drop(CString::from_raw(ptr));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(f: unsafe extern "C" fn(*const c_char, *mut *mut c_char) -> c_int, input: &str) -> (c_int, String) {
+        let input = CString::new(input).unwrap();
+        let mut out: *mut c_char = std::ptr::null_mut();
+        unsafe {
+            let code = f(input.as_ptr(), &mut out);
+            let text = CStr::from_ptr(out).to_str().unwrap().to_string();
+            redstonesim_free(out);
+            (code, text)
+        }
+    }
+
+    #[test]
+    fn simulate_and_validate_through_c_abi() {
+        let world = r#"{"blocks": [
+            {"x": 0, "y": 0, "z": 0, "type": "lever", "on": true, "facing": "east"},
+            {"x": 1, "y": 0, "z": 0, "type": "lamp", "on": false}
+        ]}"#;
+        let (code, json) = call(redstonesim_simulate, &format!(r#"{{"ticks": 5, "world": {}}}"#, world));
+        assert_eq!(code, REDSTONESIM_OK);
+        assert!(json.contains("\"terminated\""));
+        assert_eq!(call(redstonesim_validate, world), (REDSTONESIM_OK, "[]".to_string()));
+
+        let (code, json) = call(redstonesim_simulate, "{not json");
+        assert_eq!(code, REDSTONESIM_ERR_PARSE);
+        assert!(json.starts_with("{\"error\""));
+        let overlapping = r#"{"blocks": [
+            {"x": 0, "y": 0, "z": 0, "type": "lamp", "on": false},
+            {"x": 0, "y": 0, "z": 0, "type": "lamp", "on": false}
+        ]}"#;
+        assert_eq!(call(redstonesim_validate, overlapping).0, REDSTONESIM_ERR_SIMULATION);
+
+        let mut out: *mut c_char = std::ptr::null_mut();
+        let code = unsafe { redstonesim_simulate(std::ptr::null(), &mut out) };
+        assert_eq!((code, out.is_null()), (REDSTONESIM_ERR_ARGUMENT, true));
+        unsafe { redstonesim_free(std::ptr::null_mut()) };
+    }
+}
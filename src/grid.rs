@@ -0,0 +1,71 @@
+// src/grid.rs
+
+// Hash maps keyed by position. The standard SipHash is built to resist
+// hash flooding, which block coordinates do not need; a multiplicative
+// hash over the three integers is several times cheaper.
+
+use crate::Pos;
+use core::hash::{BuildHasherDefault, Hasher};
+#[cfg(not(feature = "std"))]
+pub(crate) use hashbrown::{HashMap, HashSet};
+#[cfg(feature = "std")]
+pub(crate) use std::collections::{HashMap, HashSet};
+
+const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+fn mix(state: u64, word: u64) -> u64 {
+    (state.rotate_left(5) ^ word).wrapping_mul(SEED)
+}
+
+/// Multiplicative hash of a position: each coordinate is folded in and the
+/// state multiplied by an odd constant, so nearby positions spread over
+/// the whole range. Equal to hashing `pos` with `PosHasher`.
+pub fn optimal_hash_for_pos(pos: Pos) -> u64 {
+    [pos.x, pos.y, pos.z].into_iter().fold(0, |h, c| mix(h, c as u32 as u64))
+}
+
+/// Hasher behind `SpatialGrid`. Integer writes cost one multiply each.
+#[derive(Clone, Copy, Default)]
+pub struct PosHasher(u64);
+
+impl Hasher for PosHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 = mix(self.0, *b as u64);
+        }
+    }
+
+    fn write_i32(&mut self, i: i32) {
+        self.0 = mix(self.0, i as u32 as u64);
+    }
+}
+
+pub type BuildPosHasher = BuildHasherDefault<PosHasher>;
+
+/// `HashMap` keyed by `Pos` using `PosHasher`. Create one with
+/// `SpatialGrid::default()` or by collecting.
+pub type SpatialGrid<V> = HashMap<Pos, V, BuildPosHasher>;
+
+/// `HashSet` of positions using `PosHasher`.
+pub type SpatialSet = HashSet<Pos, BuildPosHasher>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::hash::BuildHasher;
+
+    #[test]
+    fn hasher_matches_direct_hash() {
+        for p in [(0, 0, 0), (1, -64, 3), (-30_000_000, 319, 30_000_000)].map(Pos::from) {
+            assert_eq!(BuildPosHasher::default().hash_one(p), optimal_hash_for_pos(p));
+        }
+        // neighbouring positions must not collide
+        let hashes: SpatialSet = Pos::from((0, 0, 0)).neighbors().map(|(n, _)| n).into_iter().collect();
+        let distinct: HashSet<u64> = hashes.iter().map(|p| optimal_hash_for_pos(*p)).collect();
+        assert_eq!(distinct.len(), 6);
+    }
+}
@@ -14,21 +14,182 @@
 // serde_json = "1.0"
 //
 // =================================================
+// Without the default `std` feature the core simulator builds as `no_std`
+// with `alloc` (see examples/no_std_demo.rs).
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::collections::VecDeque;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::vec;
+pub use grid::{optimal_hash_for_pos, SpatialGrid, SpatialSet};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
 
 // -------------------------------------------------
 // Position
 // -------------------------------------------------
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 pub struct Pos {
     pub x: i32,
     pub y: i32,
     pub z: i32,
 }
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+impl Pos {
+    /// Taxicab distance between two positions.
+    pub fn manhattan_distance(self, other: Pos) -> u32 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+
+    /// Square of the straight-line distance; exact for any two positions
+    /// inside the world border, so range checks that compare it against a
+    /// squared radius need no floating point.
+    pub fn distance_squared(self, other: Pos) -> i64 {
+        let d = |a: i32, b: i32| (i64::from(a) - i64::from(b)).pow(2);
+        d(self.x, other.x) + d(self.y, other.y) + d(self.z, other.z)
+    }
+
+    /// Straight-line distance. Needs `std` for the square root.
+    #[cfg(feature = "std")]
+    pub fn euclidean_distance(self, other: Pos) -> f64 {
+        (self.distance_squared(other) as f64).sqrt()
+    }
+
+    /// Distance counted in king moves: the largest difference on any axis.
+    pub fn chebyshev_distance(self, other: Pos) -> i32 {
+        (self.x - other.x).abs().max((self.y - other.y).abs()).max((self.z - other.z).abs())
+    }
+
+    /// Whether `other` is no further than `radius` in a straight line
+    /// (the sphere includes its surface).
+    pub fn within_radius(self, other: Pos, radius: f64) -> bool {
+        self.distance_squared(other) as f64 <= radius * radius
+    }
+
+    /// The six face‑adjacent positions, in `Direction::all()` order, each
+    /// with the direction from `self` to it.
+    pub fn neighbors(self) -> [(Pos, Direction); 6] {
+        Direction::all().map(|d| (self.neighbor_in(d), d))
+    }
+
+    /// The adjacent position one step in direction `dir`.
+    pub fn neighbor_in(self, dir: Direction) -> Pos {
+        let (dx, dy, dz) = dir.offset();
+        Pos { x: self.x + dx, y: self.y + dy, z: self.z + dz }
+    }
+}
+
+impl From<(i32, i32, i32)> for Pos {
+    fn from((x, y, z): (i32, i32, i32)) -> Self {
+        Pos { x, y, z }
+    }
+}
+
+impl From<Pos> for (i32, i32, i32) {
+    fn from(p: Pos) -> Self {
+        (p.x, p.y, p.z)
+    }
+}
+
+impl core::ops::Add<(i32, i32, i32)> for Pos {
+    type Output = Pos;
+    fn add(self, (dx, dy, dz): (i32, i32, i32)) -> Pos {
+        Pos { x: self.x + dx, y: self.y + dy, z: self.z + dz }
+    }
+}
+
+impl core::ops::Sub<(i32, i32, i32)> for Pos {
+    type Output = Pos;
+    fn sub(self, (dx, dy, dz): (i32, i32, i32)) -> Pos {
+        Pos { x: self.x - dx, y: self.y - dy, z: self.z - dz }
+    }
+}
+
+/// One step in direction `d`.
+impl core::ops::Add<Direction> for Pos {
+    type Output = Pos;
+    fn add(self, d: Direction) -> Pos {
+        self.neighbor_in(d)
+    }
+}
+
+impl core::ops::Neg for Pos {
+    type Output = Pos;
+    fn neg(self) -> Pos {
+        Pos { x: -self.x, y: -self.y, z: -self.z }
+    }
+}
+
+impl core::fmt::Display for Pos {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "({},{},{})", self.x, self.y, self.z)
+    }
+}
+
+/// Error from parsing a `Pos`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PosParseError {
+    WrongCount(usize),      // number of coordinates found, when it is not 3
+    BadCoordinate(String),  // not an `i32`
+    BadAxis(String),        // `name=` that is not x, y or z, or names one twice
+}
+
+impl core::fmt::Display for PosParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PosParseError::WrongCount(n) => write!(f, "expected 3 coordinates x,y,z, found {}", n),
+            PosParseError::BadCoordinate(s) => write!(f, "coordinate {:?} is not an integer", s),
+            PosParseError::BadAxis(s) => write!(f, "axis {:?} is not x, y or z, or appears twice", s),
+        }
+    }
+}
+
+impl core::error::Error for PosParseError {}
+
+/// Accepts `1,2,3`, `(1, 2, 3)`, `1 2 3` and `x=1 y=2 z=3`, so the
+/// `Display` form parses back.
+impl core::str::FromStr for Pos {
+    type Err = PosParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let s = s.strip_prefix('(').and_then(|t| t.strip_suffix(')')).unwrap_or(s);
+        let parts: Vec<&str> = s.split(|c: char| c == ',' || c.is_whitespace()).filter(|t| !t.is_empty()).collect();
+        if parts.len() != 3 {
+            return Err(PosParseError::WrongCount(parts.len()));
+        }
+        let coord = |t: &str| t.parse::<i32>().map_err(|_| PosParseError::BadCoordinate(t.to_string()));
+        if !parts.iter().any(|t| t.contains('=')) {
+            return Ok(Pos { x: coord(parts[0])?, y: coord(parts[1])?, z: coord(parts[2])? });
+        }
+        let mut xyz = [None; 3];
+        for part in parts {
+            let (axis, value) = part.split_once('=').ok_or_else(|| PosParseError::BadAxis(part.to_string()))?;
+            let slot = match axis.trim() {
+                "x" | "X" => &mut xyz[0],
+                "y" | "Y" => &mut xyz[1],
+                "z" | "Z" => &mut xyz[2],
+                _ => return Err(PosParseError::BadAxis(axis.to_string())),
+            };
+            if slot.replace(coord(value)?).is_some() {
+                return Err(PosParseError::BadAxis(axis.to_string()));
+            }
+        }
+        match xyz {
+            [Some(x), Some(y), Some(z)] => Ok(Pos { x, y, z }),
+            _ => unreachable!("three distinct axes were assigned"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 #[serde(rename_all = "lowercase")]
 pub enum Direction {
     North,
@@ -62,6 +223,16 @@ impl Direction {
         }
     }
 
+    /// The two horizontal directions 90° left and right of `self`.
+    /// Vertical directions have no sides.
+    fn sides(self) -> Vec<Direction> {
+        match self {
+            Direction::North | Direction::South => vec![Direction::East, Direction::West],
+            Direction::East | Direction::West => vec![Direction::North, Direction::South],
+            Direction::Up | Direction::Down => Vec::new(),
+        }
+    }
+
     fn all() -> [Direction; 6] {
         [
             Direction::East,
@@ -72,17 +243,221 @@ impl Direction {
             Direction::North,
         ]
     }
+
+    /// The four horizontal directions, clockwise from north.
+    pub const fn horizontal_directions() -> [Direction; 4] {
+        [Direction::North, Direction::East, Direction::South, Direction::West]
+    }
+
+    pub const fn vertical_directions() -> [Direction; 2] {
+        [Direction::Up, Direction::Down]
+    }
+
+    pub fn is_horizontal(self) -> bool {
+        !self.is_vertical()
+    }
+
+    pub fn is_vertical(self) -> bool {
+        matches!(self, Direction::Up | Direction::Down)
+    }
+
+    /// Next horizontal direction clockwise (seen from above); `None` for Up/Down.
+    pub fn clockwise(self) -> Option<Direction> {
+        match self {
+            Direction::North => Some(Direction::East),
+            Direction::East => Some(Direction::South),
+            Direction::South => Some(Direction::West),
+            Direction::West => Some(Direction::North),
+            Direction::Up | Direction::Down => None,
+        }
+    }
+
+    /// Next horizontal direction counterclockwise; `None` for Up/Down.
+    pub fn counterclockwise(self) -> Option<Direction> {
+        self.clockwise().map(Direction::opposite)
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Direction::North => "north",
+            Direction::East => "east",
+            Direction::South => "south",
+            Direction::West => "west",
+            Direction::Up => "up",
+            Direction::Down => "down",
+        }
+    }
+}
+
+/// Error from parsing or decoding a `Direction`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DirectionError {
+    UnknownName(String), // not a direction name (any case) or its initial
+    OutOfRange(u8),      // binary codes are 0‑5 in declaration order
+}
+
+impl core::fmt::Display for DirectionError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DirectionError::UnknownName(s) => write!(f, "unknown direction {:?}", s),
+            DirectionError::OutOfRange(n) => write!(f, "direction code {} is out of range", n),
+        }
+    }
+}
+
+impl core::error::Error for DirectionError {}
+
+/// Same lowercase names as the JSON form.
+impl core::fmt::Display for Direction {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Case-insensitive; also takes the initials `n`, `e`, `s`, `w`, `u`, `d`.
+impl core::str::FromStr for Direction {
+    type Err = DirectionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Direction::all()
+            .into_iter()
+            .find(|d| d.name().eq_ignore_ascii_case(s) || d.name()[..1].eq_ignore_ascii_case(s))
+            .ok_or_else(|| DirectionError::UnknownName(s.to_string()))
+    }
+}
+
+/// Decodes the declaration order: north, east, south, west, up, down.
+impl TryFrom<u8> for Direction {
+    type Error = DirectionError;
+
+    fn try_from(n: u8) -> Result<Self, Self::Error> {
+        match n {
+            0 => Ok(Direction::North),
+            1 => Ok(Direction::East),
+            2 => Ok(Direction::South),
+            3 => Ok(Direction::West),
+            4 => Ok(Direction::Up),
+            5 => Ok(Direction::Down),
+            _ => Err(DirectionError::OutOfRange(n)),
+        }
+    }
+}
+
+/// Two positions that were expected to be adjacent but are not.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NonAdjacentError {
+    pub from: Pos,
+    pub to: Pos,
 }
 
+impl core::fmt::Display for NonAdjacentError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "positions are not adjacent: {} -> {}", self.from, self.to)
+    }
+}
+
+impl core::error::Error for NonAdjacentError {}
+
 /// Calculate the `Direction` from one block to an adjacent block.
-fn dir_from_to(from: Pos, to: Pos) -> Direction {
-    for d in Direction::all() {
-        let (dx, dy, dz) = d.offset();
-        if from.x + dx == to.x && from.y + dy == to.y && from.z + dz == to.z {
-            return d;
+fn dir_from_to(from: Pos, to: Pos) -> Result<Direction, NonAdjacentError> {
+    from.neighbors().into_iter().find(|(n, _)| *n == to).map(|(_, d)| d).ok_or(NonAdjacentError { from, to })
+}
+
+fn is_adjacent(a: Pos, b: Pos) -> bool {
+    (a.x - b.x).abs() + (a.y - b.y).abs() + (a.z - b.z).abs() == 1
+}
+
+/// Horizontal directions in the order used by `BlockKind::Dust::connections`.
+const DUST_SIDES: [Direction; 4] =
+    [Direction::North, Direction::East, Direction::South, Direction::West];
+
+/// Whether dust at some position visually connects to `block`, which lies in
+/// direction `dir` from the dust.
+fn dust_connects_to(block: &BlockKind, dir: Direction) -> bool {
+    match block {
+        BlockKind::Dust { .. }
+        | BlockKind::Lever { .. }
+        | BlockKind::Button { .. }
+        | BlockKind::Torch { .. }
+        | BlockKind::Comparator { .. }
+        | BlockKind::RedstoneBlock
+        | BlockKind::DetectorRail { .. }
+        | BlockKind::PressurePlate { .. }
+        | BlockKind::DaylightSensor { .. } => true,
+        BlockKind::Repeater { facing, .. } => *facing == dir || *facing == dir.opposite(),
+        BlockKind::Observer { facing, .. } => *facing == dir,
+        _ => false,
+    }
+}
+
+/// Read access to block storage, so the update rules work on both
+/// `SpatialGrid<BlockKind>` and `ChunkWorld`.
+trait BlockLookup {
+    fn get(&self, pos: &Pos) -> Option<&BlockKind>;
+
+    fn contains_key(&self, pos: &Pos) -> bool {
+        self.get(pos).is_some()
+    }
+}
+
+impl BlockLookup for SpatialGrid<BlockKind> {
+    fn get(&self, pos: &Pos) -> Option<&BlockKind> {
+        <SpatialGrid<BlockKind>>::get(self, pos)
+    }
+}
+
+/// Compute a dust block's horizontal connections from its surroundings.
+/// Dust also connects diagonally to dust one step up or down a slope.
+/// A single connection extends into a straight line; none gives a cross.
+fn dust_connections(world: &impl BlockLookup, pos: Pos) -> [bool; 4] {
+    let above = pos + Direction::Up;
+    let mut conn = [false; 4];
+    for (i, d) in DUST_SIDES.iter().enumerate() {
+        let side = pos + *d;
+        let up = side + Direction::Up;
+        let down = side + Direction::Down;
+        conn[i] = match world.get(&side) {
+            Some(b) => dust_connects_to(b, *d),
+            None => matches!(world.get(&down), Some(BlockKind::Dust { .. })),
+        } || (!world.contains_key(&above) && matches!(world.get(&up), Some(BlockKind::Dust { .. })));
+    }
+    match conn.iter().filter(|c| **c).count() {
+        0 => [true; 4],
+        1 => {
+            let i = conn.iter().position(|c| *c).unwrap();
+            conn[(i + 2) % 4] = true;
+            conn
         }
+        _ => conn,
     }
-    panic!("positions are not adjacent: {:?} -> {:?}", from, to);
+}
+
+/// Connections as they act on power: no connections at all is the
+/// unconnected cross shape.
+fn dust_shape(connections: &[bool; 4]) -> [bool; 4] {
+    if connections.iter().any(|c| *c) {
+        *connections
+    } else {
+        [true; 4]
+    }
+}
+
+/// Positions dust exchanges power with: each connected side (including the
+/// slope positions above and below it) and the block underneath.
+fn dust_positions(pos: Pos, connections: &[bool; 4]) -> Vec<Pos> {
+    let mut out = vec![pos + Direction::Down];
+    for (d, _) in DUST_SIDES.iter().zip(dust_shape(connections)).filter(|(_, c)| *c) {
+        let side = pos + *d;
+        out.push(side);
+        out.push(side + Direction::Up);
+        out.push(side + Direction::Down);
+    }
+    out
+}
+
+/// Whether dust with these connections sends power in direction `dir`.
+fn dust_points(connections: &[bool; 4], dir: Direction) -> bool {
+    dir == Direction::Down || DUST_SIDES.iter().zip(dust_shape(connections)).any(|(d, c)| c && *d == dir)
 }
 
 /// Trait for blocks that know where they accept input from and send output to.
@@ -94,7 +469,248 @@ pub trait Connectable {
 // -------------------------------------------------
 // Block kinds & internal state
 // -------------------------------------------------
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(rename_all = "lowercase")]
+pub enum ComparatorMode {
+    #[default]
+    Compare,  // output = strongest input
+    Subtract, // output = max(0, rear - max(sides))
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(rename_all = "snake_case")]
+pub enum Instrument {
+    Harp,
+    Basedrum,
+    Snare,
+    Hat,
+    Bass,
+    Flute,
+    Bell,
+    Guitar,
+    Chime,
+    Xylophone,
+    IronXylophone,
+    CowBell,
+    Didgeridoo,
+    Bit,
+    Banjo,
+    Pling,
+}
+
+impl Instrument {
+    /// Instrument a note block plays given the block beneath it.
+    pub fn for_block_below(below: Option<&BlockKind>) -> Instrument {
+        // stone‑like blocks give the bass drum; anything else plays the harp
+        match below {
+            Some(
+                BlockKind::OpaqueSolid { .. }
+                | BlockKind::RedstoneBlock
+                | BlockKind::Observer { .. }
+                | BlockKind::Furnace { .. },
+            ) => Instrument::Basedrum,
+            Some(BlockKind::Chest { .. } | BlockKind::Barrel { .. }) => Instrument::Bass,
+            _ => Instrument::Harp,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(rename_all = "snake_case")]
+pub enum RailShape {
+    NorthSouth,
+    EastWest,
+    AscendingEast,
+    AscendingWest,
+    AscendingNorth,
+    AscendingSouth,
+    SouthEast,
+    SouthWest,
+    NorthWest,
+    NorthEast,
+}
+
+impl RailShape {
+    /// The two directions the rail connects in, each with the height offset
+    /// of the rail it connects to there.
+    fn ends(self) -> [(Direction, i32); 2] {
+        use Direction::*;
+        match self {
+            RailShape::NorthSouth => [(North, 0), (South, 0)],
+            RailShape::EastWest => [(East, 0), (West, 0)],
+            RailShape::AscendingEast => [(East, 1), (West, 0)],
+            RailShape::AscendingWest => [(West, 1), (East, 0)],
+            RailShape::AscendingNorth => [(North, 1), (South, 0)],
+            RailShape::AscendingSouth => [(South, 1), (North, 0)],
+            RailShape::SouthEast => [(South, 0), (East, 0)],
+            RailShape::SouthWest => [(South, 0), (West, 0)],
+            RailShape::NorthWest => [(North, 0), (West, 0)],
+            RailShape::NorthEast => [(North, 0), (East, 0)],
+        }
+    }
+
+    /// The shape after mapping both ends through `f` (a rotation or mirror).
+    fn transformed(self, f: impl Fn(Direction) -> Direction) -> RailShape {
+        const ALL: [RailShape; 10] = [
+            RailShape::NorthSouth,
+            RailShape::EastWest,
+            RailShape::AscendingEast,
+            RailShape::AscendingWest,
+            RailShape::AscendingNorth,
+            RailShape::AscendingSouth,
+            RailShape::SouthEast,
+            RailShape::SouthWest,
+            RailShape::NorthWest,
+            RailShape::NorthEast,
+        ];
+        let [a, b] = self.ends().map(|(d, dy)| (f(d), dy));
+        ALL.into_iter().find(|s| s.ends() == [a, b] || s.ends() == [b, a]).unwrap_or(self)
+    }
+
+    /// Positions a rail of this shape at `pos` can link to: the rail at each
+    /// end, or the one below it when that rail ascends towards us.
+    fn linked_positions(self, pos: Pos) -> Vec<Pos> {
+        let mut out = Vec::new();
+        for (d, dy) in self.ends() {
+            let end = pos + d;
+            out.push(end + (0, dy, 0));
+            if dy == 0 {
+                out.push(end + Direction::Down);
+            }
+        }
+        out
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(rename_all = "snake_case")]
+pub enum PlateKind {
+    Stone,         // 15 while a mob or player stands on it
+    Wood,          // 15 while any entity, including items, is on it
+    LightWeighted, // one level per entity, up to 15
+    HeavyWeighted, // one level per 10 entities, up to 15
+}
+
+impl PlateKind {
+    /// Output level of a plate of this kind under the given load.
+    pub fn power_for(self, pressure: &EntityPressure) -> u8 {
+        let total = pressure.mobs + pressure.items;
+        match self {
+            PlateKind::Stone if pressure.mobs > 0 => 15,
+            PlateKind::Wood if total > 0 => 15,
+            PlateKind::LightWeighted => total.min(15) as u8,
+            PlateKind::HeavyWeighted => total.div_ceil(10).min(15) as u8,
+            _ => 0,
+        }
+    }
+}
+
+/// Entities standing on a pressure plate.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+pub struct EntityPressure {
+    #[serde(default)]
+    pub mobs: u32, // players and mobs
+    #[serde(default)]
+    pub items: u32, // dropped items and other non‑living entities
+}
+
+/// Length of a Minecraft day in game ticks.
+const DAY_TICKS: i64 = 24_000;
+
+/// Most tripwire blocks a line between two hooks can have, as in vanilla.
+const TRIPWIRE_MAX_WIRES: usize = 40;
+
+/// How far power travels along a line of powered or activator rails.
+const RAIL_POWER_RANGE: usize = 8;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(rename_all = "lowercase")]
+pub enum HingePosition {
+    Left,
+    Right,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(rename_all = "lowercase")]
+pub enum DoorHalf {
+    Upper,
+    Lower,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(rename_all = "lowercase")]
+pub enum TrapdoorHalf {
+    Top,
+    Bottom,
+}
+
+/// How far a copper block has weathered.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(rename_all = "lowercase")]
+pub enum OxidationLevel {
+    #[default]
+    Unoxidized,
+    Exposed,
+    Weathered,
+    Oxidized,
+}
+
+impl OxidationLevel {
+    /// Light a lit copper bulb gives off at this stage, as in vanilla.
+    pub fn light_level(self) -> u8 {
+        match self {
+            OxidationLevel::Unoxidized => 15,
+            OxidationLevel::Exposed => 12,
+            OxidationLevel::Weathered => 8,
+            OxidationLevel::Oxidized => 4,
+        }
+    }
+}
+
+/// What a bell hangs from.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
+#[serde(rename_all = "snake_case")]
+pub enum BellAttachment {
+    Floor,      // standing on the block below
+    Ceiling,    // hanging from the block above
+    SingleWall, // bracket on the wall behind it
+    DoubleWall, // bracket between two walls
+}
+
+impl BellAttachment {
+    /// Faces a bell can be struck on: along `facing` for floor and ceiling
+    /// bells, across it for wall bells.
+    pub fn strike_faces(self, facing: Direction) -> Vec<Direction> {
+        match self {
+            BellAttachment::Floor | BellAttachment::Ceiling => vec![facing, facing.opposite()],
+            BellAttachment::SingleWall | BellAttachment::DoubleWall => facing.sides(),
+        }
+    }
+}
+
+/// Sorts by variant in declaration order, then field by field.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[cfg_attr(feature = "proptest", derive(proptest_derive::Arbitrary))]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum BlockKind {
     Lever {
@@ -106,8 +722,10 @@ pub enum BlockKind {
         facing: Direction,
     }, // keeps signal while > 0
     Dust {
-        power: u8,
-    }, // 0 = off, 1‑15 = power level
+        power: u8, // 0 = off, 1‑15 = power level
+        #[serde(default)]
+        connections: [bool; 4], // North, East, South, West
+    },
     Lamp {
         on: bool,
     },
@@ -116,90 +734,526 @@ pub enum BlockKind {
         ticks_remaining: u8, // countdown until output
         powered: bool,       // current output state
         facing: Direction,
+        #[serde(default)]
+        locked: bool, // side‑powered by a repeater/comparator; output frozen
     },
     Comparator {
         output: u8, // current output power
         facing: Direction,
+        #[serde(default)]
+        mode: ComparatorMode,
     },
     Torch {
         lit: bool,
         facing: Direction,
+        #[serde(default)]
+        burnout_counter: u8, // recent toggles, forgotten one every few ticks
+        #[serde(default)]
+        burnout_cooldown: u8, // ticks until the next toggle is forgotten, or burnout ends
+        #[serde(default)]
+        burned_out: bool, // toggled too fast; dark until the cooldown runs out
     },
     Piston {
         extended: bool,
         facing: Direction,
+        #[serde(default)]
+        sticky: bool, // pulls the block in front back when retracting
+    },
+    PistonArm {
+        facing: Direction, // transient head placed in front of an extending piston
     },
     Hopper {
         enabled: bool,
         facing: Direction,
+        #[serde(default)]
+        items: u8, // 0‑5; one item moves to the faced container per enabled tick
+    },
+    Observer {
+        facing: Direction, // face that watches; output leaves the opposite face
+        powered: bool,     // true for exactly one tick after a watched change
+    },
+    OpaqueSolid {
+        strong_power: u8, // strongest direct source pointing into the block
+    },
+    RedstoneBlock, // always emits 15 on every face; never changes
+    Target {
+        power: u8,           // power set by the last hit
+        ticks_remaining: u8, // keeps `power` while > 0
+    },
+    SculkSensor {
+        power: u8,           // frequency of the last detected vibration
+        ticks_remaining: u8, // active (and deaf to new vibrations) while > 0
+        waterlogged: bool,
+    },
+    CalibratedSculkSensor {
+        power: u8,
+        ticks_remaining: u8,
+        waterlogged: bool,
+        facing: Direction, // front face; the other horizontal faces read the filter signal
+    },
+    Door {
+        open: bool,
+        hinge: HingePosition,
+        facing: Direction,
+        half: DoorHalf, // the two halves are stacked and open together
+    },
+    Trapdoor {
+        open: bool,
+        facing: Direction,
+        half: TrapdoorHalf,
+    },
+    NoteBlock {
+        instrument: Instrument,
+        pitch: u8,      // 0‑24
+        triggered: bool, // true for the tick a note is played
+        #[serde(default)]
+        powered: bool, // input state last tick, for rising‑edge detection
+    },
+    PoweredRail {
+        powered: bool, // powered directly or through up to 8 linked rails
+        shape: RailShape,
+    },
+    DetectorRail {
+        powered: bool, // a minecart is on the rail
+        shape: RailShape,
+    },
+    ActivatorRail {
+        powered: bool,
+        shape: RailShape,
+    },
+    PressurePlate {
+        powered: bool,
+        kind: PlateKind,
+        #[serde(default)]
+        power: u8, // output level while powered (weighted plates vary)
+    },
+    Dropper {
+        facing: Direction,
+        powered: bool, // input state last tick, for rising‑edge detection
+        #[serde(default)]
+        cooldown: u8, // ticks until it can fire again
+    },
+    Dispenser {
+        facing: Direction,
+        powered: bool,
+        #[serde(default)]
+        cooldown: u8,
+    },
+    Chest {
+        items: u8, // 0‑max_items; passive container, read by comparators
+        #[serde(default = "default_chest_slots")]
+        max_items: u8, // 27, or 54 for a double chest
+    },
+    Barrel {
+        items: u8, // 0‑max_items; reads like a chest
+        #[serde(default = "default_chest_slots")]
+        max_items: u8, // 27, or 54 like a double chest
+    },
+    Furnace {
+        fuel_ticks: u32, // burn time left; comparators read it against FURNACE_MAX_FUEL
+        cooking: bool,   // burning, one fuel tick per tick; goes out at 0
+    },
+    TripwireHook {
+        attached: bool, // an unbroken line of tripwire reaches a hook facing back
+        powered: bool,  // a wire on the line is tripped, or the line just broke
+        facing: Direction, // towards the line
+    },
+    Tripwire {
+        powered: bool, // an entity is on the wire
+    },
+    DaylightSensor {
+        inverted: bool, // strongest at midnight instead of noon
+        power: u8,      // set from the time of day every tick
+    },
+    LightningRod {
+        powered: bool, // struck by lightning in the last few ticks
+        #[serde(default)]
+        ticks_remaining: u8, // keeps `powered` while > 0
+    },
+    CopperBulb {
+        lit: bool,     // flips on each rising edge of its input
+        powered: bool, // input state last tick, for rising‑edge detection
+        #[serde(default)]
+        oxidation: OxidationLevel, // only changes how bright it is
+    },
+    Crafter {
+        powered: bool, // input state last tick, for rising‑edge detection
+        #[serde(default)]
+        cooldown: u8, // ticks until it can craft again
+        #[serde(default)]
+        craft_count: u32, // items crafted so far; comparators read it mod 16
+    },
+    Bell {
+        attachment: BellAttachment,
+        facing: Direction, // redstone swings it this way
+        ringing: bool,
+        #[serde(default)]
+        powered: bool, // input state last tick, for rising‑edge detection
+        #[serde(default)]
+        ticks_remaining: u8, // keeps `ringing` while > 0
+    },
+    FenceGate {
+        open: bool,
+        facing: Direction,
+        in_wall: bool, // lowered to line up with a wall; no effect on redstone
     },
 }
 
-impl Connectable for BlockKind {
-    fn input_positions(&self, pos: Pos) -> Vec<Pos> {
+/// Unpowered dust with no connections.
+impl Default for BlockKind {
+    fn default() -> Self {
+        BlockKind::Dust { power: 0, connections: [false; 4] }
+    }
+}
+
+impl BlockKind {
+    /// The `type` tag this block uses in JSON, e.g. `"lever"`.
+    pub fn type_name(&self) -> &'static str {
         match self {
-            BlockKind::Lever { .. } | BlockKind::Button { .. } => Vec::new(),
-            BlockKind::Dust { .. }
-            | BlockKind::Lamp { .. }
-            | BlockKind::Piston { .. }
-            | BlockKind::Hopper { .. }
-            | BlockKind::Comparator { .. } => Direction::all()
-                .iter()
-                .map(|d| {
-                    let (dx, dy, dz) = d.offset();
-                    Pos { x: pos.x + dx, y: pos.y + dy, z: pos.z + dz }
-                })
-                .collect(),
-            BlockKind::Repeater { facing, .. } => {
-                let back = facing.opposite();
-                let (dx, dy, dz) = back.offset();
-                vec![Pos { x: pos.x + dx, y: pos.y + dy, z: pos.z + dz }]
-            }
-            BlockKind::Torch { facing, .. } => {
-                let (dx, dy, dz) = facing.offset();
-                vec![Pos { x: pos.x + dx, y: pos.y + dy, z: pos.z + dz }]
-            }
+            BlockKind::Lever { .. } => "lever",
+            BlockKind::Button { .. } => "button",
+            BlockKind::Dust { .. } => "dust",
+            BlockKind::Lamp { .. } => "lamp",
+            BlockKind::Repeater { .. } => "repeater",
+            BlockKind::Comparator { .. } => "comparator",
+            BlockKind::Torch { .. } => "torch",
+            BlockKind::Piston { .. } => "piston",
+            BlockKind::PistonArm { .. } => "pistonarm",
+            BlockKind::Hopper { .. } => "hopper",
+            BlockKind::Observer { .. } => "observer",
+            BlockKind::OpaqueSolid { .. } => "opaquesolid",
+            BlockKind::RedstoneBlock => "redstoneblock",
+            BlockKind::Target { .. } => "target",
+            BlockKind::SculkSensor { .. } => "sculksensor",
+            BlockKind::CalibratedSculkSensor { .. } => "calibratedsculksensor",
+            BlockKind::Door { .. } => "door",
+            BlockKind::Trapdoor { .. } => "trapdoor",
+            BlockKind::NoteBlock { .. } => "noteblock",
+            BlockKind::PoweredRail { .. } => "poweredrail",
+            BlockKind::DetectorRail { .. } => "detectorrail",
+            BlockKind::ActivatorRail { .. } => "activatorrail",
+            BlockKind::PressurePlate { .. } => "pressureplate",
+            BlockKind::Dropper { .. } => "dropper",
+            BlockKind::Dispenser { .. } => "dispenser",
+            BlockKind::Chest { .. } => "chest",
+            BlockKind::Barrel { .. } => "barrel",
+            BlockKind::Furnace { .. } => "furnace",
+            BlockKind::TripwireHook { .. } => "tripwirehook",
+            BlockKind::Tripwire { .. } => "tripwire",
+            BlockKind::DaylightSensor { .. } => "daylightsensor",
+            BlockKind::LightningRod { .. } => "lightningrod",
+            BlockKind::CopperBulb { .. } => "copperbulb",
+            BlockKind::Crafter { .. } => "crafter",
+            BlockKind::Bell { .. } => "bell",
+            BlockKind::FenceGate { .. } => "fencegate",
         }
     }
 
-    fn output_positions(&self, pos: Pos) -> Vec<Pos> {
+    /// Same as [`type_name`](Self::type_name).
+    pub fn name(&self) -> &'static str {
+        self.type_name()
+    }
+
+    /// Blocks that make power of their own accord (a player, an entity, a
+    /// projectile or the sun switches them) rather than passing on an input:
+    /// levers, buttons, torches, redstone blocks, observers, targets, sculk
+    /// sensors, detector rails, pressure plates, tripwire hooks, daylight
+    /// sensors and lightning rods.
+    pub fn is_power_source(&self) -> bool {
+        matches!(
+            self,
+            BlockKind::Lever { .. }
+                | BlockKind::Button { .. }
+                | BlockKind::Torch { .. }
+                | BlockKind::RedstoneBlock
+                | BlockKind::Observer { .. }
+                | BlockKind::Target { .. }
+                | BlockKind::SculkSensor { .. }
+                | BlockKind::CalibratedSculkSensor { .. }
+                | BlockKind::DetectorRail { .. }
+                | BlockKind::PressurePlate { .. }
+                | BlockKind::TripwireHook { .. }
+                | BlockKind::DaylightSensor { .. }
+                | BlockKind::LightningRod { .. }
+        )
+    }
+
+    /// Blocks redstone acts on that never power anything in turn (lamps,
+    /// pistons, hoppers, doors, ...). Some still feed a comparator.
+    pub fn is_output(&self) -> bool {
+        matches!(
+            self,
+            BlockKind::Lamp { .. }
+                | BlockKind::Piston { .. }
+                | BlockKind::Hopper { .. }
+                | BlockKind::Door { .. }
+                | BlockKind::Trapdoor { .. }
+                | BlockKind::NoteBlock { .. }
+                | BlockKind::PoweredRail { .. }
+                | BlockKind::ActivatorRail { .. }
+                | BlockKind::Dropper { .. }
+                | BlockKind::Dispenser { .. }
+                | BlockKind::CopperBulb { .. }
+                | BlockKind::Crafter { .. }
+                | BlockKind::Bell { .. }
+                | BlockKind::FenceGate { .. }
+        )
+    }
+
+    /// Blocks that keep a countdown between ticks: button presses, repeater
+    /// delays, torch burnout, cooldowns and the like.
+    pub fn is_timer(&self) -> bool {
+        matches!(
+            self,
+            BlockKind::Button { .. }
+                | BlockKind::Repeater { .. }
+                | BlockKind::Torch { .. }
+                | BlockKind::Target { .. }
+                | BlockKind::SculkSensor { .. }
+                | BlockKind::CalibratedSculkSensor { .. }
+                | BlockKind::Dropper { .. }
+                | BlockKind::Dispenser { .. }
+                | BlockKind::LightningRod { .. }
+                | BlockKind::Crafter { .. }
+                | BlockKind::Bell { .. }
+                | BlockKind::Furnace { .. }
+        )
+    }
+
+    /// Power the block currently carries: [`power_level`], except that a lit
+    /// lamp or copper bulb, which emit nothing, reads 15.
+    pub fn current_power(&self) -> u8 {
         match self {
+            BlockKind::Lamp { on: true } | BlockKind::CopperBulb { lit: true, .. } => 15,
+            _ => power_level(self),
+        }
+    }
+
+    /// Copy of the block with every direction mapped through `f`; `mirrored`
+    /// also swaps door hinges. Used by `World::rotate` and `World::mirror`.
+    fn transformed(&self, f: impl Fn(Direction) -> Direction, mirrored: bool) -> BlockKind {
+        let mut kind = self.clone();
+        match &mut kind {
             BlockKind::Lever { facing, .. }
             | BlockKind::Button { facing, .. }
             | BlockKind::Repeater { facing, .. }
-            | BlockKind::Comparator { facing, .. } => {
-                let (dx, dy, dz) = facing.offset();
-                vec![Pos { x: pos.x + dx, y: pos.y + dy, z: pos.z + dz }]
+            | BlockKind::Comparator { facing, .. }
+            | BlockKind::Torch { facing, .. }
+            | BlockKind::Piston { facing, .. }
+            | BlockKind::PistonArm { facing }
+            | BlockKind::Hopper { facing, .. }
+            | BlockKind::Observer { facing, .. }
+            | BlockKind::CalibratedSculkSensor { facing, .. }
+            | BlockKind::Trapdoor { facing, .. }
+            | BlockKind::Dropper { facing, .. }
+            | BlockKind::Dispenser { facing, .. }
+            | BlockKind::TripwireHook { facing, .. }
+            | BlockKind::Bell { facing, .. }
+            | BlockKind::FenceGate { facing, .. } => *facing = f(*facing),
+            BlockKind::Door { facing, hinge, .. } => {
+                *facing = f(*facing);
+                if mirrored {
+                    *hinge = match hinge {
+                        HingePosition::Left => HingePosition::Right,
+                        HingePosition::Right => HingePosition::Left,
+                    };
+                }
             }
-            BlockKind::Torch { facing, .. } => Direction::all()
-                .iter()
-                .filter_map(|d| {
-                    if *d == *facing {
-                        None
-                    } else {
-                        let (dx, dy, dz) = d.offset();
-                        Some(Pos { x: pos.x + dx, y: pos.y + dy, z: pos.z + dz })
-                    }
-                })
-                .collect(),
-            BlockKind::Dust { .. } => Direction::all()
-                .iter()
-                .map(|d| {
-                    let (dx, dy, dz) = d.offset();
-                    Pos { x: pos.x + dx, y: pos.y + dy, z: pos.z + dz }
-                })
+            BlockKind::Dust { connections, .. } => {
+                let dirs = Direction::horizontal_directions();
+                let old = *connections;
+                for (i, d) in dirs.into_iter().enumerate() {
+                    let j = dirs.iter().position(|n| *n == f(d)).unwrap();
+                    connections[j] = old[i];
+                }
+            }
+            BlockKind::PoweredRail { shape, .. }
+            | BlockKind::DetectorRail { shape, .. }
+            | BlockKind::ActivatorRail { shape, .. } => *shape = shape.transformed(&f),
+            _ => {}
+        }
+        kind
+    }
+}
+
+impl Connectable for BlockKind {
+    fn input_positions(&self, pos: Pos) -> Vec<Pos> {
+        match self {
+            BlockKind::Lever { .. }
+            | BlockKind::Button { .. }
+            | BlockKind::RedstoneBlock
+            | BlockKind::PistonArm { .. }
+            | BlockKind::Target { .. }
+            | BlockKind::SculkSensor { .. }
+            | BlockKind::DetectorRail { .. }
+            | BlockKind::PressurePlate { .. }
+            | BlockKind::Chest { .. }
+            | BlockKind::Barrel { .. }
+            | BlockKind::Furnace { .. }
+            | BlockKind::TripwireHook { .. }
+            | BlockKind::Tripwire { .. }
+            | BlockKind::DaylightSensor { .. }
+            | BlockKind::LightningRod { .. } => Vec::new(),
+            BlockKind::CalibratedSculkSensor { facing, .. } => {
+                let mut dirs = vec![facing.opposite()];
+                dirs.extend(facing.sides());
+                dirs.into_iter().map(|d| pos + d).collect()
+            }
+            BlockKind::Dust { connections, .. } => dust_positions(pos, connections),
+            BlockKind::Lamp { .. }
+            | BlockKind::CopperBulb { .. }
+            | BlockKind::Piston { .. }
+            | BlockKind::Hopper { .. }
+            | BlockKind::Comparator { .. }
+            | BlockKind::OpaqueSolid { .. }
+            | BlockKind::Door { .. }
+            | BlockKind::Trapdoor { .. }
+            | BlockKind::NoteBlock { .. }
+            | BlockKind::PoweredRail { .. }
+            | BlockKind::ActivatorRail { .. }
+            | BlockKind::Dropper { .. }
+            | BlockKind::Dispenser { .. }
+            | BlockKind::Crafter { .. }
+            | BlockKind::Bell { .. } => pos.neighbors().map(|(n, _)| n).to_vec(),
+            BlockKind::Repeater { facing, .. } => {
+                // back input plus the two side (locking) inputs
+                let mut dirs = vec![facing.opposite()];
+                dirs.extend(facing.sides());
+                dirs.into_iter().map(|d| pos + d).collect()
+            }
+            BlockKind::Torch { facing, .. } | BlockKind::Observer { facing, .. } => {
+                vec![pos + *facing]
+            }
+            // the four horizontal neighbours and the block below
+            BlockKind::FenceGate { .. } => pos
+                .neighbors()
+                .into_iter()
+                .filter(|(_, d)| *d != Direction::Up)
+                .map(|(n, _)| n)
                 .collect(),
+        }
+    }
+
+    fn output_positions(&self, pos: Pos) -> Vec<Pos> {
+        match self {
+            BlockKind::Lever { facing, .. }
+            | BlockKind::Button { facing, .. }
+            | BlockKind::Repeater { facing, .. }
+            | BlockKind::Comparator { facing, .. } => {
+                vec![pos + *facing]
+            }
+            BlockKind::Observer { facing, .. } => {
+                vec![pos + facing.opposite()]
+            }
+            BlockKind::Torch { facing, .. } => {
+                pos.neighbors().into_iter().filter(|(_, d)| d != facing).map(|(n, _)| n).collect()
+            }
+            BlockKind::Dust { connections, .. } => dust_positions(pos, connections),
+            BlockKind::OpaqueSolid { .. }
+            | BlockKind::RedstoneBlock
+            | BlockKind::Target { .. }
+            | BlockKind::SculkSensor { .. }
+            | BlockKind::CalibratedSculkSensor { .. }
+            | BlockKind::DetectorRail { .. }
+            | BlockKind::TripwireHook { .. }
+            | BlockKind::LightningRod { .. } => pos.neighbors().map(|(n, _)| n).to_vec(),
             BlockKind::Lamp { .. }
+            | BlockKind::CopperBulb { .. }
             | BlockKind::Piston { .. }
-            | BlockKind::Hopper { .. } => Vec::new(),
+            | BlockKind::PistonArm { .. }
+            | BlockKind::Hopper { .. }
+            | BlockKind::Door { .. }
+            | BlockKind::Trapdoor { .. }
+            | BlockKind::NoteBlock { .. }
+            | BlockKind::Dropper { .. }
+            | BlockKind::Dispenser { .. }
+            | BlockKind::Crafter { .. }
+            | BlockKind::Bell { .. }
+            | BlockKind::FenceGate { .. }
+            | BlockKind::Chest { .. }
+            | BlockKind::Barrel { .. }
+            | BlockKind::Furnace { .. }
+            | BlockKind::Tripwire { .. } => Vec::new(),
+            // the four horizontal neighbours and the block below
+            BlockKind::PressurePlate { .. } => pos
+                .neighbors()
+                .into_iter()
+                .filter(|(_, d)| *d != Direction::Up)
+                .map(|(n, _)| n)
+                .collect(),
+            // the four horizontal neighbours and the block above
+            BlockKind::DaylightSensor { .. } => pos
+                .neighbors()
+                .into_iter()
+                .filter(|(_, d)| *d != Direction::Down)
+                .map(|(n, _)| n)
+                .collect(),
+            // rails pass power on to the rails they link to
+            BlockKind::PoweredRail { shape, .. } | BlockKind::ActivatorRail { shape, .. } => {
+                shape.linked_positions(pos)
+            }
         }
     }
 }
 
+// a single chest; a double chest holds 54
+fn default_chest_slots() -> u8 {
+    27
+}
+
+/// `(items, capacity)` of a container block, or `None` for blocks that hold no items.
+fn container_contents(block: &BlockKind) -> Option<(u8, u8)> {
+    match block {
+        BlockKind::Hopper { items, .. } => Some((*items, 5)),
+        BlockKind::Chest { items, max_items } | BlockKind::Barrel { items, max_items } => Some((*items, *max_items)),
+        _ => None,
+    }
+}
+
+fn container_items_mut(block: &mut BlockKind) -> Option<&mut u8> {
+    match block {
+        BlockKind::Hopper { items, .. } | BlockKind::Chest { items, .. } | BlockKind::Barrel { items, .. } => Some(items),
+        _ => None,
+    }
+}
+
+/// Comparator reading (0–15) of a container's fill level, or `None` if the
+/// block is not a container. Any item at all gives at least 1. A crafter
+/// reads as its craft count mod 16, and a furnace as its remaining fuel.
+pub fn container_signal_strength(block: &BlockKind) -> Option<u8> {
+    match block {
+        BlockKind::Crafter { craft_count, .. } => return Some((craft_count % 16) as u8),
+        BlockKind::Furnace { fuel_ticks, .. } => return Some(furnace_level(*fuel_ticks)),
+        _ => {}
+    }
+    let (items, capacity) = container_contents(block)?;
+    if items == 0 || capacity == 0 {
+        Some(0)
+    } else {
+        Some(1 + (items.min(capacity) as u32 * 14 / capacity as u32) as u8)
+    }
+}
+
+// 15 × fuel_ticks / FURNACE_MAX_FUEL, rounded down and capped at 15
+fn furnace_level(fuel_ticks: u32) -> u8 {
+    (u64::from(fuel_ticks) * 15 / u64::from(FURNACE_MAX_FUEL)).min(15) as u8
+}
+
+/// Power level (0–15) a block currently carries: a dust's power, or the
+/// strongest signal the block sends in any direction. Blocks that never
+/// emit power read 0.
+pub fn power_level(block: &BlockKind) -> u8 {
+    match block {
+        BlockKind::Dust { power, .. } => *power,
+        _ => Direction::all().into_iter().map(|d| output_towards(block, d)).max().unwrap_or(0),
+    }
+}
+
 // -------------------------------------------------
 // A block placed in the world
 // -------------------------------------------------
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
 pub struct PlacedBlock {
     #[serde(flatten)]
     pub pos: Pos,
@@ -207,403 +1261,4653 @@ pub struct PlacedBlock {
     pub kind: BlockKind,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
 pub struct World {
     pub blocks: Vec<PlacedBlock>,
 }
 
-impl World {
-    fn into_map(self) -> HashMap<Pos, BlockKind> {
-        self.blocks.into_iter().map(|b| (b.pos, b.kind)).collect()
+/// Clockwise rotation about the vertical axis, as seen from above.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RotationAngle {
+    Degrees90,
+    Degrees180,
+    Degrees270,
+}
+
+/// Axis whose coordinate `World::mirror` negates: `X` swaps east and
+/// west, `Z` swaps north and south.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Axis {
+    X,
+    Z,
+}
+
+/// Axis-aligned box of block positions; both corners are inclusive.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct BoundingBox {
+    pub min: Pos,
+    pub max: Pos,
+}
+
+impl BoundingBox {
+    /// Extent in blocks along x, y and z.
+    pub fn size(&self) -> (u32, u32, u32) {
+        let len = |lo: i32, hi: i32| (hi - lo + 1) as u32;
+        (len(self.min.x, self.max.x), len(self.min.y, self.max.y), len(self.min.z, self.max.z))
+    }
+
+    /// Number of block positions inside the box.
+    pub fn volume(&self) -> u64 {
+        let (x, y, z) = self.size();
+        x as u64 * y as u64 * z as u64
+    }
+
+    /// Middle position, rounded towards `min` on even extents.
+    pub fn center(&self) -> Pos {
+        let mid = |lo: i32, hi: i32| lo + (hi - lo) / 2;
+        Pos {
+            x: mid(self.min.x, self.max.x),
+            y: mid(self.min.y, self.max.y),
+            z: mid(self.min.z, self.max.z),
+        }
+    }
+
+    /// The box with `a` and `b` as opposite corners, in any order.
+    pub fn spanning(a: Pos, b: Pos) -> BoundingBox {
+        BoundingBox {
+            min: Pos { x: a.x.min(b.x), y: a.y.min(b.y), z: a.z.min(b.z) },
+            max: Pos { x: a.x.max(b.x), y: a.y.max(b.y), z: a.z.max(b.z) },
+        }
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        (self.min.x..=self.max.x).contains(&pos.x)
+            && (self.min.y..=self.max.y).contains(&pos.y)
+            && (self.min.z..=self.max.z).contains(&pos.z)
+    }
+
+    /// The box grown by `margin` blocks on every side (shrunk if negative).
+    pub fn expand(&self, margin: i32) -> BoundingBox {
+        BoundingBox { min: self.min - (margin, margin, margin), max: self.max + (margin, margin, margin) }
     }
 }
 
-// -------------------------------------------------
-// Simulation request / response
-// -------------------------------------------------
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct SimRequest {
-    pub ticks: u32,   // maximum ticks to simulate
-    pub world: World, // t = 0 state (raw user input)
-    #[serde(default = "default_true")]
-    pub early_exit: bool, // stop when stable & no timers running
+/// Redstone ticks a pressed button stays on (a stone button's 20 game ticks).
+const BUTTON_PRESS_TICKS: u8 = 10;
+
+/// A torch burns out on this many toggles in quick succession: vanilla's
+/// 8 within 60 game ticks, approximated by forgetting one toggle every
+/// `TORCH_TOGGLE_DECAY` ticks.
+const TORCH_BURNOUT_TOGGLES: u8 = 8;
+const TORCH_TOGGLE_DECAY: u8 = 4;
+
+/// Redstone ticks a burnt-out torch stays dark (60 game ticks).
+const TORCH_BURNOUT_TICKS: u8 = 30;
+
+/// Number of ticks a target block keeps its power after being hit.
+const TARGET_HIT_TICKS: u8 = 4;
+
+/// Number of ticks a sculk sensor stays active after detecting a vibration.
+const SCULK_ACTIVE_TICKS: u8 = 20;
+
+/// Maximum distance at which a sculk sensor detects vibrations.
+const SCULK_RANGE: f64 = 8.0;
+
+/// Lowest and highest block layers of the world, as in vanilla.
+const WORLD_MIN_Y: i32 = -64;
+const WORLD_MAX_Y: i32 = 319;
+
+/// Furthest x or z from the origin, as vanilla's world border. Keeps
+/// neighbour and distance arithmetic far from `i32` overflow.
+const WORLD_BORDER: i32 = 30_000_000;
+
+fn in_world(p: Pos) -> bool {
+    (-WORLD_BORDER..=WORLD_BORDER).contains(&p.x)
+        && (-WORLD_BORDER..=WORLD_BORDER).contains(&p.z)
+        && (WORLD_MIN_Y..=WORLD_MAX_Y).contains(&p.y)
 }
-fn default_true() -> bool {
-    true
+
+// why a block's own fields could not occur in game, if they could not;
+// `World::validate` also checks blocks against their neighbours
+fn block_state_error(kind: &BlockKind) -> Option<&'static str> {
+    let reason = match kind {
+        BlockKind::Repeater { facing, .. } | BlockKind::Comparator { facing, .. } | BlockKind::TripwireHook { facing, .. }
+            if facing.is_vertical() =>
+        {
+            "must face horizontally"
+        }
+        BlockKind::Repeater { delay, .. } if !(1..=4).contains(delay) => "delay must be 1‑4",
+        BlockKind::Dust { power, .. } | BlockKind::DaylightSensor { power, .. } if *power > 15 => "power must be 0‑15",
+        BlockKind::NoteBlock { pitch, .. } if *pitch > 24 => "pitch must be 0‑24",
+        BlockKind::Chest { max_items, .. } | BlockKind::Barrel { max_items, .. } if !matches!(max_items, 27 | 54) => {
+            "max_items must be 27 or 54"
+        }
+        BlockKind::Furnace { fuel_ticks: 0, cooking: true } => "cannot burn without fuel",
+        _ if container_contents(kind).is_some_and(|(n, cap)| n > cap) => "holds more items than it can",
+        _ => return None,
+    };
+    Some(reason)
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct BlockChange {
-    #[serde(flatten)]
-    pub pos: Pos,
-    #[serde(flatten)]
-    pub kind: BlockKind,
+// the error for placing `kind` at `pos` mid-run, checked like `World::validate`
+fn placement_error(pos: Pos, kind: &BlockKind) -> Option<WorldError> {
+    if !in_world(pos) {
+        return Some(WorldError::OutOfBounds(pos));
+    }
+    block_state_error(kind).map(|reason| WorldError::InvalidBlockState { pos, reason: reason.to_string() })
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct TickDiff {
-    pub tick: u32,
-    pub changes: Vec<BlockChange>,
+/// Error from editing or validating a `World`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum WorldError {
+    PositionOccupied(Pos),                          // a block already exists there
+    OutOfBounds(Pos),                               // outside the world border or build height
+    InvalidBlockState { pos: Pos, reason: String }, // fields that cannot occur in game
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum Termination {
-    Stable,          // reached stable state (no external or internal changes)
-    MaxTicksReached, // hit user‑specified limit
+impl core::fmt::Display for WorldError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            WorldError::PositionOccupied(p) => {
+                write!(f, "position ({}, {}, {}) is already occupied", p.x, p.y, p.z)
+            }
+            WorldError::OutOfBounds(p) => {
+                write!(
+                    f,
+                    "position {} is outside x, z {}..={} and y {}..={}",
+                    p, -WORLD_BORDER, WORLD_BORDER, WORLD_MIN_Y, WORLD_MAX_Y
+                )
+            }
+            WorldError::InvalidBlockState { pos, reason } => write!(f, "block at {}: {}", pos, reason),
+        }
+    }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct SimResponse {
-    pub diffs: Vec<TickDiff>,
-    pub terminated: Termination,
+impl core::error::Error for WorldError {}
+
+/// Error from `World::merge`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MergeError {
+    PositionConflict(Vec<Pos>), // both worlds have a block at each of these positions
 }
 
-// -------------------------------------------------
-// Public entry point
-// -------------------------------------------------
-/// Simulate the world for `request.ticks` or until it becomes stable.
-/// Returns per‑tick diffs only for blocks that actually changed.
-pub fn simulate(request: SimRequest) -> SimResponse {
-    let mut world = request.world.into_map();
-    let mut diffs: Vec<TickDiff> = Vec::new();
-
-    // helper to query output from a block toward a direction
-    fn output_towards(block: &BlockKind, dir: Direction) -> u8 {
-        match block {
-            BlockKind::Lever { on: true, facing } if *facing == dir => 15,
-            BlockKind::Button { ticks_remaining, facing }
-                if *ticks_remaining > 0 && *facing == dir => 15,
-            BlockKind::Repeater { powered: true, facing, .. } if *facing == dir => 15,
-            BlockKind::Comparator { output, facing } if *output > 0 && *facing == dir => *output,
-            BlockKind::Torch { lit: true, facing } if dir != *facing => 15,
-            BlockKind::Dust { power } => *power,
-            _ => 0,
+impl core::fmt::Display for MergeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            MergeError::PositionConflict(ps) => {
+                write!(f, "{} position(s) occupied in both worlds", ps.len())?;
+                if let Some(p) = ps.first() {
+                    write!(f, ", first at {}", p)?;
+                }
+                Ok(())
+            }
         }
     }
+}
 
-    fn mark_outputs(block: &BlockKind, pos: Pos, set: &mut HashSet<Pos>) {
-        for n in block.output_positions(pos) {
-            set.insert(n);
+impl core::error::Error for MergeError {}
+
+/// Error from `World::copy_region`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CopyError {
+    EmptyRegion, // no block inside the source box
+}
+
+impl core::fmt::Display for CopyError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CopyError::EmptyRegion => f.write_str("no blocks in the region to copy"),
         }
     }
+}
 
-    let mut dirty: HashSet<Pos> = world.keys().cloned().collect();
+impl core::error::Error for CopyError {}
 
-    for tick in 1..=request.ticks {
-        let mut changes: Vec<BlockChange> = Vec::new();
-        let snapshot = world.clone();
-        let mut next_dirty: HashSet<Pos> = HashSet::new();
+/// Error from `World::paste_into`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PasteError {
+    PositionConflict(Vec<Pos>), // already occupied, in the pasted world's order
+}
 
-        for pos in dirty.iter() {
-            if let Some(block) = world.get_mut(pos) {
-                let mut changed = false;
-                let mut mark_out = false;
-                let input_positions = block.input_positions(*pos);
-                match block {
-                    BlockKind::Button { ticks_remaining, .. } => {
-                        if *ticks_remaining > 0 {
-                            let prev_output = 15;
-                            *ticks_remaining -= 1;
-                            let new_output = if *ticks_remaining > 0 { 15 } else { 0 };
-                            changed = true;
-                            if prev_output != new_output {
-                                mark_out = true;
-                            }
-                            if *ticks_remaining > 0 {
-                                next_dirty.insert(*pos);
-                            }
-                        }
-                    }
-                    BlockKind::Repeater { delay, ticks_remaining, powered, facing } => {
-                        let back = facing.opposite();
-                        let (dx, dy, dz) = back.offset();
-                        let n = Pos { x: pos.x + dx, y: pos.y + dy, z: pos.z + dz };
-                        let mut input = 0;
-                        if let Some(nb) = snapshot.get(&n) {
-                            input = output_towards(nb, *facing);
-                        }
+impl core::fmt::Display for PasteError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PasteError::PositionConflict(ps) => {
+                write!(f, "cannot paste over {} occupied position(s)", ps.len())?;
+                if let Some(p) = ps.first() {
+                    write!(f, ", first at {}", p)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
 
-                        let prev_output = if *powered { 15 } else { 0 };
+impl core::error::Error for PasteError {}
 
-                        if input > 0 {
-                            if !*powered && *ticks_remaining == 0 {
-                                *ticks_remaining = *delay;
-                            }
-                        } else {
-                            *powered = false;
-                            *ticks_remaining = 0;
-                        }
+impl World {
+    /// Place `kind` at `pos`. Fails if the position is already occupied.
+    pub fn add_block(&mut self, pos: Pos, kind: BlockKind) -> Result<(), WorldError> {
+        if self.blocks.iter().any(|b| b.pos == pos) {
+            return Err(WorldError::PositionOccupied(pos));
+        }
+        self.blocks.push(PlacedBlock { pos, kind });
+        Ok(())
+    }
 
-                        if *ticks_remaining > 0 {
-                            *ticks_remaining -= 1;
-                            if *ticks_remaining == 0 && input > 0 {
-                                *powered = true;
-                            }
-                        }
+    /// Remove the block at `pos`, returning it if there was one.
+    pub fn remove_block(&mut self, pos: Pos) -> Option<BlockKind> {
+        let i = self.blocks.iter().position(|b| b.pos == pos)?;
+        Some(self.blocks.remove(i).kind)
+    }
 
-                        let new_output = if *powered { 15 } else { 0 };
+    /// Number of blocks of each kind, keyed by [`BlockKind::type_name`].
+    /// Needs `std` (hashbrown has no default hasher without it).
+    #[cfg(feature = "std")]
+    pub fn count_by_kind(&self) -> std::collections::HashMap<&'static str, usize> {
+        let mut counts = std::collections::HashMap::new();
+        for b in &self.blocks {
+            *counts.entry(b.kind.type_name()).or_default() += 1;
+        }
+        counts
+    }
 
-                        if prev_output != new_output || *ticks_remaining != 0 {
-                            changed = true;
-                        }
+    /// Number of blocks placed.
+    pub fn total_blocks(&self) -> usize {
+        self.blocks.len()
+    }
 
-                        if prev_output != new_output {
-                            mark_out = true;
-                        }
+    /// Whether a block is placed at `pos`.
+    pub fn has_block_at(&self, pos: Pos) -> bool {
+        self.blocks.iter().any(|b| b.pos == pos)
+    }
 
-                        if *ticks_remaining > 0 {
-                            next_dirty.insert(*pos);
-                        }
-                    }
-                    BlockKind::Comparator { output, .. } => {
-                        let mut new_out = 0;
-                        for n in &input_positions {
-                            if let Some(nb) = snapshot.get(n) {
-                                let dir = dir_from_to(*n, *pos);
-                                new_out = new_out.max(output_towards(nb, dir));
-                            }
-                        }
-                        if *output != new_out {
-                            *output = new_out;
-                            changed = true;
-                            mark_out = true;
-                        }
-                    }
-                    BlockKind::Dust { power } => {
-                        let mut new_power = 0;
-                        for n in &input_positions {
-                            if let Some(nb) = snapshot.get(n) {
-                                let dir = dir_from_to(*n, *pos);
-                                let pw = output_towards(nb, dir);
-                                let candidate = match nb {
-                                    BlockKind::Dust { power: p, .. } => p.saturating_sub(1),
-                                    _ => pw,
-                                };
-                                new_power = new_power.max(candidate);
-                            }
-                        }
-                        if *power != new_power {
-                            *power = new_power;
-                            changed = true;
-                            mark_out = true;
-                        }
-                    }
-                    BlockKind::Lamp { on } => {
-                        let mut powered = false;
-                        for n in &input_positions {
-                            if let Some(nb) = snapshot.get(n) {
-                                let dir = dir_from_to(*n, *pos);
-                                if output_towards(nb, dir) > 0 {
-                                    powered = true;
-                                    break;
-                                }
-                            }
-                        }
-                        if *on != powered {
-                            *on = powered;
-                            changed = true;
-                        }
-                    }
-                    BlockKind::Torch { lit, facing } => {
-                        let mut powered = false;
-                        let (dx, dy, dz) = facing.offset();
-                        let n = Pos { x: pos.x + dx, y: pos.y + dy, z: pos.z + dz };
-                        if let Some(nb) = snapshot.get(&n) {
-                            if output_towards(nb, facing.opposite()) > 0 {
-                                powered = true;
-                            }
-                        }
-                        let new_lit = !powered;
-                        if *lit != new_lit {
-                            *lit = new_lit;
-                            changed = true;
-                            mark_out = true;
-                        }
-                    }
-                    BlockKind::Piston { extended, .. } => {
-                        let mut powered = false;
-                        for n in &input_positions {
-                            if let Some(nb) = snapshot.get(n) {
-                                let dir = dir_from_to(*n, *pos);
-                                if output_towards(nb, dir) > 0 {
-                                    powered = true;
-                                    break;
-                                }
-                            }
-                        }
-                        if *extended != powered {
-                            *extended = powered;
-                            changed = true;
-                            mark_out = true;
-                        }
-                    }
-                    BlockKind::Hopper { enabled, .. } => {
-                        let mut powered = false;
-                        for n in &input_positions {
-                            if let Some(nb) = snapshot.get(n) {
-                                let dir = dir_from_to(*n, *pos);
-                                if output_towards(nb, dir) > 0 {
-                                    powered = true;
-                                    break;
-                                }
-                            }
-                        }
-                        let new_enabled = !powered;
-                        if *enabled != new_enabled {
-                            *enabled = new_enabled;
-                            changed = true;
-                        }
-                    }
-                    _ => {}
-                }
+    /// Whether no blocks are placed.
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
 
-                if changed {
-                    changes.push(BlockChange { pos: *pos, kind: block.clone() });
-                }
-                if mark_out {
-                    mark_outputs(block, *pos, &mut next_dirty);
-                }
+    /// Every block with its position, in placement order.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &BlockKind)> {
+        self.blocks.iter().map(|b| (b.pos, &b.kind))
+    }
+
+    /// Positions of every block, in placement order.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> + '_ {
+        self.blocks.iter().map(|b| b.pos)
+    }
+
+    /// Every block's kind, in placement order.
+    pub fn kinds(&self) -> impl Iterator<Item = &BlockKind> {
+        self.blocks.iter().map(|b| &b.kind)
+    }
+
+    /// The block at `pos`, if any.
+    pub fn get(&self, pos: Pos) -> Option<&BlockKind> {
+        self.blocks.iter().find(|b| b.pos == pos).map(|b| &b.kind)
+    }
+
+    /// Same as [`World::has_block_at`], named after the std containers.
+    pub fn contains(&self, pos: Pos) -> bool {
+        self.has_block_at(pos)
+    }
+
+    /// Positions of every block whose [`BlockKind::type_name`] is
+    /// `kind_name`, in placement order.
+    pub fn blocks_of_kind(&self, kind_name: &str) -> Vec<Pos> {
+        self.blocks.iter().filter(|b| b.kind.type_name() == kind_name).map(|b| b.pos).collect()
+    }
+
+    /// Check the world before simulating: one block per position, all within
+    /// the world border and build height, and no block in a state the game cannot produce.
+    pub fn validate(&self) -> Result<(), WorldError> {
+        let mut seen = SpatialSet::default();
+        for b in &self.blocks {
+            if !seen.insert(b.pos) {
+                return Err(WorldError::PositionOccupied(b.pos));
             }
+            if !in_world(b.pos) {
+                return Err(WorldError::OutOfBounds(b.pos));
+            }
+        }
+        let kind_at = |pos: Pos| self.blocks.iter().find(|b| b.pos == pos).map(|b| &b.kind);
+        for b in &self.blocks {
+            let reason = match &b.kind {
+                BlockKind::Door { half, .. }
+                    if !matches!(kind_at(door_partner(b.pos, *half)), Some(BlockKind::Door { .. })) =>
+                {
+                    "door is missing its other half"
+                }
+                kind => match block_state_error(kind) {
+                    Some(reason) => reason,
+                    None => continue,
+                },
+            };
+            return Err(WorldError::InvalidBlockState { pos: b.pos, reason: reason.to_string() });
         }
+        Ok(())
+    }
 
-        if !changes.is_empty() {
-            diffs.push(TickDiff { tick, changes });
-        } else if request.early_exit {
-            let timers_active = world.values().any(|b| match b {
-                BlockKind::Button { ticks_remaining, .. } if *ticks_remaining > 0 => true,
-                BlockKind::Repeater { ticks_remaining, .. } if *ticks_remaining > 0 => true,
-                _ => false,
-            });
-            if !timers_active {
-                return SimResponse {
-                    diffs,
-                    terminated: Termination::Stable,
+    /// Likely wiring mistakes, such as repeaters outputting into nothing or
+    /// torch loops that can never settle. All are reported; none stops the
+    /// world from simulating. See `validate` for hard errors.
+    pub fn validate_wiring(&self) -> Vec<analysis::ValidationWarning> {
+        analysis::wiring_warnings(&self.clone().into_map())
+    }
+
+    /// Hit the target block at `pos` with a projectile of the given
+    /// `strength` (clamped to 15). Does nothing if `pos` is not a target.
+    pub fn hit_target(&mut self, pos: Pos, strength: u8) {
+        if let Some(b) = self.blocks.iter_mut().find(|b| b.pos == pos) {
+            if let BlockKind::Target { .. } = b.kind {
+                b.kind = BlockKind::Target {
+                    power: strength.min(15),
+                    ticks_remaining: TARGET_HIT_TICKS,
                 };
             }
         }
-
-        dirty = next_dirty;
     }
 
-    SimResponse {
-        diffs,
-        terminated: Termination::MaxTicksReached,
+    /// Copy of the world rotated about the vertical axis through the origin,
+    /// with every block's facing turned to match. Combine with `translate`
+    /// to rotate about another point.
+    pub fn rotate(&self, angle: RotationAngle) -> World {
+        let turns = match angle {
+            RotationAngle::Degrees90 => 1,
+            RotationAngle::Degrees180 => 2,
+            RotationAngle::Degrees270 => 3,
+        };
+        let turn = |d: Direction| (0..turns).fold(d, |d, _| d.clockwise().unwrap_or(d));
+        let blocks = self
+            .blocks
+            .iter()
+            .map(|b| {
+                let (x, z) = (0..turns).fold((b.pos.x, b.pos.z), |(x, z), _| (-z, x));
+                PlacedBlock { pos: Pos { x, y: b.pos.y, z }, kind: b.kind.transformed(turn, false) }
+            })
+            .collect();
+        World { blocks }
     }
-}
-
-// -------------------------------------------------
-// Unit tests
-// -------------------------------------------------
-#[cfg(test)]
-mod tests {
-    use super::*;
 
-    #[test]
-    fn lever_to_lamp_one_tick() {
-        let world = World {
-            blocks: vec![
-                PlacedBlock {
-                    pos: Pos { x: 0, y: 0, z: 0 },
-                    kind: BlockKind::Lever { on: true, facing: Direction::East },
-                },
-                PlacedBlock {
-                    pos: Pos { x: 1, y: 0, z: 0 },
-                    kind: BlockKind::Dust { power: 0 },
-                },
-                PlacedBlock {
-                    pos: Pos { x: 2, y: 0, z: 0 },
-                    kind: BlockKind::Lamp { on: false },
-                },
-            ],
-        };
-        let req = SimRequest {
-            ticks: 5,
-            world,
-            early_exit: true,
+    /// Copy of the world reflected through the origin along `axis`. Facings
+    /// are reflected too, and door hinges swap sides.
+    pub fn mirror(&self, axis: Axis) -> World {
+        let flip = |d: Direction| match (axis, d) {
+            (Axis::X, Direction::East | Direction::West) | (Axis::Z, Direction::North | Direction::South) => {
+                d.opposite()
+            }
+            _ => d,
         };
-        let res = simulate(req);
-        assert!(matches!(res.terminated, Termination::Stable));
-        // lamp should turn on at tick = 1
-        assert!(res.diffs.iter().any(|d| d.tick == 1
-            && d.changes
-                .iter()
-                .any(|c| matches!(c.kind, BlockKind::Lamp { on: true }))));
+        let blocks = self
+            .blocks
+            .iter()
+            .map(|b| {
+                let pos = match axis {
+                    Axis::X => Pos { x: -b.pos.x, ..b.pos },
+                    Axis::Z => Pos { z: -b.pos.z, ..b.pos },
+                };
+                PlacedBlock { pos, kind: b.kind.transformed(flip, true) }
+            })
+            .collect();
+        World { blocks }
     }
 
-    #[test]
-    fn dust_attenuation() {
-        let world = World {
-            blocks: vec![
-                PlacedBlock {
-                    pos: Pos { x: 0, y: 0, z: 0 },
-                    kind: BlockKind::Lever { on: true, facing: Direction::East },
-                },
-                PlacedBlock {
-                    pos: Pos { x: 1, y: 0, z: 0 },
-                    kind: BlockKind::Dust { power: 0 },
-                },
-                PlacedBlock {
-                    pos: Pos { x: 2, y: 0, z: 0 },
-                    kind: BlockKind::Dust { power: 0 },
-                },
-            ],
-        };
-        let req = SimRequest { ticks: 3, world, early_exit: true };
-        let res = simulate(req);
-        assert!(res.diffs.iter().any(|d| d.changes.iter().any(|c| matches!(c.kind, BlockKind::Dust { power: 14 }))));
+    /// Copy of the world with every block moved by `offset`.
+    pub fn translate(&self, offset: Pos) -> World {
+        let blocks = self
+            .blocks
+            .iter()
+            .map(|b| PlacedBlock { pos: b.pos + <(i32, i32, i32)>::from(offset), kind: b.kind.clone() })
+            .collect();
+        World { blocks }
     }
 
-    #[test]
-    fn torch_turns_off_when_powered() {
-        let world = World {
-            blocks: vec![
-                PlacedBlock {
-                    pos: Pos { x: 0, y: 0, z: 0 },
-                    kind: BlockKind::Lever { on: true, facing: Direction::East },
-                },
-                PlacedBlock {
-                    pos: Pos { x: 1, y: 0, z: 0 },
-                    kind: BlockKind::Torch { lit: true, facing: Direction::West },
-                },
-            ],
-        };
-        let req = SimRequest { ticks: 2, world, early_exit: true };
-        let res = simulate(req);
-        assert!(res.diffs.iter().any(|d| d.changes.iter().any(|c| matches!(c.kind, BlockKind::Torch { lit: false }))));
+    /// Smallest box holding every block, or `None` for an empty world.
+    pub fn bounds(&self) -> Option<BoundingBox> {
+        let first = self.blocks.first()?.pos;
+        let bounds = self.blocks.iter().fold(BoundingBox { min: first, max: first }, |bb, b| BoundingBox {
+            min: Pos { x: bb.min.x.min(b.pos.x), y: bb.min.y.min(b.pos.y), z: bb.min.z.min(b.pos.z) },
+            max: Pos { x: bb.max.x.max(b.pos.x), y: bb.max.y.max(b.pos.y), z: bb.max.z.max(b.pos.z) },
+        });
+        Some(bounds)
+    }
+
+    /// Copy of the world moved so that its bounds start at the origin.
+    pub fn normalize(&self) -> World {
+        match self.bounds() {
+            Some(bb) => self.translate(-bb.min),
+            None => self.clone(),
+        }
+    }
+
+    /// Copy of the blocks inside the box with corners `min` and `max`, both
+    /// inclusive. The corners may be given in either order.
+    pub fn extract_region(&self, min: Pos, max: Pos) -> World {
+        let bb = BoundingBox::spanning(min, max);
+        World { blocks: self.blocks.iter().filter(|b| bb.contains(b.pos)).cloned().collect() }
+    }
+
+    /// Copy of the blocks inside the box with corners `src_min` and
+    /// `src_max` (inclusive, either order), moved so that the box's lowest
+    /// corner lands on `dst_origin`. Fails if the box holds no blocks.
+    pub fn copy_region(&self, src_min: Pos, src_max: Pos, dst_origin: Pos) -> Result<World, CopyError> {
+        let region = self.extract_region(src_min, src_max);
+        if region.is_empty() {
+            return Err(CopyError::EmptyRegion);
+        }
+        let min = BoundingBox::spanning(src_min, src_max).min;
+        Ok(region.translate(Pos { x: dst_origin.x - min.x, y: dst_origin.y - min.y, z: dst_origin.z - min.z }))
+    }
+
+    /// Add every block of `other` where it stands, e.g. a `copy_region`
+    /// result. Fails with every position both worlds occupy, leaving `self`
+    /// unchanged.
+    pub fn paste_into(&mut self, other: &World) -> Result<(), PasteError> {
+        let taken: SpatialSet = self.blocks.iter().map(|b| b.pos).collect();
+        let conflicts: Vec<Pos> = other.blocks.iter().map(|b| b.pos).filter(|p| taken.contains(p)).collect();
+        if !conflicts.is_empty() {
+            return Err(PasteError::PositionConflict(conflicts));
+        }
+        self.blocks.extend(other.blocks.iter().cloned());
+        Ok(())
+    }
+
+    /// Delete every block inside the box with corners `min` and `max`.
+    pub fn remove_region(&mut self, min: Pos, max: Pos) {
+        let bb = BoundingBox::spanning(min, max);
+        self.blocks.retain(|b| !bb.contains(b.pos));
+    }
+
+    /// New world holding the blocks of both, with `other` moved by `offset`.
+    /// Fails with every overlapping position, in `other`'s order, if the two
+    /// share any.
+    pub fn merge(&self, other: &World, offset: Pos) -> Result<World, MergeError> {
+        let moved = other.translate(offset);
+        let taken: SpatialSet = self.blocks.iter().map(|b| b.pos).collect();
+        let conflicts: Vec<Pos> = moved.blocks.iter().map(|b| b.pos).filter(|p| taken.contains(p)).collect();
+        if !conflicts.is_empty() {
+            return Err(MergeError::PositionConflict(conflicts));
+        }
+        let mut blocks = self.blocks.clone();
+        blocks.extend(moved.blocks);
+        Ok(World { blocks })
+    }
+
+    /// `merge`, except that where both have a block `other`'s wins.
+    pub fn merge_override(&self, other: &World, offset: Pos) -> World {
+        let moved = other.translate(offset);
+        let replaced: SpatialSet = moved.blocks.iter().map(|b| b.pos).collect();
+        let mut blocks: Vec<PlacedBlock> = self.blocks.iter().filter(|b| !replaced.contains(&b.pos)).cloned().collect();
+        blocks.extend(moved.blocks);
+        World { blocks }
+    }
+
+    /// Copy of the world without any block at a position `other` occupies,
+    /// whatever block `other` has there.
+    pub fn subtract(&self, other: &World) -> World {
+        let removed: SpatialSet = other.blocks.iter().map(|b| b.pos).collect();
+        World { blocks: self.blocks.iter().filter(|b| !removed.contains(&b.pos)).cloned().collect() }
+    }
+
+    /// The blocks keyed by position, as the simulator and `analysis` read
+    /// them, with each dust's connections worked out from its neighbours.
+    pub fn into_map(self) -> SpatialGrid<BlockKind> {
+        let mut map: SpatialGrid<BlockKind> =
+            self.blocks.into_iter().map(|b| (b.pos, b.kind)).collect();
+        // dust shape is derived from the surroundings, not trusted from input
+        let shapes: Vec<(Pos, [bool; 4])> = map
+            .iter()
+            .filter(|(_, b)| matches!(b, BlockKind::Dust { .. }))
+            .map(|(p, _)| (*p, dust_connections(&map, *p)))
+            .collect();
+        for (p, shape) in shapes {
+            if let Some(BlockKind::Dust { connections, .. }) = map.get_mut(&p) {
+                *connections = shape;
+            }
+        }
+        map
+    }
+}
+
+impl IntoIterator for World {
+    type Item = (Pos, BlockKind);
+    type IntoIter = core::iter::Map<alloc::vec::IntoIter<PlacedBlock>, fn(PlacedBlock) -> (Pos, BlockKind)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.blocks.into_iter().map(|b| (b.pos, b.kind))
+    }
+}
+
+/// Collects blocks in order without checking for duplicates; call
+/// [`World::validate`] on the result if the source is untrusted.
+impl FromIterator<(Pos, BlockKind)> for World {
+    fn from_iter<I: IntoIterator<Item = (Pos, BlockKind)>>(iter: I) -> Self {
+        World { blocks: iter.into_iter().map(|(pos, kind)| PlacedBlock { pos, kind }).collect() }
+    }
+}
+
+// -------------------------------------------------
+// Fluent world construction
+// -------------------------------------------------
+/// Error from `WorldBuilder::build`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BuildError {
+    DuplicatePosition(Pos), // two blocks were placed at the same position
+}
+
+impl core::fmt::Display for BuildError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            BuildError::DuplicatePosition(p) => {
+                write!(f, "more than one block at ({}, {}, {})", p.x, p.y, p.z)
+            }
+        }
+    }
+}
+
+impl core::error::Error for BuildError {}
+
+/// Builds a `World` one block at a time. Blocks start unpowered and idle;
+/// use `block` for anything that needs a specific initial state.
+///
+/// ```ignore
+/// let world = WorldBuilder::new()
+///     .lever((0, 0, 0), Direction::East, true)
+///     .dust((1, 0, 0))
+///     .lamp((2, 0, 0))
+///     .build()?;
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct WorldBuilder {
+    blocks: Vec<PlacedBlock>,
+}
+
+impl WorldBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Place an arbitrary block.
+    pub fn block(mut self, pos: impl Into<Pos>, kind: BlockKind) -> Self {
+        self.blocks.push(PlacedBlock { pos: pos.into(), kind });
+        self
+    }
+
+    pub fn lever(self, pos: impl Into<Pos>, facing: Direction, on: bool) -> Self {
+        self.block(pos, BlockKind::Lever { on, facing })
+    }
+
+    pub fn button(self, pos: impl Into<Pos>, facing: Direction) -> Self {
+        self.block(pos, BlockKind::Button { ticks_remaining: 0, facing })
+    }
+
+    pub fn dust(self, pos: impl Into<Pos>) -> Self {
+        self.block(pos, BlockKind::Dust { power: 0, connections: [false; 4] })
+    }
+
+    pub fn lamp(self, pos: impl Into<Pos>) -> Self {
+        self.block(pos, BlockKind::Lamp { on: false })
+    }
+
+    pub fn repeater(self, pos: impl Into<Pos>, facing: Direction, delay: u8) -> Self {
+        let kind = BlockKind::Repeater { delay, ticks_remaining: 0, powered: false, facing, locked: false };
+        self.block(pos, kind)
+    }
+
+    pub fn comparator(self, pos: impl Into<Pos>, facing: Direction, mode: ComparatorMode) -> Self {
+        self.block(pos, BlockKind::Comparator { output: 0, facing, mode })
+    }
+
+    /// A lit torch attached to the block in direction `facing`.
+    pub fn torch(self, pos: impl Into<Pos>, facing: Direction) -> Self {
+        self.block(pos, BlockKind::Torch { lit: true, facing, burnout_counter: 0, burnout_cooldown: 0, burned_out: false })
+    }
+
+    pub fn piston(self, pos: impl Into<Pos>, facing: Direction, sticky: bool) -> Self {
+        self.block(pos, BlockKind::Piston { extended: false, facing, sticky })
+    }
+
+    pub fn hopper(self, pos: impl Into<Pos>, facing: Direction, items: u8) -> Self {
+        self.block(pos, BlockKind::Hopper { enabled: true, facing, items })
+    }
+
+    pub fn observer(self, pos: impl Into<Pos>, facing: Direction) -> Self {
+        self.block(pos, BlockKind::Observer { facing, powered: false })
+    }
+
+    pub fn solid(self, pos: impl Into<Pos>) -> Self {
+        self.block(pos, BlockKind::OpaqueSolid { strong_power: 0 })
+    }
+
+    pub fn redstone_block(self, pos: impl Into<Pos>) -> Self {
+        self.block(pos, BlockKind::RedstoneBlock)
+    }
+
+    pub fn target(self, pos: impl Into<Pos>) -> Self {
+        self.block(pos, BlockKind::Target { power: 0, ticks_remaining: 0 })
+    }
+
+    pub fn tripwire_hook(self, pos: impl Into<Pos>, facing: Direction) -> Self {
+        self.block(pos, BlockKind::TripwireHook { attached: false, powered: false, facing })
+    }
+
+    pub fn tripwire(self, pos: impl Into<Pos>) -> Self {
+        self.block(pos, BlockKind::Tripwire { powered: false })
+    }
+
+    pub fn daylight_sensor(self, pos: impl Into<Pos>, inverted: bool) -> Self {
+        self.block(pos, BlockKind::DaylightSensor { inverted, power: 0 })
+    }
+
+    pub fn lightning_rod(self, pos: impl Into<Pos>) -> Self {
+        self.block(pos, BlockKind::LightningRod { powered: false, ticks_remaining: 0 })
+    }
+
+    pub fn copper_bulb(self, pos: impl Into<Pos>, oxidation: OxidationLevel) -> Self {
+        self.block(pos, BlockKind::CopperBulb { lit: false, powered: false, oxidation })
+    }
+
+    pub fn crafter(self, pos: impl Into<Pos>) -> Self {
+        self.block(pos, BlockKind::Crafter { powered: false, cooldown: 0, craft_count: 0 })
+    }
+
+    pub fn fence_gate(self, pos: impl Into<Pos>, facing: Direction) -> Self {
+        self.block(pos, BlockKind::FenceGate { open: false, facing, in_wall: false })
+    }
+
+    pub fn bell(self, pos: impl Into<Pos>, attachment: BellAttachment, facing: Direction) -> Self {
+        self.block(pos, BlockKind::Bell { attachment, facing, ringing: false, powered: false, ticks_remaining: 0 })
+    }
+
+    pub fn sculk_sensor(self, pos: impl Into<Pos>) -> Self {
+        self.block(pos, BlockKind::SculkSensor { power: 0, ticks_remaining: 0, waterlogged: false })
+    }
+
+    pub fn calibrated_sculk_sensor(self, pos: impl Into<Pos>, facing: Direction) -> Self {
+        let kind =
+            BlockKind::CalibratedSculkSensor { power: 0, ticks_remaining: 0, waterlogged: false, facing };
+        self.block(pos, kind)
+    }
+
+    /// A closed door: the lower half at `pos` and the upper half above it.
+    pub fn door(self, pos: impl Into<Pos>, facing: Direction, hinge: HingePosition) -> Self {
+        let pos = pos.into();
+        let half = |half| BlockKind::Door { open: false, hinge, facing, half };
+        self.block(pos, half(DoorHalf::Lower))
+            .block(pos + Direction::Up, half(DoorHalf::Upper))
+    }
+
+    pub fn trapdoor(self, pos: impl Into<Pos>, facing: Direction, half: TrapdoorHalf) -> Self {
+        self.block(pos, BlockKind::Trapdoor { open: false, facing, half })
+    }
+
+    pub fn note_block(self, pos: impl Into<Pos>, instrument: Instrument, pitch: u8) -> Self {
+        let kind = BlockKind::NoteBlock { instrument, pitch, triggered: false, powered: false };
+        self.block(pos, kind)
+    }
+
+    pub fn powered_rail(self, pos: impl Into<Pos>, shape: RailShape) -> Self {
+        self.block(pos, BlockKind::PoweredRail { powered: false, shape })
+    }
+
+    pub fn detector_rail(self, pos: impl Into<Pos>, shape: RailShape) -> Self {
+        self.block(pos, BlockKind::DetectorRail { powered: false, shape })
+    }
+
+    pub fn activator_rail(self, pos: impl Into<Pos>, shape: RailShape) -> Self {
+        self.block(pos, BlockKind::ActivatorRail { powered: false, shape })
+    }
+
+    pub fn pressure_plate(self, pos: impl Into<Pos>, kind: PlateKind) -> Self {
+        self.block(pos, BlockKind::PressurePlate { powered: false, kind, power: 0 })
+    }
+
+    pub fn dropper(self, pos: impl Into<Pos>, facing: Direction) -> Self {
+        self.block(pos, BlockKind::Dropper { facing, powered: false, cooldown: 0 })
+    }
+
+    pub fn dispenser(self, pos: impl Into<Pos>, facing: Direction) -> Self {
+        self.block(pos, BlockKind::Dispenser { facing, powered: false, cooldown: 0 })
+    }
+
+    pub fn chest(self, pos: impl Into<Pos>, items: u8) -> Self {
+        self.block(pos, BlockKind::Chest { items, max_items: default_chest_slots() })
+    }
+
+    pub fn barrel(self, pos: impl Into<Pos>, items: u8) -> Self {
+        self.block(pos, BlockKind::Barrel { items, max_items: default_chest_slots() })
+    }
+
+    /// A furnace with `fuel_ticks` of burn time, already burning if any.
+    pub fn furnace(self, pos: impl Into<Pos>, fuel_ticks: u32) -> Self {
+        self.block(pos, BlockKind::Furnace { fuel_ticks, cooking: fuel_ticks > 0 })
+    }
+
+    /// Finish the world, rejecting two blocks at one position.
+    pub fn build(self) -> Result<World, BuildError> {
+        let mut seen = SpatialSet::default();
+        if let Some(b) = self.blocks.iter().find(|b| !seen.insert(b.pos)) {
+            return Err(BuildError::DuplicatePosition(b.pos));
+        }
+        Ok(World { blocks: self.blocks })
+    }
+}
+
+// -------------------------------------------------
+// Simulation request / response
+// -------------------------------------------------
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+pub struct SimRequest {
+    pub ticks: u32,   // maximum ticks to simulate
+    pub world: World, // t = 0 state (raw user input)
+    #[serde(default = "default_true")]
+    pub early_exit: bool, // stop when stable & no timers running
+    #[serde(default, deserialize_with = "deserialize_events")]
+    pub events: Vec<(u32 /*tick*/, TimedEvent)>, // inputs applied at the start of a tick
+    #[serde(default)]
+    pub vibrations: Vec<(u32 /*tick*/, Pos, u8 /*frequency*/)>,
+    #[serde(default)]
+    pub minecart_events: Vec<(u32 /*tick*/, Pos, bool /*occupied*/)>, // detector rails
+    #[serde(default)]
+    pub entity_events: Vec<(u32 /*tick*/, Pos, EntityPressure)>, // load on pressure plates
+    #[serde(default)]
+    pub lightning_strikes: Vec<(u32 /*tick*/, Pos)>, // powers a lightning rod at the position
+    #[serde(default)]
+    pub parallel: bool, // update blocks on all cores; needs the `parallel` feature
+    #[serde(default)]
+    pub quasi_connectivity: bool, // Java Edition: pistons, droppers and dispensers also read the block above
+    #[serde(default)]
+    pub update_order: UpdateOrder, // order of each tick's block updates
+    #[serde(default)]
+    pub time_of_day: u32, // game time at tick 0, 0‑24000; 6000 is noon, 18000 midnight
+    #[serde(default = "default_time_advance")]
+    pub time_advance_per_tick: i32, // 0 pins the time of day
+    #[serde(default)]
+    pub seed: Option<u64>, // shuffles UpdateOrder::Random; None draws one from entropy
+}
+fn default_true() -> bool {
+    true
+}
+
+fn default_time_advance() -> i32 {
+    1
+}
+
+/// 100 ticks of an empty world with early exit; every other field takes
+/// the value it gets when omitted from JSON.
+impl Default for SimRequest {
+    fn default() -> Self {
+        SimRequest {
+            ticks: 100,
+            world: World::default(),
+            early_exit: default_true(),
+            events: vec![],
+            vibrations: vec![],
+            minecart_events: vec![],
+            entity_events: vec![],
+            lightning_strikes: vec![],
+            parallel: false,
+            quasi_connectivity: false,
+            update_order: UpdateOrder::default(),
+            time_of_day: 0,
+            time_advance_per_tick: default_time_advance(),
+            seed: None,
+        }
+    }
+}
+
+/// Order in which each tick's dirty blocks are updated. Every block reads
+/// the world as it was before the tick, so no redstone circuit (a dual-torch
+/// latch, say) behaves differently under another order, unlike in game.
+/// Only hoppers and pistons acting on the same block take turns in this
+/// order: when two hoppers race for a container's last slot, the first to
+/// update wins. The order of `TickDiff::changes` follows it too.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateOrder {
+    #[default]
+    Arbitrary,     // hash order; cheapest, but may differ between builds
+    MojangJava,    // Java's scheduled-tick priorities, ties by (x, y, z)
+    Deterministic, // by (x, y, z)
+    Random,        // shuffled by a PRNG seeded from SimRequest::seed, reseeded every tick
+}
+
+/// A projectile hitting a target block.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+pub struct TargetHitEvent {
+    #[serde(flatten)]
+    pub pos: Pos,
+    pub strength: u8,
+}
+
+/// An external input applied at the start of a tick, before any block updates.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[serde(rename_all = "snake_case")]
+pub enum TimedEvent {
+    TargetHit(TargetHitEvent),
+    LeverToggle(Pos),
+    ButtonPress(Pos), // ignored while the button is still pressed
+    BlockReplace(Pos, BlockKind), // replaces whatever was there
+    FurnaceFuel(Pos, u32 /*ticks*/), // adds burn time to a furnace and lights it
+}
+
+impl TimedEvent {
+    /// The position the event acts on.
+    pub fn pos(&self) -> Pos {
+        match self {
+            TimedEvent::TargetHit(hit) => hit.pos,
+            TimedEvent::LeverToggle(pos)
+            | TimedEvent::ButtonPress(pos)
+            | TimedEvent::BlockReplace(pos, _)
+            | TimedEvent::FurnaceFuel(pos, _) => *pos,
+        }
+    }
+}
+
+// a bare `{x, y, z, strength}` is still read as a target hit
+fn deserialize_events<'de, D: serde::Deserializer<'de>>(d: D) -> Result<Vec<(u32, TimedEvent)>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Entry {
+        Event(TimedEvent),
+        Hit(TargetHitEvent),
+    }
+    let entries = Vec::<(u32, Entry)>::deserialize(d)?;
+    Ok(entries
+        .into_iter()
+        .map(|(t, e)| match e {
+            Entry::Event(e) => (t, e),
+            Entry::Hit(h) => (t, TimedEvent::TargetHit(h)),
+        })
+        .collect())
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+pub struct BlockChange {
+    #[serde(flatten)]
+    pub pos: Pos,
+    #[serde(flatten)]
+    pub kind: BlockKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+pub struct TickDiff {
+    pub tick: u32,
+    pub changes: Vec<BlockChange>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed: Vec<Pos>, // positions left empty this tick (applied after `changes`)
+}
+
+impl TickDiff {
+    pub fn changed_positions(&self) -> impl Iterator<Item = Pos> + '_ {
+        self.changes.iter().map(|c| c.pos)
+    }
+
+    pub fn changed_kinds(&self) -> impl Iterator<Item = &BlockKind> + '_ {
+        self.changes.iter().map(|c| &c.kind)
+    }
+
+    pub fn find_change_at(&self, pos: Pos) -> Option<&BlockChange> {
+        self.changes.iter().find(|c| c.pos == pos)
+    }
+
+    /// Lamps that changed this tick and whether they are now lit.
+    pub fn changed_lamps(&self) -> impl Iterator<Item = (Pos, bool)> + '_ {
+        self.changes.iter().filter_map(|c| match c.kind {
+            BlockKind::Lamp { on } => Some((c.pos, on)),
+            _ => None,
+        })
+    }
+
+    /// Dust that changed this tick and its new power level.
+    pub fn changed_dust(&self) -> impl Iterator<Item = (Pos, u8)> + '_ {
+        self.changes.iter().filter_map(|c| match c.kind {
+            BlockKind::Dust { power, .. } => Some((c.pos, power)),
+            _ => None,
+        })
+    }
+
+    /// Torches that changed this tick and whether they are now lit.
+    pub fn changed_torches(&self) -> impl Iterator<Item = (Pos, bool)> + '_ {
+        self.changes.iter().filter_map(|c| match c.kind {
+            BlockKind::Torch { lit, .. } => Some((c.pos, lit)),
+            _ => None,
+        })
+    }
+
+    /// Repeaters that changed this tick and whether they now output.
+    pub fn changed_repeaters(&self) -> impl Iterator<Item = (Pos, bool)> + '_ {
+        self.changes.iter().filter_map(|c| match c.kind {
+            BlockKind::Repeater { powered, .. } => Some((c.pos, powered)),
+            _ => None,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[serde(rename_all = "lowercase")]
+pub enum LaunchKind {
+    Dispense, // a dispenser fired a projectile or used an item
+    Drop,     // a dropper dropped an item
+}
+
+/// A dropper or dispenser firing.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+pub struct LaunchEvent {
+    pub tick: u32,
+    #[serde(flatten)]
+    pub pos: Pos,
+    pub facing: Direction,
+    pub kind: LaunchKind,
+}
+
+/// A lightning strike that hit a lightning rod.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+pub struct LightningEvent {
+    pub tick: u32,
+    #[serde(flatten)]
+    pub pos: Pos,
+}
+
+/// A crafter crafting an item.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+pub struct CraftEvent {
+    pub tick: u32,
+    #[serde(flatten)]
+    pub pos: Pos,
+}
+
+/// A bell ringing, which alerts mobs within 48 blocks.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+pub struct BellRingEvent {
+    pub tick: u32,
+    #[serde(flatten)]
+    pub pos: Pos,
+    pub facing: Direction, // direction the bell swings
+}
+
+/// Number of ticks a bell keeps ringing after it is struck.
+const BELL_RING_TICKS: u8 = 2;
+
+/// Number of ticks a lightning rod stays powered after a strike.
+const LIGHTNING_ROD_TICKS: u8 = 8;
+
+/// Ticks a dropper or dispenser waits after firing before it can fire again.
+const LAUNCHER_COOLDOWN: u8 = 2;
+
+/// Ticks a crafter waits after crafting before it can craft again.
+const CRAFTER_COOLDOWN: u8 = 4;
+
+/// Burn time a comparator reads as full: one coal, 1600 game ticks.
+pub const FURNACE_MAX_FUEL: u32 = 800;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+#[serde(rename_all = "snake_case")]
+pub enum Termination {
+    #[default]
+    Stable,          // reached stable state (no external or internal changes)
+    MaxTicksReached, // hit user‑specified limit
+    PredicateMet,    // simulate_until's condition held (simulate_while's stopped holding)
+}
+
+/// The default is an empty run that settled immediately.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+pub struct SimResponse {
+    pub diffs: Vec<TickDiff>,
+    pub terminated: Termination,
+    #[serde(default)]
+    pub notes: Vec<(u32 /*tick*/, Pos, Instrument, u8 /*pitch*/)>, // note blocks played
+    #[serde(default)]
+    pub launch_events: Vec<LaunchEvent>, // droppers and dispensers fired
+    #[serde(default)]
+    pub lightning_events: Vec<LightningEvent>, // strikes that hit a lightning rod
+    #[serde(default)]
+    pub craft_events: Vec<CraftEvent>, // crafters crafted
+    #[serde(default)]
+    pub bell_events: Vec<BellRingEvent>, // bells rung by redstone
+    #[serde(default)]
+    pub oscillation_period: Option<u32>, // set when an early-exit run hit the limit while looping
+    #[serde(default)]
+    pub seed_used: u64, // the request's seed, or the one drawn for it; pass as `seed` to replay the run
+}
+
+impl SimResponse {
+    /// The world after the last simulated tick, rebuilt by replaying every
+    /// diff on top of `initial` (the world the request started from).
+    pub fn final_state(&self, initial: &World) -> SpatialGrid<BlockKind> {
+        self.state_at_tick(u32::MAX, initial)
+    }
+
+    /// The world as it was at the end of `tick` (0 = the initial world).
+    pub fn state_at_tick(&self, tick: u32, initial: &World) -> SpatialGrid<BlockKind> {
+        let mut world = initial.clone().into_map();
+        // diffs are in tick order, so nothing past the target is visited
+        for diff in self.diffs.iter().take_while(|d| d.tick <= tick) {
+            for c in &diff.changes {
+                world.insert(c.pos, c.kind.clone());
+            }
+            for p in &diff.removed {
+                world.remove(p);
+            }
+        }
+        world
+    }
+
+    /// [`BlockKind::current_power`] of the block at `pos` at the end of
+    /// `tick`; an empty position reads 0. O(T) in the number of diffs.
+    pub fn power_at(&self, initial: &World, pos: Pos, tick: u32) -> u8 {
+        let mut block = initial.blocks.iter().find(|b| b.pos == pos).map(|b| &b.kind);
+        for diff in self.diffs.iter().take_while(|d| d.tick <= tick) {
+            if let Some(c) = diff.find_change_at(pos) {
+                block = Some(&c.kind);
+            }
+            if diff.removed.contains(&pos) {
+                block = None;
+            }
+        }
+        block.map_or(0, BlockKind::current_power)
+    }
+
+    /// Whether the block at `pos` was powered at any tick, the initial
+    /// world included (see [`power_at`](Self::power_at)). O(T) in the
+    /// number of diffs.
+    pub fn was_ever_powered(&self, initial: &World, pos: Pos) -> bool {
+        self.first_powered_tick(initial, pos).is_some()
+    }
+
+    /// First tick at whose end the block at `pos` was powered; 0 when it
+    /// already was in `initial`. O(T) in the number of diffs.
+    pub fn first_powered_tick(&self, initial: &World, pos: Pos) -> Option<u32> {
+        if self.power_at(initial, pos, 0) > 0 {
+            return Some(0);
+        }
+        self.diffs
+            .iter()
+            .find(|d| d.find_change_at(pos).is_some_and(|c| c.kind.current_power() > 0))
+            .map(|d| d.tick)
+    }
+
+    /// Last tick in which the block at `pos` changed or was removed. O(T) in
+    /// the number of diffs.
+    pub fn last_changed_tick(&self, pos: Pos) -> Option<u32> {
+        self.diffs
+            .iter()
+            .rev()
+            .find(|d| d.find_change_at(pos).is_some() || d.removed.contains(&pos))
+            .map(|d| d.tick)
+    }
+}
+
+/// Why a simulation could not be run.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SimError {
+    NonAdjacentBlock(NonAdjacentError), // a block referenced a non‑adjacent neighbour
+    DeserializationFailed(String),      // the request JSON could not be parsed
+    InvalidBlockState(String),          // a block's fields are inconsistent with the world
+}
+
+impl core::fmt::Display for SimError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SimError::NonAdjacentBlock(e) => e.fmt(f),
+            SimError::DeserializationFailed(msg) => write!(f, "invalid request: {}", msg),
+            SimError::InvalidBlockState(msg) => write!(f, "invalid block state: {}", msg),
+        }
+    }
+}
+
+impl core::error::Error for SimError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            SimError::NonAdjacentBlock(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<WorldError> for SimError {
+    fn from(e: WorldError) -> Self {
+        SimError::InvalidBlockState(e.to_string())
+    }
+}
+
+impl From<NonAdjacentError> for SimError {
+    fn from(e: NonAdjacentError) -> Self {
+        SimError::NonAdjacentBlock(e)
+    }
+}
+
+/// Maximum number of blocks a piston can push, as in vanilla.
+const PISTON_PUSH_LIMIT: usize = 12;
+
+/// A block movement requested by a piston, applied after the tick's updates.
+enum PistonAction {
+    Extend { arm: Pos, facing: Direction, chain: Vec<Pos> },
+    Retract { arm: Pos, facing: Direction, sticky: bool },
+    ClearArm(Pos),
+}
+
+/// Everything one block's update asks of the rest of the tick. Collected
+/// per block (or per thread) and merged, so updates never touch each other.
+#[derive(Default)]
+struct TickEffects {
+    changes: Vec<BlockChange>,
+    next_dirty: SpatialSet,
+    piston_actions: Vec<PistonAction>,
+    // (source, destination) hopper moves, applied after all updates
+    hopper_transfers: Vec<(Pos, Pos)>,
+    notes: Vec<(u32, Pos, Instrument, u8)>,
+    launch_events: Vec<LaunchEvent>,
+    craft_events: Vec<CraftEvent>,
+    bell_events: Vec<BellRingEvent>,
+}
+
+impl TickEffects {
+    #[cfg(feature = "parallel")]
+    fn merge(mut self, other: TickEffects) -> TickEffects {
+        self.changes.extend(other.changes);
+        self.next_dirty.extend(other.next_dirty);
+        self.piston_actions.extend(other.piston_actions);
+        self.hopper_transfers.extend(other.hopper_transfers);
+        self.notes.extend(other.notes);
+        self.launch_events.extend(other.launch_events);
+        self.craft_events.extend(other.craft_events);
+        self.bell_events.extend(other.bell_events);
+        self
+    }
+}
+
+// Java's scheduled-tick priority, lowest first: repeaters feeding another
+// diode, then repeaters turning off, then other repeaters, then the rest
+fn java_tick_priority(world: &SpatialGrid<BlockKind>, pos: Pos) -> i8 {
+    match world.get(&pos) {
+        Some(BlockKind::Repeater { facing, powered, .. }) => {
+            let front = world.get(&(pos + *facing));
+            if matches!(front, Some(BlockKind::Repeater { .. } | BlockKind::Comparator { .. })) {
+                -3
+            } else if *powered {
+                -2
+            } else {
+                -1
+            }
+        }
+        _ => 0,
+    }
+}
+
+// the dirty positions in the order their updates run this tick
+fn update_sequence(world: &SpatialGrid<BlockKind>, dirty: &SpatialSet, order: UpdateOrder, seed: u64, tick: u32) -> Vec<Pos> {
+    let mut sequence: Vec<Pos> = dirty.iter().copied().collect();
+    match order {
+        UpdateOrder::Arbitrary => {}
+        UpdateOrder::MojangJava => sequence.sort_by_key(|p| (java_tick_priority(world, *p), p.x, p.y, p.z)),
+        UpdateOrder::Deterministic => sequence.sort_by_key(|p| (p.x, p.y, p.z)),
+        UpdateOrder::Random => {
+            use rand::seq::SliceRandom;
+            use rand::SeedableRng;
+            // sorted first so the shuffle does not depend on hash order; a
+            // fresh generator per tick keeps `restore` exact
+            sequence.sort_by_key(|p| (p.x, p.y, p.z));
+            let mut rng = rand::rngs::StdRng::seed_from_u64(seed ^ u64::from(tick).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+            sequence.shuffle(&mut rng);
+        }
+    }
+    sequence
+}
+
+// a seed for a random-order run that did not ask for one; without `std`
+// there is no entropy source, so every such run uses 0
+fn entropy_seed() -> u64 {
+    #[cfg(feature = "std")]
+    {
+        rand::random()
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        0
+    }
+}
+
+/// Update every dirty block from `snapshot`, the world as it was before the
+/// tick. Each block reads only the snapshot and writes only itself, so with
+/// the `parallel` feature the blocks can be updated on all cores.
+#[cfg_attr(not(feature = "parallel"), allow(unused_variables))]
+fn update_dirty(
+    world: &mut SpatialGrid<BlockKind>,
+    dirty: &[Pos],
+    snapshot: &SpatialGrid<BlockKind>,
+    triggered: &SpatialSet,
+    tick: u32,
+    parallel: bool,
+    quasi_connectivity: bool,
+) -> Result<TickEffects, SimError> {
+    #[cfg(feature = "parallel")]
+    if parallel {
+        use rayon::prelude::*;
+        let (updated, fx) = dirty
+            .par_iter()
+            .filter_map(|pos| snapshot.get(pos).map(|b| (*pos, b.clone())))
+            .try_fold(
+                || (Vec::new(), TickEffects::default()),
+                |(mut updated, mut fx), (pos, mut block)| {
+                    update_block(&pos, &mut block, snapshot, triggered, tick, quasi_connectivity, &mut fx)?;
+                    updated.push((pos, block));
+                    Ok::<_, SimError>((updated, fx))
+                },
+            )
+            .try_reduce(
+                || (Vec::new(), TickEffects::default()),
+                |(mut a, fa), (b, fb)| {
+                    a.extend(b);
+                    Ok((a, fa.merge(fb)))
+                },
+            )?;
+        world.extend(updated);
+        return Ok(fx);
+    }
+
+    let mut fx = TickEffects::default();
+    for pos in dirty {
+        if let Some(block) = world.get_mut(pos) {
+            update_block(pos, block, snapshot, triggered, tick, quasi_connectivity, &mut fx)?;
+        }
+    }
+    Ok(fx)
+}
+
+/// One block's update for this tick.
+fn update_block(
+    pos: &Pos,
+    block: &mut BlockKind,
+    snapshot: &SpatialGrid<BlockKind>,
+    triggered: &SpatialSet,
+    tick: u32,
+    quasi_connectivity: bool,
+    fx: &mut TickEffects,
+) -> Result<(), SimError> {
+    let mut changed = false;
+    let mut mark_out = false;
+    let input_positions = block.input_positions(*pos);
+    // Java's quasi-connectivity: powering the space above counts too, but
+    // is only noticed when something else updates the block
+    let powered_above = quasi_connectivity
+        && matches!(block, BlockKind::Piston { .. } | BlockKind::Dropper { .. } | BlockKind::Dispenser { .. })
+        && receives_power(snapshot, *pos + Direction::Up);
+    #[cfg(feature = "tracing")]
+    let old_state = block.clone();
+    match block {
+        BlockKind::Button { ticks_remaining, .. } if *ticks_remaining > 0 => {
+            let prev_output = 15;
+            *ticks_remaining -= 1;
+            let new_output = if *ticks_remaining > 0 { 15 } else { 0 };
+            changed = true;
+            if prev_output != new_output {
+                mark_out = true;
+            }
+            if *ticks_remaining > 0 {
+                fx.next_dirty.insert(*pos);
+            }
+        }
+        BlockKind::Repeater { delay, ticks_remaining, powered, facing, locked } => {
+            // a powered repeater or comparator pointing into either side locks it
+            let mut side_locked = false;
+            for side in facing.sides() {
+                let n = *pos + side;
+                if let Some(
+                    nb @ (BlockKind::Repeater { .. } | BlockKind::Comparator { .. }),
+                ) = snapshot.get(&n)
+                {
+                    if output_towards(nb, side.opposite()) > 0 {
+                        side_locked = true;
+                    }
+                }
+            }
+            if *locked != side_locked {
+                *locked = side_locked;
+                changed = true;
+            }
+
+            if !*locked {
+                let back = facing.opposite();
+                let n = *pos + back;
+                let mut input = 0;
+                if let Some(nb) = snapshot.get(&n) {
+                    input = output_towards(nb, *facing);
+                }
+
+                let prev_output = if *powered { 15 } else { 0 };
+
+                if input > 0 {
+                    if !*powered && *ticks_remaining == 0 {
+                        *ticks_remaining = *delay;
+                    }
+                } else {
+                    *powered = false;
+                    *ticks_remaining = 0;
+                }
+
+                if *ticks_remaining > 0 {
+                    *ticks_remaining -= 1;
+                    if *ticks_remaining == 0 && input > 0 {
+                        *powered = true;
+                    }
+                }
+
+                let new_output = if *powered { 15 } else { 0 };
+
+                if prev_output != new_output || *ticks_remaining != 0 {
+                    changed = true;
+                }
+
+                if prev_output != new_output {
+                    mark_out = true;
+                }
+
+                if *ticks_remaining > 0 {
+                    fx.next_dirty.insert(*pos);
+                }
+            }
+        }
+        BlockKind::Comparator { output, facing, mode } => {
+            // a container behind the comparator is read by fill level
+            let rear = *pos + facing.opposite();
+            let rear_in = snapshot
+                .get(&rear)
+                .map(|nb| container_signal_strength(nb).unwrap_or_else(|| output_towards(nb, *facing)))
+                .unwrap_or(0);
+            let new_out = match mode {
+                ComparatorMode::Compare => {
+                    let mut out = rear_in;
+                    for n in &input_positions {
+                        if let Some(nb) = snapshot.get(n).filter(|_| *n != rear) {
+                            let dir = dir_from_to(*n, *pos)?;
+                            out = out.max(output_towards(nb, dir));
+                        }
+                    }
+                    out
+                }
+                ComparatorMode::Subtract => {
+                    let mut side_in = 0;
+                    for side in facing.sides() {
+                        let n = *pos + side;
+                        if let Some(nb) = snapshot.get(&n) {
+                            side_in = side_in.max(output_towards(nb, side.opposite()));
+                        }
+                    }
+                    rear_in.saturating_sub(side_in)
+                }
+            };
+            if *output != new_out {
+                *output = new_out;
+                changed = true;
+                mark_out = true;
+            }
+        }
+        BlockKind::Dust { power, connections } => {
+            let new_conn = dust_connections(snapshot, *pos);
+            if *connections != new_conn {
+                // positions it no longer feeds must re-evaluate too
+                fx.next_dirty.extend(dust_positions(*pos, connections));
+                *connections = new_conn;
+                changed = true;
+                mark_out = true;
+            }
+            let mut new_power = 0;
+            for n in &dust_positions(*pos, connections) {
+                if let Some(nb) = snapshot.get(n) {
+                    new_power = new_power.max(dust_input(nb, *n, *pos)?);
+                }
+            }
+            if *power != new_power {
+                *power = new_power;
+                changed = true;
+                mark_out = true;
+            }
+        }
+        BlockKind::Lamp { on } => {
+            let mut powered = false;
+            for n in &input_positions {
+                if let Some(nb) = snapshot.get(n) {
+                    let dir = dir_from_to(*n, *pos)?;
+                    if output_towards(nb, dir) > 0 {
+                        powered = true;
+                        break;
+                    }
+                }
+            }
+            if *on != powered {
+                *on = powered;
+                changed = true;
+            }
+        }
+        BlockKind::Torch { lit, facing, burnout_counter, burnout_cooldown, burned_out } => {
+            let before = (*lit, *burnout_counter, *burnout_cooldown, *burned_out);
+            let mut powered = false;
+            let n = *pos + *facing;
+            if let Some(nb) = snapshot.get(&n) {
+                if output_towards(nb, facing.opposite()) > 0 {
+                    powered = true;
+                }
+            }
+            if *burnout_cooldown > 0 {
+                *burnout_cooldown -= 1;
+                if *burnout_cooldown == 0 {
+                    if *burned_out {
+                        *burned_out = false;
+                        *burnout_counter = 0;
+                    } else {
+                        *burnout_counter = burnout_counter.saturating_sub(1);
+                        if *burnout_counter > 0 {
+                            *burnout_cooldown = TORCH_TOGGLE_DECAY;
+                        }
+                    }
+                }
+            }
+            let new_lit = !powered && !*burned_out;
+            if *lit != new_lit {
+                *lit = new_lit;
+                *burnout_counter += 1;
+                if *burnout_counter >= TORCH_BURNOUT_TOGGLES {
+                    *lit = false;
+                    *burned_out = true;
+                    *burnout_cooldown = TORCH_BURNOUT_TICKS;
+                } else if *burnout_cooldown == 0 {
+                    *burnout_cooldown = TORCH_TOGGLE_DECAY;
+                }
+            }
+            changed = before != (*lit, *burnout_counter, *burnout_cooldown, *burned_out);
+            mark_out = before.0 != *lit;
+            if *burnout_cooldown > 0 {
+                fx.next_dirty.insert(*pos);
+            }
+        }
+        BlockKind::Piston { extended, facing, sticky } => {
+            let mut powered = powered_above;
+            for n in &input_positions {
+                if let Some(nb) = snapshot.get(n) {
+                    let dir = dir_from_to(*n, *pos)?;
+                    if output_towards(nb, dir) > 0 {
+                        powered = true;
+                        break;
+                    }
+                }
+            }
+            if *extended != powered {
+                let arm = *pos + *facing;
+                if powered {
+                    // a piston that cannot push its chain stays retracted
+                    if let Some(chain) = push_chain(snapshot, *pos, *facing) {
+                        fx.piston_actions.push(PistonAction::Extend {
+                            arm,
+                            facing: *facing,
+                            chain,
+                        });
+                        *extended = true;
+                        changed = true;
+                        mark_out = true;
+                    }
+                } else {
+                    fx.piston_actions.push(PistonAction::Retract {
+                        arm,
+                        facing: *facing,
+                        sticky: *sticky,
+                    });
+                    *extended = false;
+                    changed = true;
+                    mark_out = true;
+                }
+            }
+        }
+        BlockKind::Door { open, half, .. } => {
+            // powering either half opens both
+            let other = door_partner(*pos, *half);
+            let powered = receives_power(snapshot, *pos)
+                || (matches!(snapshot.get(&other), Some(BlockKind::Door { .. }))
+                    && receives_power(snapshot, other));
+            if *open != powered {
+                *open = powered;
+                changed = true;
+            }
+        }
+        BlockKind::CopperBulb { lit, powered, .. } => {
+            let now = receives_power(snapshot, *pos);
+            if *powered != now {
+                *powered = now;
+                // toggles only on the rising edge
+                if now {
+                    *lit = !*lit;
+                }
+                changed = true;
+            }
+        }
+        BlockKind::NoteBlock { instrument, pitch, triggered, powered } => {
+            if *triggered {
+                *triggered = false;
+                changed = true;
+            }
+            let now = receives_power(snapshot, *pos);
+            if *powered != now {
+                *powered = now;
+                changed = true;
+                // plays only on the rising edge
+                if now {
+                    *triggered = true;
+                    fx.notes.push((tick, *pos, *instrument, *pitch));
+                    fx.next_dirty.insert(*pos);
+                }
+            }
+        }
+        BlockKind::PoweredRail { powered, .. } | BlockKind::ActivatorRail { powered, .. } => {
+            let now = rail_line_powered(snapshot, *pos);
+            if *powered != now {
+                *powered = now;
+                changed = true;
+                mark_out = true;
+            }
+        }
+        BlockKind::Dropper { facing, powered, cooldown } => {
+            let before = (*powered, *cooldown);
+            let input = receives_power(snapshot, *pos) || powered_above;
+            if launcher_step(powered, cooldown, input, LAUNCHER_COOLDOWN) {
+                let kind = LaunchKind::Drop;
+                fx.launch_events.push(LaunchEvent { tick, pos: *pos, facing: *facing, kind });
+            }
+            changed = (*powered, *cooldown) != before;
+            if *cooldown > 0 {
+                fx.next_dirty.insert(*pos);
+            }
+        }
+        BlockKind::Dispenser { facing, powered, cooldown } => {
+            let before = (*powered, *cooldown);
+            let input = receives_power(snapshot, *pos) || powered_above;
+            if launcher_step(powered, cooldown, input, LAUNCHER_COOLDOWN) {
+                let kind = LaunchKind::Dispense;
+                fx.launch_events.push(LaunchEvent { tick, pos: *pos, facing: *facing, kind });
+            }
+            changed = (*powered, *cooldown) != before;
+            if *cooldown > 0 {
+                fx.next_dirty.insert(*pos);
+            }
+        }
+        BlockKind::Crafter { powered, cooldown, craft_count } => {
+            let before = (*powered, *cooldown);
+            if launcher_step(powered, cooldown, receives_power(snapshot, *pos), CRAFTER_COOLDOWN) {
+                *craft_count = craft_count.wrapping_add(1);
+                fx.craft_events.push(CraftEvent { tick, pos: *pos });
+                // comparators reading the count sit on any side
+                mark_around(*pos, &mut fx.next_dirty);
+            }
+            changed = (*powered, *cooldown) != before;
+            if *cooldown > 0 {
+                fx.next_dirty.insert(*pos);
+            }
+        }
+        BlockKind::Bell { facing, ringing, powered, ticks_remaining, .. } => {
+            let before = (*ringing, *powered, *ticks_remaining);
+            let input = receives_power(snapshot, *pos);
+            *ticks_remaining = ticks_remaining.saturating_sub(1);
+            if input && !*powered {
+                *ticks_remaining = BELL_RING_TICKS;
+                fx.bell_events.push(BellRingEvent { tick, pos: *pos, facing: *facing });
+            }
+            *powered = input;
+            *ringing = *ticks_remaining > 0;
+            changed = (*ringing, *powered, *ticks_remaining) != before;
+            if *ticks_remaining > 0 {
+                fx.next_dirty.insert(*pos);
+            }
+        }
+        BlockKind::Trapdoor { open, .. } => {
+            let powered = receives_power(snapshot, *pos);
+            if *open != powered {
+                *open = powered;
+                changed = true;
+            }
+        }
+        BlockKind::FenceGate { open, .. } => {
+            let mut powered = false;
+            for n in &input_positions {
+                if let Some(nb) = snapshot.get(n) {
+                    powered |= output_towards(nb, dir_from_to(*n, *pos)?) > 0;
+                }
+            }
+            if *open != powered {
+                *open = powered;
+                changed = true;
+            }
+        }
+        BlockKind::Target { power, ticks_remaining }
+        | BlockKind::SculkSensor { power, ticks_remaining, .. }
+        | BlockKind::CalibratedSculkSensor { power, ticks_remaining, .. }
+            if *ticks_remaining > 0 =>
+        {
+            *ticks_remaining -= 1;
+            changed = true;
+            if *ticks_remaining == 0 {
+                *power = 0;
+                mark_out = true;
+            } else {
+                fx.next_dirty.insert(*pos);
+            }
+        }
+        BlockKind::LightningRod { powered, ticks_remaining } if *ticks_remaining > 0 => {
+            *ticks_remaining -= 1;
+            changed = true;
+            if *ticks_remaining == 0 {
+                *powered = false;
+                mark_out = true;
+            } else {
+                fx.next_dirty.insert(*pos);
+            }
+        }
+        BlockKind::Furnace { fuel_ticks, cooking: cooking @ true } => {
+            let level = furnace_level(*fuel_ticks);
+            *fuel_ticks = fuel_ticks.saturating_sub(1);
+            *cooking = *fuel_ticks > 0;
+            changed = true;
+            if *cooking {
+                fx.next_dirty.insert(*pos);
+            }
+            // wakes any comparator reading it
+            if furnace_level(*fuel_ticks) != level {
+                mark_around(*pos, &mut fx.next_dirty);
+            }
+        }
+        BlockKind::PistonArm { .. } => {
+            fx.piston_actions.push(PistonAction::ClearArm(*pos));
+        }
+        BlockKind::Hopper { enabled, facing, items } => {
+            let mut powered = false;
+            for n in &input_positions {
+                if let Some(nb) = snapshot.get(n) {
+                    let dir = dir_from_to(*n, *pos)?;
+                    if output_towards(nb, dir) > 0 {
+                        powered = true;
+                        break;
+                    }
+                }
+            }
+            let new_enabled = !powered;
+            if *enabled != new_enabled {
+                *enabled = new_enabled;
+                changed = true;
+            }
+            if *enabled && *items > 0 {
+                let target = *pos + *facing;
+                if snapshot.get(&target).is_some_and(|b| container_contents(b).is_some()) {
+                    fx.hopper_transfers.push((*pos, target));
+                }
+            }
+        }
+        BlockKind::OpaqueSolid { strong_power } => {
+            // only direct sources strongly power a block; dust and
+            // other solids do not
+            let mut new_power = 0;
+            for n in &input_positions {
+                if let Some(
+                    nb @ (BlockKind::Lever { .. }
+                    | BlockKind::Button { .. }
+                    | BlockKind::Torch { .. }
+                    | BlockKind::Repeater { .. }
+                    | BlockKind::Comparator { .. }
+                    | BlockKind::Observer { .. }),
+                ) = snapshot.get(n)
+                {
+                    let dir = dir_from_to(*n, *pos)?;
+                    new_power = new_power.max(output_towards(nb, dir));
+                }
+            }
+            if *strong_power != new_power {
+                *strong_power = new_power;
+                changed = true;
+                mark_out = true;
+            }
+        }
+        BlockKind::TripwireHook { attached, powered, facing } => {
+            let (new_attached, tripped) = tripwire_line(snapshot, *pos, *facing);
+            // breaking an attached line gives a one-tick pulse
+            let broke = *attached && !new_attached;
+            let new_powered = (new_attached && tripped) || broke;
+            if (*attached, *powered) != (new_attached, new_powered) {
+                *attached = new_attached;
+                *powered = new_powered;
+                changed = true;
+                mark_out = true;
+            }
+            if broke {
+                fx.next_dirty.insert(*pos);
+            }
+        }
+        // a wire next to a changed block passes the change on to its hooks
+        BlockKind::Tripwire { .. } => fx.next_dirty.extend(tripwire_hooks(snapshot, *pos)),
+        BlockKind::Observer { powered, .. } => {
+            if *powered {
+                // the pulse lasts exactly one tick
+                *powered = false;
+                changed = true;
+                mark_out = true;
+            } else if triggered.contains(pos) {
+                *powered = true;
+                changed = true;
+                mark_out = true;
+                fx.next_dirty.insert(*pos);
+            }
+        }
+        _ => {}
+    }
+
+    if changed {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(pos = %pos, old_state = ?old_state, new_state = ?block, "block updated");
+        fx.changes.push(BlockChange { pos: *pos, kind: block.clone() });
+    }
+    if mark_out {
+        mark_outputs(block, *pos, &mut fx.next_dirty);
+    }
+    Ok(())
+}
+
+// -------------------------------------------------
+// Tick helpers
+// -------------------------------------------------
+// helper to query output from a block toward a direction
+fn output_towards(block: &BlockKind, dir: Direction) -> u8 {
+    match block {
+        BlockKind::Lever { on: true, facing } if *facing == dir => 15,
+        BlockKind::Button { ticks_remaining, facing }
+            if *ticks_remaining > 0 && *facing == dir => 15,
+        BlockKind::Repeater { powered: true, facing, .. } if *facing == dir => 15,
+        // `output` already reflects the comparator's mode
+        BlockKind::Comparator { output, facing, .. } if *output > 0 && *facing == dir => {
+            *output
+        }
+        BlockKind::Torch { lit: true, facing, .. } if dir != *facing => 15,
+        BlockKind::Observer { powered: true, facing } if dir == facing.opposite() => 15,
+        BlockKind::Dust { power, connections } if dust_points(connections, dir) => *power,
+        BlockKind::OpaqueSolid { strong_power } => *strong_power,
+        BlockKind::RedstoneBlock => 15,
+        BlockKind::Target { power, .. }
+        | BlockKind::SculkSensor { power, .. }
+        | BlockKind::CalibratedSculkSensor { power, .. } => *power,
+        BlockKind::DetectorRail { powered: true, .. } => 15,
+        BlockKind::PressurePlate { powered: true, power, .. } if dir != Direction::Up => *power,
+        BlockKind::TripwireHook { powered: true, .. } => 15,
+        BlockKind::DaylightSensor { power, .. } if dir != Direction::Down => *power,
+        BlockKind::LightningRod { powered: true, .. } => 15,
+        _ => 0,
+    }
+}
+
+// time of day, 0..DAY_TICKS, during `tick`
+fn time_at(time_of_day: u32, advance: i32, tick: u32) -> u32 {
+    (i64::from(time_of_day) + i64::from(advance) * i64::from(tick)).rem_euclid(DAY_TICKS) as u32
+}
+
+/// Power of a daylight sensor at `time` (game ticks into the day):
+/// `sin(time·π/12000)` scaled to 0‑15 and clamped at 0, so 15 at noon (6000)
+/// and 0 all night; an inverted sensor gives `15 - power`.
+pub fn daylight_power(time: u32, inverted: bool) -> u8 {
+    use core::f32::consts::PI;
+    let x = (i64::from(time) % DAY_TICKS) as f32 * PI / 12_000.0;
+    // Bhaskara I's approximation of sin on 0..π; exact at 0, π/2 and π, and
+    // needs no float library in no_std builds
+    let sun = if x < PI {
+        let y = x * (PI - x);
+        16.0 * y / (5.0 * PI * PI - 4.0 * y)
+    } else {
+        0.0
+    };
+    let power = (sun * 15.0) as u8;
+    if inverted {
+        15 - power
+    } else {
+        power
+    }
+}
+
+// (attached, tripped) for the hook at `hook`: whether an unbroken line of
+// tripwire leads to a hook facing back, and whether any wire on it is powered
+fn tripwire_line(world: &impl BlockLookup, hook: Pos, facing: Direction) -> (bool, bool) {
+    let (mut p, mut tripped) = (hook, false);
+    for _ in 0..=TRIPWIRE_MAX_WIRES {
+        p = p + facing;
+        match world.get(&p) {
+            Some(BlockKind::Tripwire { powered }) => tripped |= *powered,
+            Some(BlockKind::TripwireHook { facing: f, .. }) if *f == facing.opposite() => return (true, tripped),
+            _ => break,
+        }
+    }
+    (false, false)
+}
+
+// hooks at either end of the tripwire lines through `wire`
+fn tripwire_hooks(world: &impl BlockLookup, wire: Pos) -> Vec<Pos> {
+    let mut hooks = Vec::new();
+    for d in DUST_SIDES {
+        let mut p = wire;
+        for _ in 0..=TRIPWIRE_MAX_WIRES {
+            p = p + d;
+            match world.get(&p) {
+                Some(BlockKind::Tripwire { .. }) => continue,
+                Some(BlockKind::TripwireHook { facing, .. }) if *facing == d.opposite() => hooks.push(p),
+                _ => {}
+            }
+            break;
+        }
+    }
+    hooks
+}
+
+fn mark_outputs(block: &BlockKind, pos: Pos, set: &mut SpatialSet) {
+    for n in block.output_positions(pos) {
+        set.insert(n);
+    }
+}
+
+// power dust at `pos` takes from the block `nb` at one of its dust positions
+fn dust_input(nb: &BlockKind, n: Pos, pos: Pos) -> Result<u8, SimError> {
+    Ok(match nb {
+        BlockKind::Dust { power, .. } => power.saturating_sub(1),
+        // slope positions only carry dust‑to‑dust power
+        _ if !is_adjacent(n, pos) => 0,
+        BlockKind::OpaqueSolid { .. } | BlockKind::RedstoneBlock => {
+            output_towards(nb, dir_from_to(n, pos)?).saturating_sub(1)
+        }
+        _ => output_towards(nb, dir_from_to(n, pos)?),
+    })
+}
+
+// dust that reads power from the dust at `pos`, i.e. lists it among its
+// dust positions
+fn dust_fed_by(world: &SpatialGrid<BlockKind>, pos: Pos) -> Vec<Pos> {
+    let mut candidates = vec![pos + Direction::Up];
+    for d in DUST_SIDES {
+        let side = pos + d;
+        candidates.push(side);
+        candidates.push(side + Direction::Up);
+        candidates.push(side + Direction::Down);
+    }
+    candidates.retain(|q| match world.get(q) {
+        Some(BlockKind::Dust { connections, .. }) => dust_positions(*q, connections).contains(&pos),
+        _ => false,
+    });
+    candidates
+}
+
+/// Settle every dust network that contains a dirty dust block within the
+/// current tick. The network's power is cleared, each dust is seeded with
+/// what its non-dust neighbours supply, and power then floods outward from
+/// 15 down to 1, visiting only dust. Dust that changed is returned and its
+/// outputs are added to `dirty`, so the blocks it feeds update this tick.
+fn propagate_dust(
+    world: &mut SpatialGrid<BlockKind>,
+    dirty: &mut SpatialSet,
+) -> Result<Vec<BlockChange>, SimError> {
+    // every dust linked to a dirty one, in either direction
+    let mut queue: VecDeque<Pos> =
+        dirty.iter().filter(|p| matches!(world.get(*p), Some(BlockKind::Dust { .. }))).copied().collect();
+    let mut network: SpatialSet = queue.iter().copied().collect();
+    while let Some(pos) = queue.pop_front() {
+        let Some(BlockKind::Dust { connections, .. }) = world.get(&pos) else { continue };
+        let feeding = dust_positions(pos, connections)
+            .into_iter()
+            .filter(|n| matches!(world.get(n), Some(BlockKind::Dust { .. })));
+        for n in feeding.chain(dust_fed_by(world, pos)) {
+            if network.insert(n) {
+                queue.push_back(n);
+            }
+        }
+    }
+
+    let mut power: SpatialGrid<u8> = SpatialGrid::default();
+    let mut levels: Vec<Vec<Pos>> = vec![Vec::new(); 16];
+    for pos in &network {
+        let Some(BlockKind::Dust { connections, .. }) = world.get(pos) else { continue };
+        let mut seed = 0;
+        for n in dust_positions(*pos, connections) {
+            match world.get(&n) {
+                None | Some(BlockKind::Dust { .. }) => {}
+                Some(nb) => seed = seed.max(dust_input(nb, n, *pos)?.min(15)),
+            }
+        }
+        power.insert(*pos, seed);
+        levels[seed as usize].push(*pos);
+    }
+    for level in (2..=15u8).rev() {
+        for pos in core::mem::take(&mut levels[level as usize]) {
+            // already raised past this level by a stronger path
+            if power[&pos] != level {
+                continue;
+            }
+            for n in dust_fed_by(world, pos) {
+                if let Some(p) = power.get_mut(&n).filter(|p| **p < level - 1) {
+                    *p = level - 1;
+                    levels[(level - 1) as usize].push(n);
+                }
+            }
+        }
+    }
+
+    let mut changes = Vec::new();
+    for (pos, new_power) in power {
+        let Some(block) = world.get_mut(&pos) else { continue };
+        if let BlockKind::Dust { power, .. } = block {
+            if *power == new_power {
+                continue;
+            }
+            #[cfg(feature = "tracing")]
+            tracing::trace!(pos = %pos, old_power = *power, new_power, "dust settled");
+            *power = new_power;
+        }
+        changes.push(BlockChange { pos, kind: block.clone() });
+        mark_outputs(block, pos, dirty);
+    }
+    Ok(changes)
+}
+
+// whether any neighbour of `pos` emits power into it
+fn receives_power(world: &impl BlockLookup, pos: Pos) -> bool {
+    pos.neighbors()
+        .iter()
+        .any(|(n, d)| world.get(n).is_some_and(|nb| output_towards(nb, d.opposite()) > 0))
+}
+
+// whether a powered/activator rail is powered directly or through a line
+// of up to RAIL_POWER_RANGE linked rails of the same kind
+fn rail_line_powered(world: &impl BlockLookup, start: Pos) -> bool {
+    let Some(kind) = world.get(&start).map(core::mem::discriminant) else {
+        return false;
+    };
+    let mut seen: SpatialSet = SpatialSet::from_iter([start]);
+    let mut frontier = vec![start];
+    for _ in 0..=RAIL_POWER_RANGE {
+        let mut next = Vec::new();
+        for p in frontier {
+            if receives_power(world, p) {
+                return true;
+            }
+            if let Some(
+                BlockKind::PoweredRail { shape, .. } | BlockKind::ActivatorRail { shape, .. },
+            ) = world.get(&p)
+            {
+                for n in shape.linked_positions(p) {
+                    let same = world.get(&n).map(core::mem::discriminant) == Some(kind);
+                    if same && seen.insert(n) {
+                        next.push(n);
+                    }
+                }
+            }
+        }
+        frontier = next;
+    }
+    false
+}
+
+// advance a dropper/dispenser/crafter by one tick; returns whether it fires,
+// which happens on a rising edge once the cooldown has run out
+fn launcher_step(powered: &mut bool, cooldown: &mut u8, input: bool, cooldown_ticks: u8) -> bool {
+    *cooldown = cooldown.saturating_sub(1);
+    let rising = input && !*powered;
+    *powered = input;
+    if rising && *cooldown == 0 {
+        *cooldown = cooldown_ticks;
+        return true;
+    }
+    false
+}
+
+// the other half of a door
+fn door_partner(pos: Pos, half: DoorHalf) -> Pos {
+    match half {
+        DoorHalf::Lower => pos + Direction::Up,
+        DoorHalf::Upper => pos + Direction::Down,
+    }
+}
+
+fn mark_around(pos: Pos, set: &mut SpatialSet) {
+    set.insert(pos);
+    for (n, _) in pos.neighbors() {
+        set.insert(n);
+    }
+}
+
+fn is_movable(block: &BlockKind) -> bool {
+    !matches!(block, BlockKind::Piston { extended: true, .. } | BlockKind::PistonArm { .. })
+}
+
+// blocks a piston at `pos` would push, nearest first; `None` if blocked
+fn push_chain(world: &impl BlockLookup, pos: Pos, facing: Direction) -> Option<Vec<Pos>> {
+    let mut chain = Vec::new();
+    let mut p = pos + facing;
+    while let Some(b) = world.get(&p) {
+        if !is_movable(b) || chain.len() == PISTON_PUSH_LIMIT {
+            return None;
+        }
+        chain.push(p);
+        p = p + facing;
+    }
+    Some(chain)
+}
+
+// -------------------------------------------------
+// Public entry point
+// -------------------------------------------------
+/// Simulate the world for `request.ticks` or until it becomes stable.
+/// Returns per‑tick diffs only for blocks that actually changed.
+pub fn simulate(request: SimRequest) -> Result<SimResponse, SimError> {
+    run_to_end(request.into_iter())
+}
+
+/// Like `simulate`, but stop as soon as `predicate` holds for the world at
+/// the end of a tick (or for the initial world), with
+/// `Termination::PredicateMet`. The run still ends early if it settles or
+/// reaches `request.ticks` first.
+pub fn simulate_until<F>(request: SimRequest, predicate: F) -> Result<SimResponse, SimError>
+where
+    F: Fn(&SpatialGrid<BlockKind>) -> bool,
+{
+    let mut sim = request.into_iter();
+    let mut diffs = Vec::new();
+    if sim.error.is_none() && predicate(&sim.world) {
+        sim.terminated = Some(Termination::PredicateMet);
+    }
+    while let Some(diff) = sim.next() {
+        if !diff.changes.is_empty() || !diff.removed.is_empty() {
+            diffs.push(diff);
+        }
+        if predicate(&sim.world) {
+            sim.terminated = Some(Termination::PredicateMet);
+            break;
+        }
+    }
+    finish(sim, diffs)
+}
+
+/// Like `simulate`, but stop as soon as `predicate` no longer holds, with
+/// `Termination::PredicateMet`.
+pub fn simulate_while<F>(request: SimRequest, predicate: F) -> Result<SimResponse, SimError>
+where
+    F: Fn(&SpatialGrid<BlockKind>) -> bool,
+{
+    simulate_until(request, |world| !predicate(world))
+}
+
+/// Run every request and return the results in the same order. With the
+/// `parallel` feature the requests are spread over all cores (each one
+/// still runs on a single core); without it they run one after another.
+pub fn simulate_many(requests: Vec<SimRequest>) -> Vec<Result<SimResponse, SimError>> {
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        requests.into_par_iter().map(simulate).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        requests.into_iter().map(simulate).collect()
+    }
+}
+
+fn run_to_end(mut sim: SimulationIterator) -> Result<SimResponse, SimError> {
+    let diffs: Vec<TickDiff> = sim.by_ref().filter(|d| !d.changes.is_empty() || !d.removed.is_empty()).collect();
+    finish(sim, diffs)
+}
+
+// the response for a run that has stopped, given its non-empty diffs
+fn finish(mut sim: SimulationIterator, diffs: Vec<TickDiff>) -> Result<SimResponse, SimError> {
+    if let Some(e) = sim.error.take() {
+        return Err(e);
+    }
+    let terminated = sim.terminated.take().unwrap_or(Termination::MaxTicksReached);
+    // a run that never settled is checked for a cycle over its second half,
+    // leaving the first half for start-up transients
+    let oscillation_period = match terminated {
+        Termination::MaxTicksReached if sim.early_exit => analysis::detect_period(&diffs, sim.max_ticks / 2),
+        _ => None,
+    };
+    Ok(SimResponse {
+        diffs,
+        terminated,
+        notes: sim.notes,
+        launch_events: sim.launch_events,
+        lightning_events: sim.lightning_events,
+        craft_events: sim.craft_events,
+        bell_events: sim.bell_events,
+        oscillation_period,
+        seed_used: sim.seed,
+    })
+}
+
+// -------------------------------------------------
+// Step‑by‑step simulation
+// -------------------------------------------------
+/// Runs a request one tick at a time, so callers can inspect or modify the
+/// world between ticks. Each `next()` performs one tick and returns its diff
+/// (empty while only timers are running), or `None` once the world is stable
+/// or the tick limit is reached.
+#[derive(Clone)]
+pub struct SimulationIterator {
+    world: SpatialGrid<BlockKind>,
+    dirty: SpatialSet,
+    // observers whose watched block changed during the previous tick
+    triggered: SpatialSet,
+    tick: u32,
+    max_ticks: u32,
+    early_exit: bool,
+    parallel: bool,
+    quasi_connectivity: bool,
+    update_order: UpdateOrder,
+    seed: u64,
+    time_of_day: u32,
+    time_advance_per_tick: i32,
+    events: Vec<(u32, TimedEvent)>,
+    vibrations: Vec<(u32, Pos, u8)>,
+    minecart_events: Vec<(u32, Pos, bool)>,
+    entity_events: Vec<(u32, Pos, EntityPressure)>,
+    lightning_strikes: Vec<(u32, Pos)>,
+    notes: Vec<(u32, Pos, Instrument, u8)>,
+    launch_events: Vec<LaunchEvent>,
+    lightning_events: Vec<LightningEvent>,
+    craft_events: Vec<CraftEvent>,
+    bell_events: Vec<BellRingEvent>,
+    terminated: Option<Termination>,
+    error: Option<SimError>,
+    // peek_next_changes' answer, dropped by anything that changes the state
+    lookahead: Option<Option<TickDiff>>,
+}
+
+/// The state of a `SimulationIterator` between ticks, taken with
+/// `snapshot` and put back with `restore` to branch a run. The request's
+/// settings and scheduled events are not part of it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WorldSnapshot {
+    tick: u32,
+    blocks: Vec<(Pos, BlockKind)>,
+    dirty: Vec<Pos>,
+    triggered: Vec<Pos>,
+    notes: Vec<(u32, Pos, Instrument, u8)>,
+    launch_events: Vec<LaunchEvent>,
+    lightning_events: Vec<LightningEvent>,
+    #[serde(default)]
+    craft_events: Vec<CraftEvent>,
+    #[serde(default)]
+    bell_events: Vec<BellRingEvent>,
+    terminated: Option<Termination>,
+}
+
+impl WorldSnapshot {
+    /// Number of ticks simulated when the snapshot was taken.
+    pub fn tick(&self) -> u32 {
+        self.tick
+    }
+}
+
+impl IntoIterator for SimRequest {
+    type Item = TickDiff;
+    type IntoIter = SimulationIterator;
+
+    fn into_iter(self) -> SimulationIterator {
+        // an invalid world, or an input outside it, never starts; `simulate`
+        // reports the error
+        let outside = self
+            .events
+            .iter()
+            .map(|(_, e)| e.pos())
+            .chain(self.vibrations.iter().map(|(_, p, _)| *p))
+            .chain(self.minecart_events.iter().map(|(_, p, _)| *p))
+            .chain(self.entity_events.iter().map(|(_, p, _)| *p))
+            .chain(self.lightning_strikes.iter().map(|(_, p)| *p))
+            .find(|p| !in_world(*p));
+        let replaced = self.events.iter().find_map(|(_, e)| match e {
+            TimedEvent::BlockReplace(pos, kind) => placement_error(*pos, kind),
+            _ => None,
+        });
+        let error = self
+            .world
+            .validate()
+            .err()
+            .or(outside.map(WorldError::OutOfBounds))
+            .or(replaced)
+            .map(SimError::from);
+        // into_map already looks at neighbours, so skip it for a bad world
+        let world = if error.is_some() { SpatialGrid::default() } else { self.world.into_map() };
+        // redstone blocks never update, so they are left out of the initial dirty set
+        let dirty = world
+            .iter()
+            .filter(|(_, b)| !matches!(b, BlockKind::RedstoneBlock))
+            .map(|(p, _)| *p)
+            .collect();
+        // only a random order needs entropy; other runs report 0 unless given a seed
+        let seed = match (self.seed, self.update_order) {
+            (Some(seed), _) => seed,
+            (None, UpdateOrder::Random) => entropy_seed(),
+            (None, _) => 0,
+        };
+        SimulationIterator {
+            world,
+            dirty,
+            triggered: SpatialSet::default(),
+            tick: 0,
+            max_ticks: self.ticks,
+            early_exit: self.early_exit,
+            parallel: self.parallel,
+            quasi_connectivity: self.quasi_connectivity,
+            update_order: self.update_order,
+            seed,
+            time_of_day: self.time_of_day,
+            time_advance_per_tick: self.time_advance_per_tick,
+            events: self.events,
+            vibrations: self.vibrations,
+            minecart_events: self.minecart_events,
+            entity_events: self.entity_events,
+            lightning_strikes: self.lightning_strikes,
+            notes: Vec::new(),
+            launch_events: Vec::new(),
+            lightning_events: Vec::new(),
+            craft_events: Vec::new(),
+            bell_events: Vec::new(),
+            terminated: None,
+            error,
+            lookahead: None,
+        }
+    }
+}
+
+impl SimulationIterator {
+    /// Current state of every block.
+    pub fn world(&self) -> &SpatialGrid<BlockKind> {
+        &self.world
+    }
+
+    /// Number of ticks simulated so far.
+    pub fn tick(&self) -> u32 {
+        self.tick
+    }
+
+    /// Seed of the update order, as in `SimResponse::seed_used`.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Why the run stopped early, if the world turned out to be invalid.
+    pub fn error(&self) -> Option<&SimError> {
+        self.error.as_ref()
+    }
+
+    /// Note blocks played so far, as in `SimResponse::notes`.
+    pub fn notes(&self) -> &[(u32, Pos, Instrument, u8)] {
+        &self.notes
+    }
+
+    /// Droppers and dispensers fired so far, as in `SimResponse::launch_events`.
+    pub fn launch_events(&self) -> &[LaunchEvent] {
+        &self.launch_events
+    }
+
+    /// Lightning rods struck so far, as in `SimResponse::lightning_events`.
+    pub fn lightning_events(&self) -> &[LightningEvent] {
+        &self.lightning_events
+    }
+
+    /// Crafters fired so far, as in `SimResponse::craft_events`.
+    pub fn craft_events(&self) -> &[CraftEvent] {
+        &self.craft_events
+    }
+
+    /// Bells rung so far, as in `SimResponse::bell_events`.
+    pub fn bell_events(&self) -> &[BellRingEvent] {
+        &self.bell_events
+    }
+
+    /// Capture the state after the current tick, so the run can later go
+    /// back to it.
+    pub fn snapshot(&self) -> WorldSnapshot {
+        WorldSnapshot {
+            tick: self.tick,
+            blocks: self.world.iter().map(|(p, b)| (*p, b.clone())).collect(),
+            dirty: self.dirty.iter().copied().collect(),
+            triggered: self.triggered.iter().copied().collect(),
+            notes: self.notes.clone(),
+            launch_events: self.launch_events.clone(),
+            lightning_events: self.lightning_events.clone(),
+            craft_events: self.craft_events.clone(),
+            bell_events: self.bell_events.clone(),
+            terminated: self.terminated.clone(),
+        }
+    }
+
+    /// Rewind (or fast-forward) to `snapshot`. The next `next()` simulates
+    /// the tick after the snapshot's, exactly as it did the first time.
+    pub fn restore(&mut self, snapshot: WorldSnapshot) {
+        self.lookahead = None;
+        self.tick = snapshot.tick;
+        self.world = snapshot.blocks.into_iter().collect();
+        self.dirty = snapshot.dirty.into_iter().collect();
+        self.triggered = snapshot.triggered.into_iter().collect();
+        self.notes = snapshot.notes;
+        self.launch_events = snapshot.launch_events;
+        self.lightning_events = snapshot.lightning_events;
+        self.craft_events = snapshot.craft_events;
+        self.bell_events = snapshot.bell_events;
+        self.terminated = snapshot.terminated;
+    }
+
+    /// What the next `next()` will return, changes and removals both,
+    /// worked out on a copy of the simulation. The first call costs a clone
+    /// and a tick; the answer is kept until the state changes.
+    pub fn peek_next_changes(&mut self) -> Option<TickDiff> {
+        if self.lookahead.is_none() {
+            self.lookahead = Some(self.clone().next());
+        }
+        self.lookahead.clone().flatten()
+    }
+
+    /// Place a block between ticks. It, its neighbours and the positions it
+    /// outputs to update on the next tick.
+    pub fn add_block(&mut self, pos: Pos, kind: BlockKind) -> Result<(), WorldError> {
+        if self.world.contains_key(&pos) {
+            return Err(WorldError::PositionOccupied(pos));
+        }
+        self.set_block(pos, kind)?;
+        Ok(())
+    }
+
+    /// Remove a block between ticks. Its neighbours and former output
+    /// positions update on the next tick.
+    pub fn remove_block(&mut self, pos: Pos) -> Option<BlockKind> {
+        let kind = self.world.remove(&pos)?;
+        self.lookahead = None;
+        mark_around(pos, &mut self.dirty);
+        mark_outputs(&kind, pos, &mut self.dirty);
+        self.dirty.remove(&pos);
+        if matches!(self.terminated, Some(Termination::Stable)) {
+            self.terminated = None;
+        }
+        Some(kind)
+    }
+
+    /// Replace the block at `pos` between ticks (e.g. flip a lever). It,
+    /// everything around it and what either block outputs to update on the
+    /// next tick; a simulation that had become stable resumes. Fails, and
+    /// changes nothing, for a block `World::validate` would reject.
+    pub fn set_block(&mut self, pos: Pos, kind: BlockKind) -> Result<Option<BlockKind>, WorldError> {
+        if let Some(e) = placement_error(pos, &kind) {
+            return Err(e);
+        }
+        self.lookahead = None;
+        if let Some(old) = self.world.get(&pos) {
+            mark_outputs(old, pos, &mut self.dirty);
+        }
+        mark_around(pos, &mut self.dirty);
+        mark_outputs(&kind, pos, &mut self.dirty);
+        if matches!(self.terminated, Some(Termination::Stable)) {
+            self.terminated = None;
+        }
+        Ok(self.world.insert(pos, kind))
+    }
+
+    /// Schedule `event` for the next tick, as if the request had listed it
+    /// (e.g. press a button from a debugger). A simulation that had become
+    /// stable resumes.
+    pub fn push_event(&mut self, event: TimedEvent) {
+        self.lookahead = None;
+        self.events.push((self.tick + 1, event));
+        if matches!(self.terminated, Some(Termination::Stable)) {
+            self.terminated = None;
+        }
+    }
+}
+
+impl SimulationIterator {
+    // one tick of the update loop; `Ok(None)` once the run is over
+    fn step(&mut self) -> Result<Option<TickDiff>, SimError> {
+        if self.terminated.is_some() || self.error.is_some() {
+            return Ok(None);
+        }
+        if self.tick >= self.max_ticks {
+            // still changing at the limit: feedback loops may be oscillating
+            #[cfg(feature = "tracing")]
+            if !self.dirty.is_empty() {
+                for cycle in analysis::detect_cycles(&self.world) {
+                    tracing::warn!(tick = self.tick, cycle = ?cycle, "feedback loop still active at the tick limit");
+                }
+            }
+            self.terminated = Some(Termination::MaxTicksReached);
+            return Ok(None);
+        }
+        self.tick += 1;
+        let tick = self.tick;
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!(
+            "tick",
+            tick,
+            dirty_count = self.dirty.len(),
+            changes_count = tracing::field::Empty
+        )
+        .entered();
+        let SimulationIterator {
+            world,
+            dirty,
+            triggered,
+            early_exit,
+            parallel,
+            quasi_connectivity,
+            update_order,
+            seed,
+            time_of_day,
+            time_advance_per_tick,
+            events,
+            vibrations,
+            minecart_events,
+            entity_events,
+            lightning_strikes,
+            notes,
+            launch_events,
+            lightning_events,
+            craft_events,
+            bell_events,
+            terminated,
+            ..
+        } = self;
+
+        let mut changes: Vec<BlockChange> = Vec::new();
+        let mut next_dirty: SpatialSet = SpatialSet::default();
+
+        // scheduled inputs take effect before this tick's updates; timers they
+        // start (targets, buttons) count down from the next tick
+        for (_, event) in events.iter().filter(|(t, _)| *t == tick) {
+            match event {
+                TimedEvent::TargetHit(hit) => {
+                    if let Some(block @ BlockKind::Target { .. }) = world.get_mut(&hit.pos) {
+                        *block = BlockKind::Target {
+                            power: hit.strength.min(15),
+                            ticks_remaining: TARGET_HIT_TICKS,
+                        };
+                        changes.push(BlockChange { pos: hit.pos, kind: block.clone() });
+                        mark_outputs(block, hit.pos, dirty);
+                        dirty.remove(&hit.pos);
+                        next_dirty.insert(hit.pos);
+                    }
+                }
+                TimedEvent::LeverToggle(pos) => {
+                    if let Some(block) = world.get_mut(pos) {
+                        if let BlockKind::Lever { on, .. } = block {
+                            *on = !*on;
+                            changes.push(BlockChange { pos: *pos, kind: block.clone() });
+                            mark_around(*pos, dirty);
+                            mark_outputs(block, *pos, dirty);
+                        }
+                    }
+                }
+                TimedEvent::ButtonPress(pos) => {
+                    if let Some(block) = world.get_mut(pos) {
+                        if let BlockKind::Button { ticks_remaining: ticks_remaining @ 0, .. } = block {
+                            *ticks_remaining = BUTTON_PRESS_TICKS;
+                            changes.push(BlockChange { pos: *pos, kind: block.clone() });
+                            mark_outputs(block, *pos, dirty);
+                            dirty.remove(pos);
+                            next_dirty.insert(*pos);
+                        }
+                    }
+                }
+                TimedEvent::BlockReplace(pos, kind) => {
+                    if let Some(old) = world.insert(*pos, kind.clone()) {
+                        mark_outputs(&old, *pos, dirty);
+                    }
+                    mark_around(*pos, dirty);
+                    mark_outputs(kind, *pos, dirty);
+                    changes.push(BlockChange { pos: *pos, kind: kind.clone() });
+                }
+                TimedEvent::FurnaceFuel(pos, ticks) => {
+                    if let Some(block @ BlockKind::Furnace { .. }) = world.get_mut(pos) {
+                        if let BlockKind::Furnace { fuel_ticks, cooking } = block {
+                            *fuel_ticks = fuel_ticks.saturating_add(*ticks);
+                            *cooking = *fuel_ticks > 0;
+                        }
+                        changes.push(BlockChange { pos: *pos, kind: block.clone() });
+                        // comparators read the new level now; burning starts next tick
+                        mark_around(*pos, dirty);
+                        dirty.remove(pos);
+                        next_dirty.insert(*pos);
+                    }
+                }
+            }
+        }
+
+        // vibrations: every idle sensor in range picks up the nearest one,
+        // calibrated sensors only when the frequency matches their filter
+        let heard_now: Vec<(Pos, u8)> = vibrations
+            .iter()
+            .filter(|(t, _, _)| *t == tick)
+            .map(|(_, p, f)| (*p, *f))
+            .collect();
+        if !heard_now.is_empty() {
+            let sensors: Vec<Pos> = world
+                .iter()
+                .filter(|(_, b)| {
+                    matches!(
+                        b,
+                        BlockKind::SculkSensor { ticks_remaining: 0, .. }
+                            | BlockKind::CalibratedSculkSensor { ticks_remaining: 0, .. }
+                    )
+                })
+                .map(|(p, _)| *p)
+                .collect();
+            for pos in sensors {
+                let mut filter = 0;
+                if let Some(block @ BlockKind::CalibratedSculkSensor { .. }) = world.get(&pos) {
+                    for n in block.input_positions(pos) {
+                        if let Some(nb) = world.get(&n) {
+                            filter = filter.max(output_towards(nb, dir_from_to(n, pos)?));
+                        }
+                    }
+                }
+                let heard = heard_now
+                    .iter()
+                    .filter(|(p, f)| p.within_radius(pos, SCULK_RANGE) && (filter == 0 || *f == filter))
+                    .min_by_key(|(p, _)| p.distance_squared(pos));
+                if let (Some((_, frequency)), Some(block)) = (heard, world.get_mut(&pos)) {
+                    if let BlockKind::SculkSensor { power, ticks_remaining, .. }
+                    | BlockKind::CalibratedSculkSensor { power, ticks_remaining, .. } = block
+                    {
+                        *power = (*frequency).min(15);
+                        *ticks_remaining = SCULK_ACTIVE_TICKS;
+                    }
+                    changes.push(BlockChange { pos, kind: block.clone() });
+                    mark_outputs(block, pos, dirty);
+                    dirty.remove(&pos);
+                    next_dirty.insert(pos);
+                }
+            }
+        }
+
+        for (_, pos, occupied) in minecart_events.iter().filter(|(t, _, _)| *t == tick) {
+            if let Some(block) = world.get_mut(pos) {
+                if let BlockKind::DetectorRail { powered, .. } = block {
+                    if *powered != *occupied {
+                        *powered = *occupied;
+                        changes.push(BlockChange { pos: *pos, kind: block.clone() });
+                        mark_outputs(block, *pos, dirty);
+                    }
+                }
+            }
+        }
+
+        // entities stay on a plate or wire until a later event changes its load
+        for (_, pos, pressure) in entity_events.iter().filter(|(t, _, _)| *t == tick) {
+            let mut wire_changed = false;
+            if let Some(block) = world.get_mut(pos) {
+                if let BlockKind::PressurePlate { powered, kind, power } = block {
+                    let new_power = kind.power_for(pressure);
+                    if *power != new_power {
+                        *power = new_power;
+                        *powered = new_power > 0;
+                        changes.push(BlockChange { pos: *pos, kind: block.clone() });
+                        mark_outputs(block, *pos, dirty);
+                    }
+                } else if let BlockKind::Tripwire { powered } = block {
+                    let occupied = pressure.mobs + pressure.items > 0;
+                    if *powered != occupied {
+                        *powered = occupied;
+                        changes.push(BlockChange { pos: *pos, kind: block.clone() });
+                        wire_changed = true;
+                    }
+                }
+            }
+            if wire_changed {
+                dirty.extend(tripwire_hooks(world, *pos));
+            }
+        }
+
+        // a struck rod restarts its timer even when already powered; unlike
+        // targets the strike tick counts as the first of its ticks
+        for (_, pos) in lightning_strikes.iter().filter(|(t, _)| *t == tick) {
+            if let Some(block @ BlockKind::LightningRod { .. }) = world.get_mut(pos) {
+                *block = BlockKind::LightningRod { powered: true, ticks_remaining: LIGHTNING_ROD_TICKS };
+                // the rod's own update this tick records the change
+                mark_outputs(block, *pos, dirty);
+                dirty.insert(*pos);
+                lightning_events.push(LightningEvent { tick, pos: *pos });
+            }
+        }
+
+        // daylight sensors follow the time of day
+        let time = time_at(*time_of_day, *time_advance_per_tick, tick);
+        for (pos, block) in world.iter_mut() {
+            if let BlockKind::DaylightSensor { inverted, power } = block {
+                let new_power = daylight_power(time, *inverted);
+                if *power != new_power {
+                    *power = new_power;
+                    changes.push(BlockChange { pos: *pos, kind: block.clone() });
+                    mark_outputs(block, *pos, dirty);
+                }
+            }
+        }
+
+        // door halves always update together
+        let partners: Vec<Pos> = dirty
+            .iter()
+            .filter_map(|p| match world.get(p) {
+                Some(BlockKind::Door { half, .. }) => Some(door_partner(*p, *half)),
+                _ => None,
+            })
+            .collect();
+        dirty.extend(partners);
+
+        // dust settles first, so everything it feeds sees its final power
+        let dust_changes = propagate_dust(world, dirty)?;
+        let dust_changed: SpatialSet = dust_changes.iter().map(|c| c.pos).collect();
+        // blocks started by this tick's events wait for the next one, even
+        // when dust next to them changed
+        dirty.retain(|p| !next_dirty.contains(p));
+
+        let snapshot = world.clone();
+        let order = update_sequence(world, dirty, *update_order, *seed, tick);
+        let fx = update_dirty(world, &order, &snapshot, triggered, tick, *parallel, *quasi_connectivity)?;
+        // dust whose shape also changed is reported once, in its final state
+        let updated: SpatialSet = fx.changes.iter().map(|c| c.pos).collect();
+        changes.extend(dust_changes.into_iter().filter(|c| !updated.contains(&c.pos)));
+        changes.extend(fx.changes);
+        next_dirty.extend(fx.next_dirty);
+        notes.extend(fx.notes);
+        launch_events.extend(fx.launch_events);
+        craft_events.extend(fx.craft_events);
+        bell_events.extend(fx.bell_events);
+        let (piston_actions, hopper_transfers) = (fx.piston_actions, fx.hopper_transfers);
+
+        // move one item per transfer; checked against the live world so two
+        // hoppers feeding one container cannot overfill it
+        for (src, dst) in hopper_transfers {
+            let has_room = world.get(&dst).and_then(container_contents).is_some_and(|(n, cap)| n < cap);
+            if !has_room {
+                continue;
+            }
+            let Some(from) = world.get_mut(&src).and_then(container_items_mut) else { continue };
+            if *from == 0 {
+                continue;
+            }
+            *from -= 1;
+            if let Some(to) = world.get_mut(&dst).and_then(container_items_mut) {
+                *to += 1;
+            }
+            for p in [src, dst] {
+                let kind = world[&p].clone();
+                match changes.iter_mut().find(|c| c.pos == p) {
+                    Some(c) => c.kind = kind,
+                    None => changes.push(BlockChange { pos: p, kind }),
+                }
+                // wakes the hoppers again and any comparator reading them
+                mark_around(p, &mut next_dirty);
+            }
+        }
+
+        // apply piston movements now that no block is borrowed
+        let mut removed: Vec<Pos> = Vec::new();
+        for action in piston_actions {
+            match action {
+                PistonAction::Extend { arm, facing, chain } => {
+                    for p in chain.iter().rev() {
+                        if let Some(b) = world.remove(p) {
+                            let dst = *p + facing;
+                            changes.push(BlockChange { pos: dst, kind: b.clone() });
+                            world.insert(dst, b);
+                            mark_around(*p, &mut next_dirty);
+                            mark_around(dst, &mut next_dirty);
+                        }
+                    }
+                    let head = BlockKind::PistonArm { facing };
+                    changes.push(BlockChange { pos: arm, kind: head.clone() });
+                    world.insert(arm, head);
+                    next_dirty.insert(arm);
+                }
+                PistonAction::Retract { arm, facing, sticky } => {
+                    if matches!(world.get(&arm), Some(BlockKind::PistonArm { .. })) {
+                        world.remove(&arm);
+                        removed.push(arm);
+                    }
+                    let front = arm + facing;
+                    if sticky && !world.contains_key(&arm) {
+                        if let Some(b) = world.get(&front).filter(|b| is_movable(b)).cloned() {
+                            world.remove(&front);
+                            removed.push(front);
+                            changes.push(BlockChange { pos: arm, kind: b.clone() });
+                            world.insert(arm, b);
+                        }
+                    }
+                    mark_around(arm, &mut next_dirty);
+                    mark_around(front, &mut next_dirty);
+                }
+                PistonAction::ClearArm(pos) => {
+                    if matches!(world.get(&pos), Some(BlockKind::PistonArm { .. })) {
+                        world.remove(&pos);
+                        removed.push(pos);
+                        mark_around(pos, &mut next_dirty);
+                    }
+                }
+            }
+        }
+        removed.retain(|p| !world.contains_key(p));
+
+        // second snapshot comparison: observers fire on the tick after the
+        // block they watch differs from its pre-tick state (dust settled
+        // before the snapshot was taken, so its changes are checked apart)
+        triggered.clear();
+        for (pos, block) in world.iter() {
+            if let BlockKind::Observer { facing, .. } = block {
+                let watched = *pos + *facing;
+                if world.get(&watched) != snapshot.get(&watched) || dust_changed.contains(&watched) {
+                    triggered.insert(*pos);
+                    next_dirty.insert(*pos);
+                }
+            }
+        }
+
+        if changes.is_empty() && removed.is_empty() && *early_exit {
+            let timers_active = world.values().any(|b| match b {
+                BlockKind::Button { ticks_remaining, .. } if *ticks_remaining > 0 => true,
+                BlockKind::Repeater { ticks_remaining, locked: false, .. } if *ticks_remaining > 0 => {
+                    true
+                }
+                BlockKind::Target { ticks_remaining, .. }
+                | BlockKind::SculkSensor { ticks_remaining, .. }
+                | BlockKind::CalibratedSculkSensor { ticks_remaining, .. }
+                    if *ticks_remaining > 0 =>
+                {
+                    true
+                }
+                BlockKind::Dropper { cooldown, .. }
+                | BlockKind::Dispenser { cooldown, .. }
+                | BlockKind::Crafter { cooldown, .. }
+                    if *cooldown > 0 =>
+                {
+                    true
+                }
+                BlockKind::Torch { burned_out: true, .. } => true,
+                BlockKind::LightningRod { ticks_remaining, .. } | BlockKind::Bell { ticks_remaining, .. } => {
+                    *ticks_remaining > 0
+                }
+                BlockKind::Furnace { cooking, .. } => *cooking,
+                BlockKind::DaylightSensor { .. } => *time_advance_per_tick != 0,
+                _ => false,
+            });
+            let events_pending = events.iter().any(|(t, _)| *t > tick)
+                || vibrations.iter().any(|(t, _, _)| *t > tick)
+                || minecart_events.iter().any(|(t, _, _)| *t > tick)
+                || entity_events.iter().any(|(t, _, _)| *t > tick)
+                || lightning_strikes.iter().any(|(t, _)| *t > tick);
+            if !timers_active && !events_pending {
+                *terminated = Some(Termination::Stable);
+                return Ok(None);
+            }
+        }
+
+        *dirty = next_dirty;
+        #[cfg(feature = "tracing")]
+        span.record("changes_count", changes.len());
+        Ok(Some(TickDiff { tick, changes, removed }))
+    }
+}
+
+impl Iterator for SimulationIterator {
+    type Item = TickDiff;
+
+    /// Stops early on an invalid world; the cause is kept in `error()`.
+    fn next(&mut self) -> Option<TickDiff> {
+        self.lookahead = None;
+        match self.step() {
+            Ok(diff) => diff,
+            Err(e) => {
+                self.error = Some(e);
+                None
+            }
+        }
+    }
+}
+
+/// Why [`Simulation::apply_change`] refused a change.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ChangeError {
+    MidTick,              // the last tick failed partway, leaving the world half updated
+    InvalidRequest,       // the request never started; see `Simulation::error`
+    Rejected(WorldError), // outside the world, or a state the game cannot produce
+}
+
+impl core::fmt::Display for ChangeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ChangeError::MidTick => write!(f, "a tick stopped partway; the world cannot be changed"),
+            ChangeError::InvalidRequest => write!(f, "the simulation never started"),
+            ChangeError::Rejected(e) => e.fmt(f),
+        }
+    }
+}
+
+impl core::error::Error for ChangeError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            ChangeError::Rejected(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// A run that takes block changes between ticks, for editors that let the
+/// user flip levers or place blocks while it plays. `step` runs one tick,
+/// as `SimulationIterator::next` does.
+#[derive(Clone)]
+pub struct Simulation {
+    iter: SimulationIterator,
+}
+
+impl Simulation {
+    pub fn new(request: SimRequest) -> Self {
+        Simulation { iter: request.into_iter() }
+    }
+
+    /// Run one tick; `None` once the run is over, as for `next()`.
+    pub fn step(&mut self) -> Option<TickDiff> {
+        self.iter.next()
+    }
+
+    /// What the next `step` will return, without running it. See
+    /// `SimulationIterator::peek_next_changes`.
+    pub fn peek_next_changes(&mut self) -> Option<TickDiff> {
+        self.iter.peek_next_changes()
+    }
+
+    /// Current state of every block.
+    pub fn world(&self) -> &SpatialGrid<BlockKind> {
+        self.iter.world()
+    }
+
+    /// Number of ticks simulated so far.
+    pub fn tick(&self) -> u32 {
+        self.iter.tick()
+    }
+
+    /// Why the run stopped early, if it did.
+    pub fn error(&self) -> Option<&SimError> {
+        self.iter.error()
+    }
+
+    /// Put `kind` at `pos` before the next tick. The block, its neighbours,
+    /// and what it outputs to (before and after the change) update on the
+    /// next `step`, and a run that had settled resumes. Fails after a tick
+    /// stopped partway on an error, since the world is then mid-tick.
+    pub fn apply_change(&mut self, pos: Pos, kind: BlockKind) -> Result<(), ChangeError> {
+        match self.iter.error {
+            Some(_) if self.iter.tick == 0 => return Err(ChangeError::InvalidRequest),
+            Some(_) => return Err(ChangeError::MidTick),
+            None => {}
+        }
+        self.iter.set_block(pos, kind).map_err(ChangeError::Rejected)?;
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> SimulationIterator {
+        self.iter
+    }
+}
+
+impl From<SimulationIterator> for Simulation {
+    fn from(iter: SimulationIterator) -> Self {
+        Simulation { iter }
+    }
+}
+
+// -------------------------------------------------
+// Unit tests
+// -------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lever_to_lamp_one_tick() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, true)
+            .dust((1, 0, 0))
+            .lamp((2, 0, 0))
+            .build()
+            .unwrap();
+        let req = SimRequest { ticks: 5, world, ..Default::default() };
+        let res = simulate(req).unwrap();
+        assert!(matches!(res.terminated, Termination::Stable));
+        // lamp should turn on at tick = 1
+        assert!(res.diffs.iter().any(|d| d.tick == 1
+            && d.changes
+                .iter()
+                .any(|c| matches!(c.kind, BlockKind::Lamp { on: true }))));
+    }
+
+    #[test]
+    fn dust_attenuation() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, true)
+            .dust((1, 0, 0))
+            .dust((2, 0, 0))
+            .build()
+            .unwrap();
+        let req = SimRequest { ticks: 3, world, ..Default::default() };
+        let res = simulate(req).unwrap();
+        assert!(res.diffs.iter().any(|d| d.changed_dust().any(|(_, power)| power == 14)));
+    }
+
+    #[test]
+    fn dust_chain_settles_in_one_tick() {
+        let mut builder = WorldBuilder::new().lever((0, 0, 0), Direction::East, true);
+        for x in 1..=15 {
+            builder = builder.dust((x, 0, 0));
+        }
+        let world = builder.lamp((16, 0, 0)).build().unwrap();
+        let req = SimRequest { ticks: 10, world, ..Default::default() };
+        let mut sim = req.into_iter();
+
+        let first = sim.next().unwrap();
+        let mut dust: Vec<(i32, u8)> = first.changed_dust().map(|(p, power)| (p.x, power)).collect();
+        dust.sort();
+        assert_eq!(dust, (1..=15).map(|x| (x, 16 - x as u8)).collect::<Vec<_>>());
+        assert!(first.changed_lamps().any(|(_, on)| on));
+
+        // switching the lever off clears the whole chain just as fast
+        sim.set_block(Pos { x: 0, y: 0, z: 0 }, BlockKind::Lever { on: false, facing: Direction::East }).unwrap();
+        let off = sim.next().unwrap();
+        assert_eq!(off.changed_dust().filter(|(_, power)| *power == 0).count(), 15);
+        assert!(off.changed_lamps().any(|(_, on)| !on));
+    }
+
+    #[test]
+    fn apply_change_flips_a_lever_between_ticks() {
+        let lever = Pos::default();
+        let mut sim = Simulation::new(SimRequest { ticks: 20, world: lever_lamp_world(false), ..Default::default() });
+        while sim.step().is_some() {}
+        assert!(matches!(sim.world()[&Pos::from((1, 0, 0))], BlockKind::Lamp { on: false }));
+
+        // the settled run picks up again and the lamp lights on the next tick
+        sim.apply_change(lever, BlockKind::Lever { on: true, facing: Direction::East }).unwrap();
+        let diff = sim.step().unwrap();
+        assert_eq!(diff.changed_lamps().collect::<Vec<_>>(), vec![(Pos::from((1, 0, 0)), true)]);
+
+        let far = Pos { x: i32::MAX, y: 0, z: 0 };
+        assert_eq!(
+            sim.apply_change(far, BlockKind::RedstoneBlock),
+            Err(ChangeError::Rejected(WorldError::OutOfBounds(far)))
+        );
+        let mut invalid = Simulation::new(SimRequest { world: [(far, BlockKind::RedstoneBlock)].into_iter().collect(), ..Default::default() });
+        assert_eq!(invalid.apply_change(lever, BlockKind::RedstoneBlock), Err(ChangeError::InvalidRequest));
+    }
+
+    #[test]
+    fn apply_change_refuses_after_a_tick_fails_partway() {
+        let lamp = Pos::from((1, 0, 0));
+        let mut sim = Simulation::new(SimRequest { ticks: 20, world: lever_lamp_world(true), ..Default::default() });
+        assert!(sim.step().is_some());
+        // no valid world fails mid-run, so stand in for a tick that stopped
+        // on a bad neighbour lookup after updating part of the world
+        sim.iter.error = Some(SimError::NonAdjacentBlock(NonAdjacentError { from: lamp, to: Pos::from((3, 0, 0)) }));
+        assert!(sim.step().is_none());
+        assert_eq!(sim.apply_change(Pos::default(), BlockKind::RedstoneBlock), Err(ChangeError::MidTick));
+        assert!(matches!(sim.world()[&Pos::default()], BlockKind::Lever { on: true, .. }));
+    }
+
+    #[test]
+    fn restore_replays_same_diffs() {
+        // two torches feeding each other blink forever, driving a repeater
+        let world = WorldBuilder::new()
+            .torch((1, 0, 0), Direction::West)
+            .torch((0, 0, 1), Direction::East)
+            .dust((0, 0, 0))
+            .dust((1, 0, 1))
+            .repeater((2, 0, 1), Direction::East, 1)
+            .lamp((3, 0, 1))
+            .build()
+            .unwrap();
+        let req = SimRequest { ticks: 20, world, ..Default::default() };
+        // changes within a tick come in no particular order
+        let sorted = |mut d: TickDiff| {
+            d.changes.sort_by_key(|c| (c.pos.x, c.pos.y, c.pos.z));
+            (d.tick, d.changes.into_iter().map(|c| (c.pos, c.kind)).collect::<Vec<_>>())
+        };
+        let mut sim = req.into_iter();
+        sim.by_ref().take(3).for_each(drop);
+        let saved = sim.snapshot();
+        assert_eq!(saved.tick(), 3);
+        let first: Vec<_> = sim.by_ref().take(3).map(sorted).collect();
+        assert_eq!(sim.tick(), 6);
+
+        sim.restore(saved);
+        assert_eq!(sim.tick(), 3);
+        let again: Vec<_> = sim.by_ref().take(3).map(sorted).collect();
+        assert_eq!(first, again);
+        assert!(first.iter().any(|(_, changes)| !changes.is_empty()));
+    }
+
+    #[test]
+    fn fast_torch_loop_burns_out() {
+        let world = WorldBuilder::new()
+            .torch((1, 0, 0), Direction::West)
+            .torch((0, 0, 1), Direction::East)
+            .dust((0, 0, 0))
+            .dust((1, 0, 1))
+            .build()
+            .unwrap();
+        let req = SimRequest { ticks: 45, world, ..Default::default() };
+        let res = simulate(req).unwrap();
+        let torch = |tick: u32| {
+            let diff = res.diffs.iter().find(|d| d.tick == tick).unwrap();
+            match diff.find_change_at(Pos::from((1, 0, 0))).unwrap().kind {
+                BlockKind::Torch { lit, burned_out, .. } => (lit, burned_out),
+                _ => unreachable!(),
+            }
+        };
+        assert_eq!(torch(9), (false, false));
+        // the 8th toggle would have lit it
+        assert_eq!(torch(10), (false, true));
+        assert_eq!(torch(39), (false, true));
+        assert_eq!(torch(40), (true, false));
+    }
+
+    #[test]
+    fn occasional_torch_toggles_do_not_burn_out() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, false)
+            .solid((1, 0, 0))
+            .torch((2, 0, 0), Direction::West)
+            .build()
+            .unwrap();
+        let lever = Pos::from((0, 0, 0));
+        let events = (1..=12).map(|i| (i * 6, TimedEvent::LeverToggle(lever))).collect();
+        let req = SimRequest { world, events, ..Default::default() };
+        let res = simulate(req).unwrap();
+        assert!(matches!(res.terminated, Termination::Stable));
+        let torches: Vec<bool> = res.diffs.iter().flat_map(|d| d.changed_torches()).map(|(_, lit)| lit).collect();
+        assert!(torches.len() > 12);
+        assert!(res.diffs.iter().flat_map(|d| &d.changes).all(|c| !matches!(c.kind, BlockKind::Torch { burned_out: true, .. })));
+    }
+
+    #[test]
+    fn quasi_connected_piston_waits_for_an_update() {
+        // the lever powers the empty space above the piston, not the piston
+        let world = WorldBuilder::new()
+            .piston((0, 0, 0), Direction::East, false)
+            .lever((1, 1, 0), Direction::West, false)
+            .build()
+            .unwrap();
+        let events = vec![
+            (3, TimedEvent::LeverToggle(Pos::from((1, 1, 0)))),
+            // placing a block beside the piston updates it
+            (6, TimedEvent::BlockReplace(Pos::from((0, 0, 1)), BlockKind::OpaqueSolid { strong_power: 0 })),
+        ];
+        let run = |quasi_connectivity| {
+            let req = SimRequest {
+                ticks: 20,
+                world: world.clone(),
+                events: events.clone(),
+                quasi_connectivity,
+                ..Default::default()
+            };
+            let res = simulate(req).unwrap();
+            res.diffs
+                .iter()
+                .find(|d| d.changes.iter().any(|c| matches!(c.kind, BlockKind::Piston { extended: true, .. })))
+                .map(|d| d.tick)
+        };
+        assert_eq!(run(true), Some(6));
+        assert_eq!(run(false), None);
+    }
+
+    #[test]
+    fn dust_only_powers_connected_sides() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, true)
+            .dust((1, 0, 0))
+            .dust((2, 0, 0))
+            // beside the line: not connected
+            .lamp((2, 0, 1))
+            // at the end of the line: the dust points into it
+            .lamp((3, 0, 0))
+            .build()
+            .unwrap();
+        let req = SimRequest { ticks: 10, world, ..Default::default() };
+        let res = simulate(req).unwrap();
+        let lit: Vec<Pos> =
+            res.diffs.iter().flat_map(|d| d.changed_lamps()).filter(|(_, on)| *on).map(|(p, _)| p).collect();
+        assert_eq!(lit, vec![Pos { x: 3, y: 0, z: 0 }]);
+    }
+
+    #[test]
+    fn dust_climbs_slope() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, true)
+            .dust((1, 0, 0))
+            .solid((2, 0, 0))
+            .dust((2, 1, 0))
+            .build()
+            .unwrap();
+        let req = SimRequest { ticks: 10, world, ..Default::default() };
+        let res = simulate(req).unwrap();
+        assert!(res.diffs.iter().any(|d| d.changes.iter().any(|c| c.pos == Pos { x: 2, y: 1, z: 0 }
+            && matches!(c.kind, BlockKind::Dust { power: 14, connections: [false, true, false, true] }))));
+    }
+
+    #[test]
+    fn torch_turns_off_when_powered() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, true)
+            .torch((1, 0, 0), Direction::West)
+            .build()
+            .unwrap();
+        let req = SimRequest { ticks: 2, world, ..Default::default() };
+        let res = simulate(req).unwrap();
+        assert!(res.diffs.iter().any(|d| d.changed_torches().any(|(_, lit)| !lit)));
+    }
+
+    #[test]
+    fn repeater_requires_back_input() {
+        let world = WorldBuilder::new()
+            .lever((1, 0, 1), Direction::North, true)
+            .repeater((1, 0, 0), Direction::East, 1)
+            .dust((2, 0, 0))
+            .lamp((3, 0, 0))
+            .build()
+            .unwrap();
+        let req = SimRequest { ticks: 3, world, ..Default::default() };
+        let res = simulate(req).unwrap();
+        assert!(!res.diffs.iter().any(|d| d.changed_lamps().any(|(_, on)| on)));
+    }
+
+    fn piston_retraction_world(sticky: bool) -> World {
+        WorldBuilder::new()
+            .block((0, 0, 0), BlockKind::Piston { extended: true, facing: Direction::East, sticky })
+            .dust((2, 0, 0))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn sticky_piston_pulls_block_back() {
+        let req = SimRequest { ticks: 5, world: piston_retraction_world(true), ..Default::default() };
+        let res = simulate(req).unwrap();
+        let first = &res.diffs[0];
+        assert_eq!(first.tick, 1);
+        assert!(first.changes.iter().any(|c| c.pos == Pos { x: 1, y: 0, z: 0 }
+            && matches!(c.kind, BlockKind::Dust { .. })));
+        assert_eq!(first.removed, vec![Pos { x: 2, y: 0, z: 0 }]);
+    }
+
+    #[test]
+    fn non_sticky_piston_leaves_gap() {
+        let req = SimRequest { ticks: 5, world: piston_retraction_world(false), ..Default::default() };
+        let res = simulate(req).unwrap();
+        assert!(res.diffs.iter().all(|d| d.removed.is_empty()));
+        assert!(!res.diffs.iter().any(|d| d.changes.iter().any(|c| matches!(c.kind, BlockKind::Dust { .. }))));
+    }
+
+    #[test]
+    fn piston_push_limit() {
+        let column = |height| {
+            (1..=height)
+                .fold(WorldBuilder::new(), |b, y| b.solid((0, y, 0)))
+                .lever((-1, 0, 0), Direction::East, true)
+                .piston((0, 0, 0), Direction::Up, false)
+                .build()
+                .unwrap()
+        };
+        let req = SimRequest { ticks: 5, world: column(13), ..Default::default() };
+        let res = simulate(req).unwrap();
+        assert!(!res.diffs.iter().any(|d| d.changes.iter().any(|c| matches!(c.kind, BlockKind::Piston { extended: true, .. }))));
+
+        // with twelve blocks the whole column moves up and the arm appears
+        let req = SimRequest { ticks: 5, world: column(12), ..Default::default() };
+        let res = simulate(req).unwrap();
+        let first = &res.diffs[0];
+        assert!(first.changed_positions().any(|p| p == Pos { x: 0, y: 13, z: 0 }));
+        assert!(first.changes.iter().any(|c| c.pos == Pos { x: 0, y: 1, z: 0 }
+            && matches!(c.kind, BlockKind::PistonArm { facing: Direction::Up })));
+        assert_eq!(res.diffs[1].removed, vec![Pos { x: 0, y: 1, z: 0 }]);
+    }
+
+    #[test]
+    fn target_hit_decays_after_four_ticks() {
+        let world = WorldBuilder::new()
+            .target((0, 0, 0))
+            .dust((1, 0, 0))
+            .dust((2, 0, 0))
+            .build()
+            .unwrap();
+        let hit = TargetHitEvent { pos: Pos { x: 0, y: 0, z: 0 }, strength: 10 };
+        let req = SimRequest { ticks: 30, world, events: vec![(2, TimedEvent::TargetHit(hit))], ..Default::default() };
+        let res = simulate(req).unwrap();
+        assert!(matches!(res.terminated, Termination::Stable));
+        let target_power: Vec<(u32, u8)> = res
+            .diffs
+            .iter()
+            .flat_map(|d| {
+                d.changes.iter().filter_map(move |c| match c.kind {
+                    BlockKind::Target { power, .. } => Some((d.tick, power)),
+                    _ => None,
+                })
+            })
+            .collect();
+        assert_eq!(target_power, vec![(2, 10), (3, 10), (4, 10), (5, 10), (6, 0)]);
+        let changes: Vec<&BlockChange> = res.diffs.iter().flat_map(|d| d.changes.iter()).collect();
+        assert!(changes.iter().any(|c| c.pos.x == 1 && matches!(c.kind, BlockKind::Dust { power: 10, .. })));
+        assert!(changes.iter().any(|c| c.pos.x == 2 && matches!(c.kind, BlockKind::Dust { power: 9, .. })));
+    }
+
+    #[test]
+    fn timed_events_apply_at_their_tick() {
+        let world = WorldBuilder::new()
+            .button((0, 0, 0), Direction::East)
+            .dust((1, 0, 0))
+            .lamp((2, 0, 0))
+            .lever((0, 0, 5), Direction::East, false)
+            .lamp((1, 0, 5))
+            .build()
+            .unwrap();
+        let events = vec![
+            (5, TimedEvent::ButtonPress(Pos::from((0, 0, 0)))),
+            (10, TimedEvent::LeverToggle(Pos::from((0, 0, 5)))),
+            (12, TimedEvent::BlockReplace(Pos::from((1, 0, 0)), BlockKind::OpaqueSolid { strong_power: 0 })),
+        ];
+        let req = SimRequest { ticks: 40, world, events, ..Default::default() };
+        let res = simulate(req).unwrap();
+        assert!(matches!(res.terminated, Termination::Stable));
+        let diff = |tick: u32| res.diffs.iter().find(|d| d.tick == tick).unwrap();
+        assert!(res.diffs.iter().all(|d| d.tick >= 5));
+        assert!(diff(5).changes.iter().any(|c| c.pos.x == 0 && c.kind == BlockKind::Button { ticks_remaining: 10, facing: Direction::East }));
+        assert!(diff(5).changed_lamps().any(|(p, on)| p.x == 2 && on));
+        assert!(diff(10).changes.iter().any(|c| c.kind == BlockKind::Lever { on: true, facing: Direction::East }));
+        assert!(diff(10).changed_lamps().any(|(p, on)| p.z == 5 && on));
+        assert!(diff(12).changes.iter().any(|c| c.pos.x == 1 && matches!(c.kind, BlockKind::OpaqueSolid { .. })));
+        // the button is cut off from its lamp before it releases
+        assert!(res.diffs.iter().flat_map(|d| d.changed_lamps()).any(|(p, on)| p.x == 2 && !on));
+    }
+
+    #[test]
+    fn hit_target_sets_power_and_timer() {
+        let mut world = WorldBuilder::new().target((0, 0, 0)).build().unwrap();
+        world.hit_target(Pos { x: 0, y: 0, z: 0 }, 20);
+        assert_eq!(world.blocks[0].kind, BlockKind::Target { power: 15, ticks_remaining: 4 });
+    }
+
+    #[test]
+    fn sculk_sensor_detects_vibration_in_range() {
+        let world = WorldBuilder::new().sculk_sensor((0, 0, 0)).dust((1, 0, 0)).build().unwrap();
+        let vibrations = vec![
+            (2, Pos { x: 20, y: 0, z: 0 }, 3), // out of range
+            (2, Pos { x: 0, y: 0, z: 5 }, 7),
+        ];
+        let req = SimRequest { ticks: 40, world, vibrations, ..Default::default() };
+        let res = simulate(req).unwrap();
+        let sensor: Vec<(u32, u8)> = res
+            .diffs
+            .iter()
+            .flat_map(|d| {
+                d.changes.iter().filter_map(move |c| match c.kind {
+                    BlockKind::SculkSensor { power, ticks_remaining, .. }
+                        if ticks_remaining == 0 || ticks_remaining == SCULK_ACTIVE_TICKS =>
+                    {
+                        Some((d.tick, power))
+                    }
+                    _ => None,
+                })
+            })
+            .collect();
+        assert_eq!(sensor, vec![(2, 7), (22, 0)]);
+        assert!(res.diffs.iter().any(|d| d.changes.iter().any(|c| matches!(c.kind, BlockKind::Dust { power: 7, .. }))));
     }
 
     #[test]
-    fn repeater_requires_back_input() {
-        let world = World {
-            blocks: vec![
-                PlacedBlock {
-                    pos: Pos { x: 1, y: 0, z: 1 },
-                    kind: BlockKind::Lever { on: true, facing: Direction::North },
-                },
-                PlacedBlock {
-                    pos: Pos { x: 1, y: 0, z: 0 },
-                    kind: BlockKind::Repeater {
-                        delay: 1,
-                        ticks_remaining: 0,
-                        powered: false,
-                        facing: Direction::East,
-                    },
-                },
-                PlacedBlock {
-                    pos: Pos { x: 2, y: 0, z: 0 },
-                    kind: BlockKind::Dust { power: 0 },
-                },
-                PlacedBlock {
-                    pos: Pos { x: 3, y: 0, z: 0 },
-                    kind: BlockKind::Lamp { on: false },
+    fn calibrated_sculk_sensor_filters_frequency() {
+        let world = WorldBuilder::new()
+            .redstone_block((-2, 0, 0))
+            // feeds 14 into the back of the sensor
+            .dust((-1, 0, 0))
+            .calibrated_sculk_sensor((0, 0, 0), Direction::East)
+            .build()
+            .unwrap();
+        let vibrations = vec![(3, Pos { x: 0, y: 0, z: 2 }, 5), (5, Pos { x: 0, y: 0, z: 2 }, 14)];
+        let req = SimRequest { ticks: 10, world, vibrations, ..Default::default() };
+        let res = simulate(req).unwrap();
+        let first = res
+            .diffs
+            .iter()
+            .find(|d| d.changes.iter().any(|c| matches!(c.kind, BlockKind::CalibratedSculkSensor { .. })))
+            .unwrap();
+        assert_eq!(first.tick, 5);
+        assert!(first.changes.iter().any(|c| matches!(c.kind, BlockKind::CalibratedSculkSensor { power: 14, .. })));
+    }
+
+    #[test]
+    fn door_halves_open_together() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, true)
+            .dust((1, 0, 0))
+            .door((2, 0, 0), Direction::East, HingePosition::Left)
+            .build()
+            .unwrap();
+        let req = SimRequest { ticks: 10, world, ..Default::default() };
+        let res = simulate(req).unwrap();
+        let opened = res
+            .diffs
+            .iter()
+            .find(|d| d.changes.iter().any(|c| matches!(c.kind, BlockKind::Door { .. })))
+            .unwrap();
+        let mut halves: Vec<Pos> = opened
+            .changes
+            .iter()
+            .filter(|c| matches!(c.kind, BlockKind::Door { open: true, .. }))
+            .map(|c| c.pos)
+            .collect();
+        halves.sort_by_key(|p| p.y);
+        assert_eq!(halves, vec![Pos { x: 2, y: 0, z: 0 }, Pos { x: 2, y: 1, z: 0 }]);
+    }
+
+    #[test]
+    fn trapdoor_opens_when_powered() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::Up, true)
+            .trapdoor((0, 1, 0), Direction::North, TrapdoorHalf::Bottom)
+            .build()
+            .unwrap();
+        let req = SimRequest { ticks: 5, world, ..Default::default() };
+        let res = simulate(req).unwrap();
+        assert!(res.diffs.iter().any(|d| d.changes.iter().any(|c| matches!(c.kind, BlockKind::Trapdoor { open: true, .. }))));
+    }
+
+    #[test]
+    fn fence_gates_open_together_and_stay_open_while_any_input_is_on() {
+        // a dust hub with three arms ending next to gates at x = 3
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, true)
+            .dust((1, 0, 0))
+            .dust((2, 0, 0))
+            .dust((1, 0, -1))
+            .dust((1, 0, -2))
+            .dust((2, 0, -2))
+            .dust((1, 0, 1))
+            .dust((1, 0, 2))
+            .dust((2, 0, 2))
+            .fence_gate((3, 0, -2), Direction::East)
+            .fence_gate((3, 0, 0), Direction::East)
+            .fence_gate((3, 0, 2), Direction::East)
+            // a separate gate between two levers
+            .lever((10, 0, 0), Direction::East, true)
+            .fence_gate((11, 0, 0), Direction::North)
+            .lever((12, 0, 0), Direction::West, true)
+            .build()
+            .unwrap();
+        let events = vec![
+            (3, TimedEvent::LeverToggle(Pos { x: 10, y: 0, z: 0 })),
+            (6, TimedEvent::LeverToggle(Pos { x: 12, y: 0, z: 0 })),
+        ];
+        let req = SimRequest { ticks: 10, world: world.clone(), events, ..Default::default() };
+        let res = simulate(req).unwrap();
+        let opened: Vec<(u32, Pos)> = res
+            .diffs
+            .iter()
+            .flat_map(|d| d.changes.iter().map(move |c| (d.tick, c)))
+            .filter(|(_, c)| matches!(c.kind, BlockKind::FenceGate { open: true, .. }) && c.pos.x == 3)
+            .map(|(t, c)| (t, c.pos))
+            .collect();
+        assert_eq!(opened.len(), 3);
+        assert!(opened.iter().all(|(t, _)| *t == opened[0].0));
+        let open_at = |tick| matches!(
+            res.state_at_tick(tick, &world)[&Pos { x: 11, y: 0, z: 0 }],
+            BlockKind::FenceGate { open: true, .. }
+        );
+        assert_eq!([2, 5, 8].map(open_at), [true, true, false]);
+    }
+
+    #[test]
+    fn note_block_plays_once_per_rising_edge() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, true)
+            .repeater((1, 0, 0), Direction::East, 2)
+            .note_block((2, 0, 0), Instrument::Bell, 12)
+            .build()
+            .unwrap();
+        let req = SimRequest { ticks: 20, world, early_exit: false, ..Default::default() };
+        let res = simulate(req).unwrap();
+        assert_eq!(res.notes, vec![(3, Pos { x: 2, y: 0, z: 0 }, Instrument::Bell, 12)]);
+        let triggered: Vec<(u32, bool)> = res
+            .diffs
+            .iter()
+            .flat_map(|d| {
+                d.changes.iter().filter_map(move |c| match c.kind {
+                    BlockKind::NoteBlock { triggered, .. } => Some((d.tick, triggered)),
+                    _ => None,
+                })
+            })
+            .collect();
+        assert_eq!(triggered, vec![(3, true), (4, false)]);
+    }
+
+    #[test]
+    fn powered_rail_line_reaches_eight_rails() {
+        let world = (1..=11)
+            .fold(WorldBuilder::new(), |b, x| b.powered_rail((x, 0, 0), RailShape::EastWest))
+            .lever((0, 0, 0), Direction::East, true)
+            .build()
+            .unwrap();
+        let req = SimRequest { ticks: 30, world, ..Default::default() };
+        let res = simulate(req).unwrap();
+        assert!(matches!(res.terminated, Termination::Stable));
+        let mut powered: Vec<i32> = res
+            .diffs
+            .iter()
+            .flat_map(|d| d.changes.iter())
+            .filter(|c| matches!(c.kind, BlockKind::PoweredRail { powered: true, .. }))
+            .map(|c| c.pos.x)
+            .collect();
+        powered.sort();
+        assert_eq!(powered, (1..=9).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn detector_rail_powers_while_occupied() {
+        let world = WorldBuilder::new()
+            .detector_rail((0, 0, 0), RailShape::NorthSouth)
+            .lamp((1, 0, 0))
+            .build()
+            .unwrap();
+        let minecart_events =
+            vec![(2, Pos { x: 0, y: 0, z: 0 }, true), (5, Pos { x: 0, y: 0, z: 0 }, false)];
+        let req = SimRequest { ticks: 20, world, minecart_events, ..Default::default() };
+        let res = simulate(req).unwrap();
+        let lamp: Vec<(u32, bool)> =
+            res.diffs.iter().flat_map(|d| d.changed_lamps().map(|(_, on)| (d.tick, on))).collect();
+        assert_eq!(lamp, vec![(2, true), (5, false)]);
+    }
+
+    #[test]
+    fn stone_pressure_plate_powers_lamp_while_occupied() {
+        let world = WorldBuilder::new()
+            .pressure_plate((0, 0, 0), PlateKind::Stone)
+            .lamp((1, 0, 0))
+            .build()
+            .unwrap();
+        let plate = Pos { x: 0, y: 0, z: 0 };
+        let entity_events = vec![
+            (2, plate, EntityPressure { mobs: 0, items: 3 }), // items do not trigger stone
+            (3, plate, EntityPressure { mobs: 1, items: 0 }),
+            (6, plate, EntityPressure::default()),
+        ];
+        let req = SimRequest { ticks: 20, world, entity_events, ..Default::default() };
+        let res = simulate(req).unwrap();
+        let lamp: Vec<(u32, bool)> =
+            res.diffs.iter().flat_map(|d| d.changed_lamps().map(|(_, on)| (d.tick, on))).collect();
+        assert_eq!(lamp, vec![(3, true), (6, false)]);
+    }
+
+    #[test]
+    fn tripwire_hook_fires_when_tripped_or_cut() {
+        let world = (1..4)
+            .fold(WorldBuilder::new().tripwire_hook((0, 0, 0), Direction::East), |b, x| b.tripwire((x, 0, 0)))
+            .tripwire_hook((4, 0, 0), Direction::West)
+            .lamp((-1, 0, 0))
+            .build()
+            .unwrap();
+        let wire = Pos { x: 2, y: 0, z: 0 };
+        let entity_events =
+            vec![(5, wire, EntityPressure { mobs: 1, items: 0 }), (10, wire, EntityPressure::default())];
+        let events = vec![(15, TimedEvent::BlockReplace(wire, BlockKind::OpaqueSolid { strong_power: 0 }))];
+        let req = SimRequest { ticks: 30, world, events, entity_events, ..Default::default() };
+        let res = simulate(req).unwrap();
+        let hook = Pos { x: 0, y: 0, z: 0 };
+        let attached = res.diffs.iter().find_map(|d| match d.find_change_at(hook)?.kind {
+            BlockKind::TripwireHook { attached: true, .. } => Some(d.tick),
+            _ => None,
+        });
+        assert_eq!(attached, Some(1));
+        let lamp: Vec<(u32, bool)> =
+            res.diffs.iter().flat_map(|d| d.changed_lamps().map(|(_, on)| (d.tick, on))).collect();
+        // on while the wire is occupied, then one pulse when it is cut
+        assert_eq!(lamp, vec![(6, true), (11, false), (17, true), (18, false)]);
+    }
+
+    #[test]
+    fn daylight_sensor_follows_the_sun() {
+        assert_eq!(daylight_power(18_000, false), 0); // midnight
+        assert_eq!(daylight_power(6_000, false), 15); // noon
+        assert_eq!(daylight_power(0, false), 0);
+        assert_eq!(daylight_power(2_000, false), 7); // sin(π/6) = 0.5
+        assert_eq!(daylight_power(18_000, true), 15);
+        assert_eq!(daylight_power(6_000, true), 0);
+        assert_eq!(daylight_power(2_000, true), 8);
+
+        // time pinned at noon: the sensor lights the lamp and the run settles
+        let world = WorldBuilder::new().daylight_sensor((0, 0, 0), false).lamp((1, 0, 0)).build().unwrap();
+        let req = SimRequest {
+            ticks: 50,
+            world: world.clone(),
+            time_of_day: 6_000,
+            time_advance_per_tick: 0,
+            ..Default::default()
+        };
+        let res = simulate(req).unwrap();
+        assert!(matches!(res.terminated, Termination::Stable));
+        assert_eq!(res.final_state(&world)[&Pos { x: 1, y: 0, z: 0 }], BlockKind::Lamp { on: true });
+
+        // time running from late afternoon: the lamp goes out once the power
+        // drops below 1, about 255 ticks before sunset at 12000
+        let req = SimRequest { ticks: 300, world, time_of_day: 11_500, ..Default::default() };
+        let res = simulate(req).unwrap();
+        assert!(matches!(res.terminated, Termination::MaxTicksReached));
+        let lamp: Vec<(u32, bool)> =
+            res.diffs.iter().flat_map(|d| d.changed_lamps().map(|(_, on)| (d.tick, on))).collect();
+        assert_eq!(lamp, vec![(1, true), (249, false)]);
+    }
+
+    #[test]
+    fn lightning_rod_powers_dust_for_eight_ticks() {
+        let world = WorldBuilder::new().lightning_rod((0, 0, 0)).dust((1, 0, 0)).build().unwrap();
+        let rod = Pos { x: 0, y: 0, z: 0 };
+        let req = SimRequest {
+            ticks: 20,
+            world: world.clone(),
+            lightning_strikes: vec![(3, rod), (5, Pos { x: 9, y: 0, z: 0 })],
+            ..Default::default()
+        };
+        let res = simulate(req).unwrap();
+        let dust_at = |tick| match res.state_at_tick(tick, &world)[&Pos { x: 1, y: 0, z: 0 }] {
+            BlockKind::Dust { power, .. } => power,
+            _ => unreachable!(),
+        };
+        assert_eq!(dust_at(2), 0);
+        assert!((3..=10).all(|t| dust_at(t) == 15));
+        assert_eq!(dust_at(11), 0);
+        let rod_at = |tick| res.diffs.iter().find(|d| d.tick == tick)?.find_change_at(rod).map(|c| c.kind.clone());
+        assert_eq!(rod_at(3), Some(BlockKind::LightningRod { powered: true, ticks_remaining: 7 }));
+        assert_eq!(rod_at(10), Some(BlockKind::LightningRod { powered: false, ticks_remaining: 0 }));
+        // the strike on empty ground hit no rod
+        assert_eq!(res.lightning_events, vec![LightningEvent { tick: 3, pos: rod }]);
+    }
+
+    #[test]
+    fn copper_bulb_toggles_on_each_rising_edge() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, false)
+            .copper_bulb((1, 0, 0), OxidationLevel::Weathered)
+            .build()
+            .unwrap();
+        let lever = Pos { x: 0, y: 0, z: 0 };
+        let events = [2, 5, 8].map(|t| (t, TimedEvent::LeverToggle(lever))).to_vec();
+        let req = SimRequest { ticks: 20, world: world.clone(), events, ..Default::default() };
+        let res = simulate(req).unwrap();
+        let lit_at = |tick| match res.state_at_tick(tick, &world)[&Pos { x: 1, y: 0, z: 0 }] {
+            BlockKind::CopperBulb { lit, .. } => lit,
+            _ => unreachable!(),
+        };
+        // lever on at 2 lights it, off at 5 leaves it lit, on again at 8 puts it out
+        assert_eq!([1, 2, 4, 5, 7, 8, 10].map(lit_at), [false, true, true, true, true, false, false]);
+        assert_eq!(OxidationLevel::Weathered.light_level(), 8);
+    }
+
+    #[test]
+    fn response_queries_replay_the_diffs() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, false)
+            .repeater((1, 0, 0), Direction::East, 4)
+            .lamp((2, 0, 0))
+            .dust((0, 0, 5))
+            .build()
+            .unwrap();
+        let lever = Pos { x: 0, y: 0, z: 0 };
+        let events = [(1, TimedEvent::LeverToggle(lever)), (10, TimedEvent::LeverToggle(lever))].to_vec();
+        let req = SimRequest { ticks: 30, world: world.clone(), events, ..Default::default() };
+        let res = simulate(req).unwrap();
+        let lamp = Pos { x: 2, y: 0, z: 0 };
+        let lit: Vec<u32> = (0..=20).filter(|&t| res.power_at(&world, lamp, t) == 15).collect();
+        assert_eq!(lit, (5..=10).collect::<Vec<_>>());
+        assert_eq!(res.first_powered_tick(&world, lamp), Some(5));
+        assert_eq!(res.last_changed_tick(lamp), Some(11));
+        assert_eq!(res.power_at(&world, lamp, 0), 0);
+        assert_eq!(res.power_at(&world, lever, 1), 15);
+        assert_eq!(res.power_at(&world, lever, 10), 0);
+        assert_eq!(res.power_at(&world, Pos { x: 9, y: 9, z: 9 }, 5), 0);
+        assert!(res.was_ever_powered(&world, lamp));
+        // the lone dust never changes
+        let dust = Pos { x: 0, y: 0, z: 5 };
+        assert!(!res.was_ever_powered(&world, dust));
+        assert_eq!(res.last_changed_tick(dust), None);
+
+        // a lever that starts on and is never touched agrees with power_at
+        let world = WorldBuilder::new().lever((0, 0, 0), Direction::East, true).build().unwrap();
+        let res = simulate(SimRequest { ticks: 5, world: world.clone(), ..Default::default() }).unwrap();
+        assert_eq!(res.power_at(&world, lever, 0), 15);
+        assert!(res.was_ever_powered(&world, lever));
+        assert_eq!(res.first_powered_tick(&world, lever), Some(0));
+    }
+
+    #[test]
+    fn weighted_plate_power_levels() {
+        let load = |mobs| EntityPressure { mobs, items: 0 };
+        assert_eq!(PlateKind::LightWeighted.power_for(&load(4)), 4);
+        assert_eq!(PlateKind::LightWeighted.power_for(&load(40)), 15);
+        assert_eq!(PlateKind::HeavyWeighted.power_for(&load(11)), 2);
+        assert_eq!(PlateKind::HeavyWeighted.power_for(&load(0)), 0);
+    }
+
+    #[test]
+    fn dispenser_fires_once_per_rising_edge() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, true)
+            .repeater((1, 0, 0), Direction::East, 1)
+            .dispenser((2, 0, 0), Direction::East)
+            .build()
+            .unwrap();
+        let req = SimRequest { ticks: 20, world, early_exit: false, ..Default::default() };
+        let res = simulate(req).unwrap();
+        assert_eq!(
+            res.launch_events,
+            vec![LaunchEvent {
+                tick: 2,
+                pos: Pos { x: 2, y: 0, z: 0 },
+                facing: Direction::East,
+                kind: LaunchKind::Dispense,
+            }]
+        );
+    }
+
+    #[test]
+    fn crafter_crafts_once_per_clock_period() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, false)
+            .crafter((1, 0, 0))
+            .comparator((2, 0, 0), Direction::East, ComparatorMode::Compare)
+            .build()
+            .unwrap();
+        let lever = Pos { x: 0, y: 0, z: 0 };
+        let crafter = Pos { x: 1, y: 0, z: 0 };
+        // a slow clock with a period of 10, then edges every 2 ticks
+        let toggles = [2, 7, 12, 17].into_iter().chain(30..38);
+        let events = toggles.map(|t| (t, TimedEvent::LeverToggle(lever))).collect();
+        let req = SimRequest { ticks: 50, world: world.clone(), early_exit: false, events, ..Default::default() };
+        let res = simulate(req).unwrap();
+        let ticks: Vec<u32> = res.craft_events.iter().map(|e| e.tick).collect();
+        // the rising edges at 32 and 36 fall inside the 4-tick cooldown
+        assert_eq!(ticks, vec![2, 12, 30, 34]);
+        assert!(res.craft_events.iter().all(|e| e.pos == crafter));
+        let final_state = res.final_state(&world);
+        assert_eq!(final_state[&Pos { x: 2, y: 0, z: 0 }], BlockKind::Comparator {
+            output: 4,
+            facing: Direction::East,
+            mode: ComparatorMode::Compare,
+        });
+    }
+
+    #[test]
+    fn crafter_count_wraps_instead_of_overflowing() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, false)
+            .block((1, 0, 0), BlockKind::Crafter { powered: false, cooldown: 0, craft_count: u32::MAX })
+            .build()
+            .unwrap();
+        let events = vec![(1, TimedEvent::LeverToggle(Pos::default()))];
+        let req = SimRequest { ticks: 10, world: world.clone(), events, ..Default::default() };
+        let res = simulate(req).unwrap();
+        assert_eq!(res.craft_events.len(), 1);
+        assert!(matches!(res.final_state(&world)[&Pos::from((1, 0, 0))], BlockKind::Crafter { craft_count: 0, .. }));
+    }
+
+    #[test]
+    fn bell_rings_for_two_ticks() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, true)
+            .dust((1, 0, 0))
+            .bell((2, 0, 0), BellAttachment::Floor, Direction::East)
+            .build()
+            .unwrap();
+        let req = SimRequest { ticks: 10, world, ..Default::default() };
+        let res = simulate(req).unwrap();
+        let bell = Pos { x: 2, y: 0, z: 0 };
+        let ringing: Vec<(u32, bool)> = res
+            .diffs
+            .iter()
+            .flat_map(|d| d.changes.iter().filter(|c| c.pos == bell).map(move |c| (d.tick, c.kind.clone())))
+            .filter_map(|(t, kind)| match kind {
+                BlockKind::Bell { ringing, .. } => Some((t, ringing)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(ringing, vec![(1, true), (2, true), (3, false)]);
+        assert_eq!(res.bell_events, vec![BellRingEvent { tick: 1, pos: bell, facing: Direction::East }]);
+        assert_eq!(res.terminated, Termination::Stable);
+        assert_eq!(BellAttachment::SingleWall.strike_faces(Direction::East), vec![Direction::North, Direction::South]);
+    }
+
+    #[test]
+    fn hopper_chain_moves_one_item_per_tick() {
+        let world = WorldBuilder::new()
+            .hopper((0, 0, 0), Direction::East, 3)
+            .hopper((1, 0, 0), Direction::Down, 0)
+            .build()
+            .unwrap();
+        let req = SimRequest { ticks: 10, world, ..Default::default() };
+        let res = simulate(req).unwrap();
+        let items_at = |tick: u32, pos: Pos| {
+            let diff = res.diffs.iter().find(|d| d.tick == tick)?;
+            match diff.find_change_at(pos)?.kind {
+                BlockKind::Hopper { items, .. } => Some(items),
+                _ => None,
+            }
+        };
+        let (src, dst) = (Pos { x: 0, y: 0, z: 0 }, Pos { x: 1, y: 0, z: 0 });
+        for tick in 1..=3 {
+            assert_eq!(items_at(tick, src), Some(3 - tick as u8));
+            assert_eq!(items_at(tick, dst), Some(tick as u8));
+        }
+        assert!(matches!(res.terminated, Termination::Stable));
+        assert_eq!(
+            container_signal_strength(&BlockKind::Hopper { enabled: true, facing: Direction::Down, items: 5 }),
+            Some(15)
+        );
+    }
+
+    #[test]
+    fn power_level_reads_dust_and_sources() {
+        assert_eq!(power_level(&BlockKind::Dust { power: 7, connections: [false, true, false, true] }), 7);
+        assert_eq!(power_level(&BlockKind::Lever { on: true, facing: Direction::East }), 15);
+        assert_eq!(power_level(&BlockKind::Lever { on: false, facing: Direction::East }), 0);
+        assert_eq!(power_level(&BlockKind::DaylightSensor { inverted: false, power: 9 }), 9);
+        assert_eq!(power_level(&BlockKind::Lamp { on: true }), 0);
+    }
+
+    #[test]
+    fn comparator_reads_hopper_fill_level() {
+        let world = WorldBuilder::new()
+            .hopper((0, 0, 0), Direction::Down, 5)
+            .comparator((1, 0, 0), Direction::East, ComparatorMode::Compare)
+            .hopper((0, 0, 2), Direction::Down, 0)
+            .comparator((1, 0, 2), Direction::East, ComparatorMode::Compare)
+            .build()
+            .unwrap();
+        let req = SimRequest { ticks: 10, world: world.clone(), ..Default::default() };
+        let state = simulate(req).unwrap().final_state(&world);
+        let output = |z| match state[&Pos { x: 1, y: 0, z }] {
+            BlockKind::Comparator { output, .. } => output,
+            _ => unreachable!(),
+        };
+        assert_eq!((output(0), output(2)), (15, 0));
+    }
+
+    #[test]
+    fn comparator_reads_chest_and_barrel_fill_level() {
+        let world = WorldBuilder::new()
+            .chest((0, 0, 0), 14)
+            .comparator((1, 0, 0), Direction::East, ComparatorMode::Compare)
+            .chest((0, 0, 2), 13)
+            .comparator((1, 0, 2), Direction::East, ComparatorMode::Compare)
+            .block((0, 0, 4), BlockKind::Chest { items: 27, max_items: 54 })
+            .comparator((1, 0, 4), Direction::East, ComparatorMode::Compare)
+            .barrel((0, 0, 6), 27)
+            .comparator((1, 0, 6), Direction::East, ComparatorMode::Compare)
+            .build()
+            .unwrap();
+        let state = simulate(SimRequest { world: world.clone(), ..Default::default() }).unwrap().final_state(&world);
+        let output = |z| match state[&Pos { x: 1, y: 0, z }] {
+            BlockKind::Comparator { output, .. } => output,
+            _ => unreachable!(),
+        };
+        // 1 + floor(14 × items / max_items): a chest just over half full reads 8
+        assert_eq!((output(0), output(2), output(4), output(6)), (8, 7, 8, 15));
+        assert_eq!(container_signal_strength(&BlockKind::Barrel { items: 0, max_items: 27 }), Some(0));
+
+        let oversized: World = [(Pos::default(), BlockKind::Chest { items: 0, max_items: 30 })].into_iter().collect();
+        assert!(matches!(oversized.validate(), Err(WorldError::InvalidBlockState { .. })));
+    }
+
+    #[test]
+    fn containers_without_capacity_never_reach_a_running_simulation() {
+        let empty = BlockKind::Chest { items: 3, max_items: 0 };
+        assert_eq!(container_signal_strength(&empty), Some(0));
+
+        let world = WorldBuilder::new().comparator((1, 0, 0), Direction::East, ComparatorMode::Compare).build().unwrap();
+        let events = vec![(1, TimedEvent::BlockReplace(Pos::default(), empty.clone()))];
+        let req = SimRequest { world: world.clone(), events, ..Default::default() };
+        assert!(matches!(simulate(req), Err(SimError::InvalidBlockState(_))));
+
+        let mut sim = SimRequest { world, ..Default::default() }.into_iter();
+        assert!(matches!(sim.add_block(Pos::default(), empty.clone()), Err(WorldError::InvalidBlockState { .. })));
+        assert!(matches!(sim.set_block(Pos::default(), empty), Err(WorldError::InvalidBlockState { .. })));
+        assert!(!sim.world().contains_key(&Pos::default()));
+        let barrel = BlockKind::Barrel { items: 30, max_items: 54 };
+        assert_eq!(sim.set_block(Pos::default(), barrel), Ok(None));
+        // 1 + floor(14 × 30 / 54)
+        while sim.next().is_some() {}
+        assert!(matches!(sim.world()[&Pos::from((1, 0, 0))], BlockKind::Comparator { output: 8, .. }));
+    }
+
+    #[test]
+    fn comparator_reads_furnace_fuel() {
+        let world = WorldBuilder::new()
+            .furnace((0, 0, 0), 0)
+            .comparator((1, 0, 0), Direction::East, ComparatorMode::Compare)
+            .build()
+            .unwrap();
+        let events = vec![(2, TimedEvent::FurnaceFuel(Pos::default(), FURNACE_MAX_FUEL - 1))];
+        let req = SimRequest { ticks: 10, world: world.clone(), early_exit: false, events, ..Default::default() };
+        let res = simulate(req).unwrap();
+        let comparator = Pos::from((1, 0, 0));
+        assert_eq!(res.power_at(&world, comparator, 1), 0);
+        // one tick short of full; the comparator settles a tick after the furnace
+        assert_eq!(res.power_at(&world, comparator, 3), 14);
+
+        // a full furnace burns down one level every FURNACE_MAX_FUEL / 15 ticks
+        let full = WorldBuilder::new()
+            .furnace((0, 0, 0), FURNACE_MAX_FUEL)
+            .comparator((1, 0, 0), Direction::East, ComparatorMode::Compare)
+            .build()
+            .unwrap();
+        let res = simulate(SimRequest { ticks: FURNACE_MAX_FUEL + 5, world: full.clone(), ..Default::default() }).unwrap();
+        let step = FURNACE_MAX_FUEL / 15;
+        for level in 0..15 {
+            let furnace = res.state_at_tick(level * step + 1, &full)[&Pos::default()].clone();
+            let BlockKind::Furnace { fuel_ticks, .. } = furnace else { unreachable!() };
+            let expected = container_signal_strength(&furnace).unwrap();
+            assert_eq!(fuel_ticks, FURNACE_MAX_FUEL - level * step - 1);
+            assert_eq!(res.power_at(&full, comparator, level * step + 2), expected, "level {level}");
+        }
+        let end = res.final_state(&full);
+        assert_eq!(end[&Pos::default()], BlockKind::Furnace { fuel_ticks: 0, cooking: false });
+        assert!(matches!(end[&comparator], BlockKind::Comparator { output: 0, .. }));
+        assert_eq!(res.terminated, Termination::Stable);
+    }
+
+    #[test]
+    fn update_order_decides_which_hopper_fills_the_last_slot() {
+        // two hoppers feed one that has room for a single item; whichever
+        // updates first takes the slot
+        let run = |west: u8, east: u8| {
+            let world = WorldBuilder::new()
+                .hopper((0, 0, 0), Direction::East, west)
+                .hopper((1, 0, 0), Direction::Down, 4)
+                .hopper((2, 0, 0), Direction::West, east)
+                .build()
+                .unwrap();
+            let req = SimRequest {
+                ticks: 10,
+                world: world.clone(),
+                update_order: UpdateOrder::Deterministic,
+                ..Default::default()
+            };
+            let state = simulate(req).unwrap().final_state(&world);
+            let items = |x| match state[&Pos { x, y: 0, z: 0 }] {
+                BlockKind::Hopper { items, .. } => items,
+                _ => unreachable!(),
+            };
+            (items(0), items(1), items(2))
+        };
+        assert_eq!(run(1, 1), (0, 5, 1));
+        assert_eq!(run(2, 1), (1, 5, 1));
+    }
+
+    #[test]
+    fn update_order_decides_hopper_races_and_change_order() {
+        // two hoppers race for the last slot of a third while a lamp and the
+        // head of a repeater chain switch on in the same tick
+        let world = WorldBuilder::new()
+            .hopper((0, 0, 3), Direction::East, 1)
+            .hopper((1, 0, 3), Direction::Down, 4)
+            .hopper((2, 0, 3), Direction::West, 1)
+            .lever((2, 0, 5), Direction::East, false)
+            .lamp((3, 0, 5))
+            .lever((4, 0, 0), Direction::East, false)
+            .repeater((5, 0, 0), Direction::East, 1)
+            .repeater((6, 0, 0), Direction::East, 1)
+            .build()
+            .unwrap();
+        let events = vec![
+            (1, TimedEvent::LeverToggle(Pos::from((2, 0, 5)))),
+            (1, TimedEvent::LeverToggle(Pos::from((4, 0, 0)))),
+        ];
+        let run = |update_order| {
+            let req = SimRequest { ticks: 10, world: world.clone(), events: events.clone(), update_order, ..Default::default() };
+            let res = simulate(req).unwrap();
+            let state = res.final_state(&world);
+            let items = |x| match state[&Pos { x, y: 0, z: 3 }] {
+                BlockKind::Hopper { items, .. } => items,
+                _ => unreachable!(),
+            };
+            let first_tick: Vec<Pos> = res.diffs[0].changes.iter().map(|c| c.pos).collect();
+            ((items(0), items(2)), first_tick)
+        };
+        let (deterministic, java) = (run(UpdateOrder::Deterministic), run(UpdateOrder::MojangJava));
+
+        // sorted by x the west hopper updates first and fills the slot
+        assert_eq!(deterministic.0, (0, 1));
+        assert_eq!(java.0, (0, 1));
+        // Java runs the repeater feeding a repeater before the lamp
+        let (lamp, repeater) = (Pos::from((3, 0, 5)), Pos::from((5, 0, 0)));
+        let index = |changes: &[Pos], p| changes.iter().position(|c| *c == p).unwrap();
+        assert!(index(&deterministic.1, lamp) < index(&deterministic.1, repeater));
+        assert!(index(&java.1, repeater) < index(&java.1, lamp));
+    }
+
+    #[test]
+    fn mojang_java_order_runs_repeater_chains_first() {
+        let world = WorldBuilder::new()
+            .lamp((0, 0, 0))
+            .repeater((5, 0, 0), Direction::East, 1)
+            .repeater((6, 0, 0), Direction::East, 1)
+            .build()
+            .unwrap();
+        let dirty: SpatialSet = world.blocks.iter().map(|b| b.pos).collect();
+        let blocks = world.into_map();
+        let xs = |order| update_sequence(&blocks, &dirty, order, 0, 1).iter().map(|p| p.x).collect::<Vec<_>>();
+        assert_eq!(xs(UpdateOrder::Deterministic), vec![0, 5, 6]);
+        assert_eq!(xs(UpdateOrder::MojangJava), vec![5, 6, 0]);
+    }
+
+    #[test]
+    fn random_order_replays_from_its_seed() {
+        // sixteen lamps that all light on the first tick, in update order
+        let world = (0..16)
+            .fold(WorldBuilder::new(), |b, z| b.lever((0, 0, z * 2), Direction::East, true).lamp((1, 0, z * 2)))
+            .build()
+            .unwrap();
+        let run = |seed| {
+            let req = SimRequest {
+                ticks: 5,
+                world: world.clone(),
+                update_order: UpdateOrder::Random,
+                seed,
+                ..Default::default()
+            };
+            simulate(req).unwrap()
+        };
+        let a = run(Some(7));
+        assert_eq!(a.seed_used, 7);
+        assert_eq!(a.diffs, run(Some(7)).diffs);
+        assert_ne!(a.diffs, run(Some(8)).diffs);
+        assert_eq!(a.final_state(&world), run(Some(8)).final_state(&world));
+
+        let drawn = run(None);
+        assert_eq!(drawn.diffs, run(Some(drawn.seed_used)).diffs);
+    }
+
+    fn lever_lamp_world(on: bool) -> World {
+        WorldBuilder::new().lever((0, 0, 0), Direction::East, on).lamp((1, 0, 0)).build().unwrap()
+    }
+
+    #[test]
+    fn iterator_steps_one_tick_at_a_time() {
+        let req = SimRequest { ticks: 10, world: lever_lamp_world(true), ..Default::default() };
+        let mut sim = req.into_iter();
+        let first: Vec<TickDiff> = sim.by_ref().take(3).collect();
+        // the lamp lights on tick 1; tick 2 changes nothing, so the run ends stable
+        assert_eq!(first.iter().map(|d| d.tick).collect::<Vec<_>>(), vec![1]);
+        assert!(first[0].changed_lamps().any(|(_, on)| on));
+        assert_eq!(sim.tick(), 2);
+        assert!(matches!(sim.world()[&Pos { x: 1, y: 0, z: 0 }], BlockKind::Lamp { on: true }));
+    }
+
+    #[test]
+    fn iterator_allows_toggling_a_lever_mid_run() {
+        let req = SimRequest { ticks: 10, world: lever_lamp_world(false), early_exit: false, ..Default::default() };
+        let mut sim = req.into_iter();
+        let quiet: Vec<TickDiff> = sim.by_ref().take(3).collect();
+        assert_eq!(quiet.len(), 3);
+        assert!(quiet.iter().all(|d| d.changes.is_empty()));
+
+        let lever = Pos { x: 0, y: 0, z: 0 };
+        sim.set_block(lever, BlockKind::Lever { on: true, facing: Direction::East }).unwrap();
+        let diff = sim.next().unwrap();
+        assert_eq!(diff.tick, 4);
+        assert!(diff.changed_lamps().any(|(_, on)| on));
+    }
+
+    #[test]
+    fn peek_matches_the_next_tick() {
+        let mut sim = SimRequest { world: lever_lamp_world(false), early_exit: false, ..Default::default() }.into_iter();
+        sim.by_ref().take(2).for_each(drop);
+        assert!(sim.peek_next_changes().unwrap().changes.is_empty());
+
+        let lever = Pos { x: 0, y: 0, z: 0 };
+        sim.set_block(lever, BlockKind::Lever { on: true, facing: Direction::East }).unwrap();
+        let peeked = sim.peek_next_changes();
+        assert!(!peeked.as_ref().unwrap().changes.is_empty());
+        assert_eq!(sim.peek_next_changes(), peeked);
+        assert_eq!(sim.tick(), 2);
+        assert_eq!(sim.next(), peeked);
+
+        // each following tick too, until the run ends
+        for _ in 0..5 {
+            let peeked = sim.peek_next_changes();
+            assert_eq!(sim.next(), peeked);
+        }
+
+        // a retracting sticky piston removes the block it pulls back
+        let mut sim = Simulation::new(SimRequest { ticks: 5, world: piston_retraction_world(true), ..Default::default() });
+        let peeked = sim.peek_next_changes();
+        assert_eq!(peeked.as_ref().unwrap().removed, vec![Pos { x: 2, y: 0, z: 0 }]);
+        assert_eq!(sim.step(), peeked);
+    }
+
+    #[test]
+    fn world_add_and_remove_block() {
+        let mut world = lever_lamp_world(true);
+        let lamp = Pos { x: 1, y: 0, z: 0 };
+        assert_eq!(
+            world.add_block(lamp, BlockKind::Lamp { on: false }),
+            Err(WorldError::PositionOccupied(lamp))
+        );
+        assert!(matches!(world.remove_block(lamp), Some(BlockKind::Lamp { .. })));
+        assert!(world.remove_block(lamp).is_none());
+        assert_eq!(world.add_block(lamp, BlockKind::Lamp { on: false }), Ok(()));
+        assert_eq!(world.blocks.len(), 2);
+    }
+
+    #[test]
+    fn block_kind_classification() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, true)
+            .button((0, 0, 2), Direction::East)
+            .dust((1, 0, 0))
+            .repeater((2, 0, 0), Direction::East, 2)
+            .lamp((3, 0, 0))
+            .redstone_block((0, 0, 4))
+            .build()
+            .unwrap();
+        let kinds: Vec<&BlockKind> = world.blocks.iter().map(|b| &b.kind).collect();
+        let names: Vec<&str> = kinds.iter().map(|k| k.name()).collect();
+        assert_eq!(names, ["lever", "button", "dust", "repeater", "lamp", "redstoneblock"]);
+        let flags = |f: fn(&BlockKind) -> bool| kinds.iter().map(|k| f(k)).collect::<Vec<_>>();
+        assert_eq!(flags(BlockKind::is_power_source), [true, true, false, false, false, true]);
+        assert_eq!(flags(BlockKind::is_output), [false, false, false, false, true, false]);
+        assert_eq!(flags(BlockKind::is_timer), [false, true, false, true, false, false]);
+
+        assert_eq!(kinds[0].current_power(), 15);
+        assert_eq!(BlockKind::Lamp { on: true }.current_power(), 15);
+        assert_eq!(BlockKind::Lamp { on: false }.current_power(), 0);
+        assert_eq!(BlockKind::Dust { power: 9, connections: Default::default() }.current_power(), 9);
+    }
+
+    #[test]
+    fn world_statistics() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, true)
+            .dust((1, 0, 0))
+            .dust((2, 0, 0))
+            .lamp((3, 0, 0))
+            .build()
+            .unwrap();
+        let counts = world.count_by_kind();
+        assert_eq!((counts["dust"], counts["lamp"], counts["lever"], counts.len()), (2, 1, 1, 3));
+        assert_eq!(world.total_blocks(), 4);
+        assert!(world.has_block_at(Pos { x: 2, y: 0, z: 0 }));
+        assert!(!world.has_block_at(Pos { x: 4, y: 0, z: 0 }));
+        assert_eq!(world.blocks_of_kind("dust"), vec![Pos { x: 1, y: 0, z: 0 }, Pos { x: 2, y: 0, z: 0 }]);
+        assert!(world.blocks_of_kind("repeater").is_empty());
+    }
+
+    #[test]
+    fn world_container_api() {
+        let world = WorldBuilder::new().lever((0, 0, 0), Direction::East, true).lamp((1, 0, 0)).build().unwrap();
+        let lamp = Pos { x: 1, y: 0, z: 0 };
+        assert!(!world.is_empty());
+        assert!(World { blocks: vec![] }.is_empty());
+        assert_eq!(world.positions().collect::<Vec<_>>(), vec![Pos { x: 0, y: 0, z: 0 }, lamp]);
+        assert_eq!(world.kinds().map(BlockKind::type_name).collect::<Vec<_>>(), vec!["lever", "lamp"]);
+        assert_eq!(world.iter().nth(1), Some((lamp, &BlockKind::Lamp { on: false })));
+        assert_eq!(world.get(lamp), Some(&BlockKind::Lamp { on: false }));
+        assert_eq!(world.get(Pos { x: 2, y: 0, z: 0 }), None);
+        assert!(world.contains(lamp) && !world.contains(Pos { x: 2, y: 0, z: 0 }));
+
+        let rebuilt: World = world.clone().into_iter().collect();
+        assert_eq!(rebuilt, world);
+    }
+
+    #[test]
+    fn iterator_removing_a_lever_turns_the_lamp_off() {
+        let req = SimRequest { ticks: 10, world: lever_lamp_world(true), ..Default::default() };
+        let mut sim = req.into_iter();
+        assert_eq!(sim.by_ref().count(), 1);
+        assert!(sim.remove_block(Pos { x: 0, y: 0, z: 0 }).is_some());
+        let diff = sim.next().unwrap();
+        assert_eq!(diff.changed_lamps().collect::<Vec<_>>(), vec![(Pos { x: 1, y: 0, z: 0 }, false)]);
+    }
+
+    #[test]
+    fn simulate_until_stops_when_the_lamp_lights() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, true)
+            .repeater((1, 0, 0), Direction::East, 4)
+            .repeater((2, 0, 0), Direction::East, 4)
+            .lamp((3, 0, 0))
+            .build()
+            .unwrap();
+        let req = SimRequest { ticks: 50, world, ..Default::default() };
+        let lamp = Pos { x: 3, y: 0, z: 0 };
+        let lit = |w: &SpatialGrid<BlockKind>| matches!(w.get(&lamp), Some(BlockKind::Lamp { on: true }));
+
+        let full = simulate(req.clone()).unwrap();
+        let res = simulate_until(req.clone(), lit).unwrap();
+        assert_eq!(res.terminated, Termination::PredicateMet);
+        assert!(res.diffs.last().unwrap().changed_lamps().any(|(_, on)| on));
+        assert_eq!(res.diffs[..], full.diffs[..res.diffs.len()]);
+
+        // the same stop, phrased the other way round
+        assert_eq!(simulate_while(req.clone(), |w| !lit(w)).unwrap(), res);
+        // a condition that already holds stops before the first tick
+        let res = simulate_while(req.clone(), lit).unwrap();
+        assert!(res.diffs.is_empty() && res.terminated == Termination::PredicateMet);
+        // one that never holds runs like simulate
+        assert_eq!(simulate_until(req, |_| false).unwrap(), full);
+    }
+
+    #[test]
+    fn iterator_push_event_resumes_a_stable_run() {
+        let req = SimRequest { ticks: 10, world: lever_lamp_world(false), ..Default::default() };
+        let mut sim = req.into_iter();
+        sim.by_ref().for_each(drop);
+        let settled = sim.tick();
+        sim.push_event(TimedEvent::LeverToggle(Pos { x: 0, y: 0, z: 0 }));
+        let lit = sim.by_ref().find(|d| d.changed_lamps().any(|(_, on)| on)).unwrap();
+        assert!(lit.tick > settled);
+    }
+
+    #[test]
+    fn builder_rejects_duplicate_positions() {
+        let err = WorldBuilder::new().dust((0, 0, 0)).lamp((1, 0, 0)).lamp((0, 0, 0)).build();
+        assert_eq!(err.unwrap_err(), BuildError::DuplicatePosition(Pos { x: 0, y: 0, z: 0 }));
+        let world = WorldBuilder::new().door((0, 0, 0), Direction::North, HingePosition::Right).build().unwrap();
+        assert_eq!(world.blocks.len(), 2);
+    }
+
+    #[test]
+    fn pos_arithmetic() {
+        let p = Pos::from((1, 2, 3));
+        assert_eq!(p + Direction::Up, Pos { x: 1, y: 3, z: 3 });
+        assert_eq!(p + (1, 1, 1) - (2, 2, 2), Pos { x: 0, y: 1, z: 2 });
+        assert_eq!(-p, Pos { x: -1, y: -2, z: -3 });
+        assert_eq!(<(i32, i32, i32)>::from(p), (1, 2, 3));
+        assert_eq!(p.manhattan_distance(Pos { x: -1, y: 2, z: 0 }), 5);
+        assert_eq!(p.distance_squared(Pos { x: -1, y: 2, z: 0 }), 13);
+        assert_eq!(p.chebyshev_distance(Pos { x: -1, y: 2, z: 0 }), 3);
+        assert_eq!(p.euclidean_distance(Pos { x: 4, y: 6, z: 3 }), 5.0);
+        assert!(p.within_radius(Pos { x: 4, y: 6, z: 3 }, 5.0));
+        assert!(!p.within_radius(Pos { x: 4, y: 6, z: 4 }, 5.0));
+        // opposite corners of the world overflow none of them
+        let lo = Pos { x: -WORLD_BORDER, y: WORLD_MIN_Y, z: -WORLD_BORDER };
+        let hi = Pos { x: WORLD_BORDER, y: WORLD_MAX_Y, z: WORLD_BORDER };
+        let (across, height) = (2 * WORLD_BORDER, WORLD_MAX_Y - WORLD_MIN_Y);
+        assert_eq!(lo.manhattan_distance(hi), (2 * across + height) as u32);
+        assert_eq!(lo.distance_squared(hi), 2 * i64::from(across).pow(2) + i64::from(height).pow(2));
+        assert_eq!(hi.chebyshev_distance(lo), across);
+        assert!(lo.within_radius(hi, 1e8));
+        assert_eq!(p.neighbors()[0], (p + Direction::all()[0], Direction::all()[0]));
+        assert_eq!(p.neighbor_in(Direction::Down), Pos { x: 1, y: 1, z: 3 });
+        assert_eq!(p.to_string(), "(1,2,3)");
+    }
+
+    #[test]
+    fn pos_parses_every_accepted_format() {
+        let p = Pos { x: 1, y: -2, z: 3 };
+        for text in ["1,-2,3", "(1, -2, 3)", " 1 -2 3 ", "x=1 y=-2 z=3", "z=3, x=1, y=-2", "(1,-2,3)"] {
+            assert_eq!(text.parse::<Pos>(), Ok(p), "{text}");
+        }
+        assert_eq!(p.to_string().parse::<Pos>(), Ok(p));
+
+        assert_eq!("1,2".parse::<Pos>(), Err(PosParseError::WrongCount(2)));
+        assert_eq!("1,2,three".parse::<Pos>(), Err(PosParseError::BadCoordinate("three".into())));
+        assert_eq!("x=1 x=2 z=3".parse::<Pos>(), Err(PosParseError::BadAxis("x".into())));
+        assert_eq!("x=1 w=2 z=3".parse::<Pos>(), Err(PosParseError::BadAxis("w".into())));
+        assert_eq!("".parse::<Pos>().unwrap_err().to_string(), "expected 3 coordinates x,y,z, found 0");
+        assert_eq!(
+            "1,2,3.5".parse::<Pos>().unwrap_err().to_string(),
+            "coordinate \"3.5\" is not an integer"
+        );
+    }
+
+    #[test]
+    fn direction_helpers() {
+        assert_eq!(Direction::North.clockwise(), Some(Direction::East));
+        assert_eq!(Direction::North.counterclockwise(), Some(Direction::West));
+        assert_eq!(Direction::Up.clockwise(), None);
+        assert!(Direction::horizontal_directions().iter().all(|d| d.is_horizontal()));
+        assert!(Direction::vertical_directions().iter().all(|d| d.is_vertical()));
+        for d in Direction::all() {
+            assert_eq!(d.to_string().parse::<Direction>(), Ok(d));
+        }
+        assert!("sideways".parse::<Direction>().is_err());
+        for text in ["north", "North", "NORTH", "n", "N"] {
+            assert_eq!(text.parse::<Direction>(), Ok(Direction::North), "{text}");
+        }
+        assert_eq!("d".parse::<Direction>(), Ok(Direction::Down));
+        assert_eq!("".parse::<Direction>(), Err(DirectionError::UnknownName(String::new())));
+        assert_eq!("no".parse::<Direction>().unwrap_err().to_string(), "unknown direction \"no\"");
+        assert_eq!(Direction::try_from(3), Ok(Direction::West));
+        assert_eq!(Direction::try_from(6), Err(DirectionError::OutOfRange(6)));
+    }
+
+    #[test]
+    fn dir_from_to_rejects_non_adjacent() {
+        let (a, b) = (Pos { x: 0, y: 0, z: 0 }, Pos { x: 2, y: 0, z: 0 });
+        assert_eq!(dir_from_to(a, a + Direction::East), Ok(Direction::East));
+        let err = SimError::from(dir_from_to(a, b).unwrap_err());
+        assert_eq!(err, SimError::NonAdjacentBlock(NonAdjacentError { from: a, to: b }));
+    }
+
+    #[test]
+    fn invalid_world_is_rejected() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, true)
+            .repeater((1, 0, 0), Direction::Up, 1)
+            .build()
+            .unwrap();
+        let req = SimRequest { ticks: 5, world, ..Default::default() };
+        assert!(matches!(simulate(req), Err(SimError::InvalidBlockState(_))));
+
+        let above = WorldBuilder::new().lamp((0, WORLD_MAX_Y + 1, 0)).build().unwrap();
+        assert!(matches!(above.validate(), Err(WorldError::OutOfBounds(_))));
+    }
+
+    // found by fuzz_simulate: neighbours of a block at i32::MAX overflowed
+    #[test]
+    fn positions_past_the_world_border_are_rejected() {
+        let edge = WorldBuilder::new().lever((i32::MAX, 0, 0), Direction::East, true).dust((i32::MAX, 1, 0)).build().unwrap();
+        assert!(matches!(edge.validate(), Err(WorldError::OutOfBounds(_))));
+        let req = SimRequest { ticks: 5, world: edge, early_exit: false, ..Default::default() };
+        assert!(matches!(simulate(req), Err(SimError::InvalidBlockState(_))));
+
+        let far = Pos { x: i32::MIN, y: 0, z: i32::MIN };
+        let req = SimRequest {
+            ticks: 5,
+            world: lever_lamp_world(true),
+            early_exit: false,
+            events: vec![(1, TimedEvent::BlockReplace(far, BlockKind::RedstoneBlock))],
+            vibrations: vec![(1, far, 3)],
+            ..Default::default()
+        };
+        assert!(matches!(simulate(req), Err(SimError::InvalidBlockState(_))));
+    }
+
+    #[test]
+    fn final_state_replays_diffs() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, true)
+            .dust((1, 0, 0))
+            .lamp((2, 0, 0))
+            .build()
+            .unwrap();
+        let req = SimRequest { ticks: 10, world: world.clone(), ..Default::default() };
+        let res = simulate(req).unwrap();
+        let expected: SpatialGrid<BlockKind> = [
+            (Pos { x: 0, y: 0, z: 0 }, BlockKind::Lever { on: true, facing: Direction::East }),
+            (Pos { x: 1, y: 0, z: 0 }, BlockKind::Dust { power: 15, connections: [false, true, false, true] }),
+            (Pos { x: 2, y: 0, z: 0 }, BlockKind::Lamp { on: true }),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(res.final_state(&world), expected);
+        let start = res.state_at_tick(0, &world);
+        assert_eq!(start[&Pos { x: 2, y: 0, z: 0 }], BlockKind::Lamp { on: false });
+    }
+
+    #[test]
+    fn redstone_block_powers_dust() {
+        let world = WorldBuilder::new().redstone_block((0, 0, 0)).dust((1, 0, 0)).build().unwrap();
+        let req = SimRequest { ticks: 5, world, ..Default::default() };
+        let res = simulate(req).unwrap();
+        assert!(matches!(res.terminated, Termination::Stable));
+        let changes: Vec<&BlockChange> = res.diffs.iter().flat_map(|d| d.changes.iter()).collect();
+        assert_eq!(changes.len(), 1);
+        assert!(matches!(changes[0].kind, BlockKind::Dust { power: 14, .. }));
+    }
+
+    #[test]
+    fn strongly_powered_block_feeds_dust() {
+        let world = WorldBuilder::new()
+            .lever((-2, 0, 0), Direction::East, true)
+            .repeater((-1, 0, 0), Direction::East, 1)
+            .solid((0, 0, 0))
+            .dust((1, 0, 0))
+            .dust((2, 0, 0))
+            .build()
+            .unwrap();
+        let req = SimRequest { ticks: 10, world, ..Default::default() };
+        let res = simulate(req).unwrap();
+        assert!(matches!(res.terminated, Termination::Stable));
+        let changes: Vec<&BlockChange> = res.diffs.iter().flat_map(|d| d.changes.iter()).collect();
+        assert!(changes.iter().any(|c| matches!(c.kind, BlockKind::OpaqueSolid { strong_power: 15 })));
+        assert!(changes.iter().any(|c| c.pos.x == 2 && matches!(c.kind, BlockKind::Dust { power: 13, .. })));
+    }
+
+    #[test]
+    fn side_powered_repeater_holds_state() {
+        let world = WorldBuilder::new()
+            // observer clock driving the back of the locked repeater
+            .block((-2, 0, 0), BlockKind::Observer { facing: Direction::East, powered: true })
+            .observer((-1, 0, 0), Direction::West)
+            .repeater((0, 0, 0), Direction::East, 1)
+            // powered repeater feeding the side
+            .lever((0, 0, -2), Direction::South, true)
+            .block(
+                (0, 0, -1),
+                BlockKind::Repeater {
+                    delay: 1,
+                    ticks_remaining: 0,
+                    powered: true,
+                    facing: Direction::South,
+                    locked: false,
                 },
-            ],
+            )
+            .build()
+            .unwrap();
+        let req = SimRequest { ticks: 12, world, ..Default::default() };
+        let res = simulate(req).unwrap();
+        let target = Pos { x: 0, y: 0, z: 0 };
+        let states: Vec<&BlockKind> = res
+            .diffs
+            .iter()
+            .flat_map(|d| d.changes.iter())
+            .filter(|c| c.pos == target)
+            .map(|c| &c.kind)
+            .collect();
+        assert!(matches!(states.first(), Some(BlockKind::Repeater { locked: true, .. })));
+        assert!(!states.iter().any(|k| matches!(k, BlockKind::Repeater { powered: true, .. })));
+    }
+
+    #[test]
+    fn comparator_subtract_mode() {
+        let mut builder = WorldBuilder::new()
+            .comparator((0, 0, 0), Direction::East, ComparatorMode::Subtract)
+            // rear: lever + 6 dust -> 10 at the comparator
+            .lever((-7, 0, 0), Direction::East, true)
+            // side: lever + 12 dust -> 4 at the comparator
+            .lever((0, 0, 13), Direction::North, true);
+        for x in -6..=-1 {
+            builder = builder.dust((x, 0, 0));
+        }
+        for z in 1..=12 {
+            builder = builder.dust((0, 0, z));
+        }
+        let req = SimRequest { ticks: 40, world: builder.build().unwrap(), ..Default::default() };
+        let res = simulate(req).unwrap();
+        assert!(matches!(res.terminated, Termination::Stable));
+        let last = res
+            .diffs
+            .iter()
+            .flat_map(|d| d.changes.iter())
+            .rev()
+            .find_map(|c| match c.kind {
+                BlockKind::Comparator { output, .. } => Some(output),
+                _ => None,
+            });
+        assert_eq!(last, Some(6));
+    }
+
+    #[test]
+    fn observer_emits_one_tick_pulse() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, true)
+            .dust((1, 0, 0))
+            .observer((1, 0, 1), Direction::North)
+            .lamp((1, 0, 2))
+            .build()
+            .unwrap();
+        let req = SimRequest { ticks: 10, world, ..Default::default() };
+        let res = simulate(req).unwrap();
+        assert!(matches!(res.terminated, Termination::Stable));
+        let observer_states: Vec<(u32, bool)> = res
+            .diffs
+            .iter()
+            .flat_map(|d| {
+                d.changes.iter().filter_map(move |c| match c.kind {
+                    BlockKind::Observer { powered, .. } => Some((d.tick, powered)),
+                    _ => None,
+                })
+            })
+            .collect();
+        // dust changes at tick 1, observer fires at tick 2 and resets at tick 3
+        assert_eq!(observer_states, vec![(2, true), (3, false)]);
+        assert!(res.diffs.iter().any(|d| d.tick == 3 && d.changed_lamps().any(|(_, on)| on)));
+    }
+
+    #[test]
+    fn two_observers_form_a_clock() {
+        let world = WorldBuilder::new()
+            .block((0, 0, 0), BlockKind::Observer { facing: Direction::East, powered: true })
+            .observer((1, 0, 0), Direction::West)
+            .build()
+            .unwrap();
+        let req = SimRequest { ticks: 20, world, ..Default::default() };
+        let res = simulate(req).unwrap();
+        assert!(matches!(res.terminated, Termination::MaxTicksReached));
+        assert_eq!(res.diffs.len(), 20);
+    }
+
+    #[test]
+    fn world_transforms() {
+        let world = WorldBuilder::new()
+            .lever((1, 0, 0), Direction::East, true)
+            .powered_rail((2, 0, 0), RailShape::AscendingEast)
+            .door((0, 0, 3), Direction::North, HingePosition::Left)
+            .build()
+            .unwrap();
+
+        let quarter = world.rotate(RotationAngle::Degrees90);
+        assert_eq!(quarter.blocks[0].pos, Pos::from((0, 0, 1)));
+        assert!(matches!(quarter.blocks[0].kind, BlockKind::Lever { facing: Direction::South, .. }));
+        assert!(matches!(quarter.blocks[1].kind, BlockKind::PoweredRail { shape: RailShape::AscendingSouth, .. }));
+        let full = (0..3).fold(quarter, |w, _| w.rotate(RotationAngle::Degrees90));
+        assert_eq!(full, world);
+        assert_eq!(world.rotate(RotationAngle::Degrees180).rotate(RotationAngle::Degrees180), world);
+        assert_eq!(world.rotate(RotationAngle::Degrees270).rotate(RotationAngle::Degrees90), world);
+
+        let mirrored = world.mirror(Axis::X);
+        assert_eq!(mirrored.blocks[0].pos, Pos::from((-1, 0, 0)));
+        assert!(matches!(mirrored.blocks[0].kind, BlockKind::Lever { facing: Direction::West, .. }));
+        assert!(matches!(mirrored.blocks[2].kind, BlockKind::Door { hinge: HingePosition::Right, .. }));
+        assert_eq!(mirrored.mirror(Axis::X), world);
+        assert_eq!(world.mirror(Axis::Z).mirror(Axis::Z), world);
+
+        let moved = world.translate(Pos::from((5, -2, 1)));
+        assert_eq!(moved.blocks[0].pos, Pos::from((6, -2, 1)));
+        assert_eq!(moved.translate(Pos::from((-5, 2, -1))), world);
+    }
+
+    #[test]
+    fn world_bounds() {
+        assert_eq!(World { blocks: vec![] }.bounds(), None);
+        let world = WorldBuilder::new()
+            .lever((-2, 0, 1), Direction::East, true)
+            .dust((1, 0, 1))
+            .lamp((1, 3, -1))
+            .build()
+            .unwrap();
+        let bb = world.bounds().unwrap();
+        assert_eq!(bb, BoundingBox { min: Pos::from((-2, 0, -1)), max: Pos::from((1, 3, 1)) });
+        assert_eq!(bb.size(), (4, 4, 3));
+        assert_eq!(bb.volume(), 48);
+        assert_eq!(bb.center(), Pos::from((-1, 1, 0)));
+        assert!(bb.contains(Pos::from((0, 2, 0))) && !bb.contains(Pos::from((2, 0, 0))));
+        assert_eq!(bb.expand(1).size(), (6, 6, 5));
+
+        let normalized = world.normalize();
+        assert_eq!(normalized.bounds().unwrap().min, Pos::from((0, 0, 0)));
+        assert_eq!(normalized.blocks[0].pos, Pos::from((0, 0, 2)));
+    }
+
+    #[test]
+    fn block_kinds_hash_and_sort() {
+        let lamp = BlockKind::Lamp { on: false };
+        let kinds: std::collections::HashSet<BlockKind> =
+            [lamp.clone(), BlockKind::default(), lamp.clone(), BlockKind::default()].into_iter().collect();
+        assert_eq!(kinds.len(), 2);
+
+        let mut sorted = vec![lamp.clone(), BlockKind::Dust { power: 3, connections: [false; 4] }, BlockKind::default()];
+        sorted.sort();
+        assert_eq!(sorted, vec![BlockKind::default(), BlockKind::Dust { power: 3, connections: [false; 4] }, lamp]);
+
+        let world = WorldBuilder::new().lamp((1, 0, 0)).dust((0, 5, 0)).dust((0, 0, 1)).build().unwrap();
+        let by_pos: std::collections::BTreeMap<Pos, BlockKind> = world.into_iter().collect();
+        let order: Vec<Pos> = by_pos.into_keys().collect();
+        assert_eq!(order, vec![Pos::from((0, 0, 1)), Pos::from((0, 5, 0)), Pos::from((1, 0, 0))]);
+    }
+
+    #[test]
+    fn defaults_support_struct_update() {
+        assert!(World::default().is_empty());
+        assert_eq!(Pos::default(), Pos::from((0, 0, 0)));
+        assert_eq!(BoundingBox::default().volume(), 1);
+        assert_eq!(BlockKind::default().type_name(), "dust");
+        assert_eq!(BlockKind::default().current_power(), 0);
+
+        let req = SimRequest { world: lever_lamp_world(true), ..Default::default() };
+        assert_eq!((req.ticks, req.early_exit, req.time_advance_per_tick), (100, true, 1));
+        let resp = simulate(req).unwrap();
+        assert_eq!(resp.terminated, Termination::Stable);
+        let empty = simulate(SimRequest::default()).unwrap();
+        assert_eq!(empty, SimResponse { seed_used: empty.seed_used, ..Default::default() });
+    }
+
+    #[test]
+    fn copy_and_paste_a_region() {
+        let mut world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, false)
+            .dust((1, 0, 0))
+            .lamp((2, 0, 0))
+            .build()
+            .unwrap();
+        let copy = world.copy_region(Pos::from((2, 0, 0)), Pos::from((0, 0, 0)), Pos::from((10, 0, 0))).unwrap();
+        assert_eq!(copy.positions().collect::<Vec<_>>(), [(10, 0, 0), (11, 0, 0), (12, 0, 0)].map(Pos::from));
+        assert_eq!(copy.get(Pos::from((10, 0, 0))), world.get(Pos::from((0, 0, 0))));
+        assert_eq!(
+            world.copy_region(Pos::from((5, 5, 5)), Pos::from((6, 6, 6)), Pos::default()),
+            Err(CopyError::EmptyRegion)
+        );
+
+        world.paste_into(&copy).unwrap();
+        assert_eq!(world.total_blocks(), 6);
+        let again = world.paste_into(&copy).unwrap_err();
+        assert_eq!(again, PasteError::PositionConflict(copy.positions().collect()));
+        assert_eq!(world.total_blocks(), 6);
+
+        // both copies work on their own
+        let events = [(0, 0, 0), (10, 0, 0)].map(|p| (1, TimedEvent::LeverToggle(Pos::from(p)))).to_vec();
+        let resp = simulate(SimRequest { world: world.clone(), events, ..Default::default() }).unwrap();
+        let end = resp.final_state(&world);
+        assert_eq!(end[&Pos::from((2, 0, 0))], BlockKind::Lamp { on: true });
+        assert_eq!(end[&Pos::from((12, 0, 0))], BlockKind::Lamp { on: true });
+    }
+
+    #[test]
+    fn merge_and_subtract_worlds() {
+        let base = WorldBuilder::new().lever((0, 0, 0), Direction::East, true).dust((1, 0, 0)).build().unwrap();
+        let part = WorldBuilder::new().dust((0, 0, 0)).lamp((1, 0, 0)).build().unwrap();
+
+        let merged = base.merge(&part, Pos::from((2, 0, 0))).unwrap();
+        let positions: Vec<i32> = merged.blocks.iter().map(|b| b.pos.x).collect();
+        assert_eq!(positions, vec![0, 1, 2, 3]);
+        assert_eq!(
+            base.merge(&part, Pos::from((1, 0, 0))),
+            Err(MergeError::PositionConflict(vec![Pos::from((1, 0, 0))]))
+        );
+
+        // the lever gives way to the dust, the dust to the lamp
+        assert_eq!(base.merge_override(&part, Pos::from((0, 0, 0))), part);
+        assert_eq!(base.merge_override(&part, Pos::from((1, 0, 0))).blocks.len(), 3);
+        assert_eq!(merged.subtract(&part.translate(Pos::from((2, 0, 0)))), base);
+    }
+
+    #[test]
+    fn extract_and_remove_region() {
+        let mut world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, true)
+            .dust((1, 0, 0))
+            .repeater((2, 0, 0), Direction::East, 3)
+            .lamp((3, 0, 0))
+            .lamp((1, 5, 0))
+            .build()
+            .unwrap();
+        let region = world.extract_region(Pos::from((2, 1, 0)), Pos::from((1, 0, 0)));
+        assert_eq!(region.blocks, world.blocks[1..3].to_vec());
+        world.remove_region(Pos::from((1, 0, 0)), Pos::from((2, 1, 0)));
+        let left: Vec<Pos> = world.blocks.iter().map(|b| b.pos).collect();
+        assert_eq!(left, vec![Pos::from((0, 0, 0)), Pos::from((3, 0, 0)), Pos::from((1, 5, 0))]);
+    }
+
+    /// 50 independent lever → dust → repeater → dust → lamp chains, 500 blocks.
+    fn independent_chains() -> World {
+        (0..50)
+            .fold(WorldBuilder::new(), |b, z| {
+                (1..8)
+                    .fold(b.lever((0, 0, z * 2), Direction::East, true), |b, x| b.dust((x, 0, z * 2)))
+                    .repeater((8, 0, z * 2), Direction::East, 4)
+                    .lamp((9, 0, z * 2))
+            })
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn parallel_matches_sequential() {
+        let run = |parallel| {
+            let req = SimRequest { ticks: 20, world: independent_chains(), parallel, ..Default::default() };
+            let res = simulate(req).unwrap();
+            let world = independent_chains();
+            (res.diffs.len(), res.final_state(&world))
+        };
+        assert_eq!(run(true), run(false));
+    }
+
+    #[test]
+    fn simulate_many_keeps_request_order() {
+        let req = |ticks| SimRequest { ticks, world: independent_chains(), early_exit: false, ..Default::default() };
+        let lens = |results: Vec<Result<SimResponse, SimError>>| -> Vec<usize> {
+            results.into_iter().map(|r| r.unwrap().diffs.len()).collect()
         };
-        let req = SimRequest { ticks: 3, world, early_exit: true };
-        let res = simulate(req);
-        assert!(!res.diffs.iter().any(|d| d.changes.iter().any(|c| matches!(c.kind, BlockKind::Lamp { on: true }))));
+        let many = lens(simulate_many(vec![req(2), req(6), req(4)]));
+        assert_eq!(many, lens(vec![simulate(req(2)), simulate(req(6)), simulate(req(4))]));
+        assert!(many[0] < many[2] && many[2] < many[1]);
     }
 }
 
+pub mod analysis;
+pub mod blockstate;
+pub mod chunk;
+pub mod circuits;
+pub mod compress;
+pub mod recording;
+pub mod grid;
+#[cfg(feature = "bincode")]
+pub mod binary;
+#[cfg(feature = "msgpack")]
+pub mod msgpack;
+#[cfg(feature = "nbt")]
+pub mod nbt;
+#[cfg(feature = "schematic")]
+pub mod schematic;
+#[cfg(feature = "csv")]
+pub mod csv_io;
+#[cfg(feature = "yaml")]
+pub mod yaml;
+#[cfg(feature = "compact")]
+pub mod compact;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "async")]
+pub mod async_sim;
+#[cfg(feature = "async")]
+pub use async_sim::{simulate_async, simulate_streaming};
+#[cfg(feature = "python")]
 pub mod py;
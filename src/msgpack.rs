@@ -0,0 +1,108 @@
+// src/msgpack.rs
+
+// MessagePack encoding through serde. Unlike the bincode format this keeps
+// field names, so it reads the same shapes as the JSON interface.
+
+use crate::{simulate, SimError, SimRequest};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MsgPackError {
+    Encode(String),
+    Decode(String),
+    Simulation(SimError), // the request decoded but could not be simulated
+}
+
+impl std::fmt::Display for MsgPackError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MsgPackError::Encode(e) => write!(f, "MessagePack encoding failed: {}", e),
+            MsgPackError::Decode(e) => write!(f, "invalid MessagePack data: {}", e),
+            MsgPackError::Simulation(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for MsgPackError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MsgPackError::Simulation(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<SimError> for MsgPackError {
+    fn from(e: SimError) -> Self {
+        MsgPackError::Simulation(e)
+    }
+}
+
+pub fn to_msgpack(val: &impl Serialize) -> Result<Vec<u8>, MsgPackError> {
+    // named (map) encoding: flattened and tagged fields need the keys
+    rmp_serde::to_vec_named(val).map_err(|e| MsgPackError::Encode(e.to_string()))
+}
+
+pub fn from_msgpack<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, MsgPackError> {
+    rmp_serde::from_slice(bytes).map_err(|e| MsgPackError::Decode(e.to_string()))
+}
+
+/// `simulate` with a MessagePack-encoded `SimRequest` in and `SimResponse` out.
+pub fn simulate_msgpack(bytes: &[u8]) -> Result<Vec<u8>, MsgPackError> {
+    let request: SimRequest = from_msgpack(bytes)?;
+    to_msgpack(&simulate(request)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Direction, OxidationLevel, SimResponse, World, WorldBuilder};
+
+    fn request(world: World) -> SimRequest {
+        SimRequest { ticks: 20, world, ..Default::default() }
+    }
+
+    fn lever_lamp() -> World {
+        WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, true)
+            .dust((1, 0, 0))
+            .lamp((2, 0, 0))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn simulates_through_msgpack() {
+        let world = lever_lamp();
+        assert_eq!(from_msgpack::<World>(&to_msgpack(&world).unwrap()).unwrap(), world);
+
+        let bytes = to_msgpack(&request(world.clone())).unwrap();
+        let res: SimResponse = from_msgpack(&simulate_msgpack(&bytes).unwrap()).unwrap();
+        let direct = simulate(request(world)).unwrap();
+        assert_eq!(format!("{:?}", res.diffs), format!("{:?}", direct.diffs));
+    }
+
+    #[test]
+    fn copper_bulb_oxidation_round_trips() {
+        let levels =
+            [OxidationLevel::Unoxidized, OxidationLevel::Exposed, OxidationLevel::Weathered, OxidationLevel::Oxidized];
+        let world = levels
+            .into_iter()
+            .zip(0..)
+            .fold(WorldBuilder::new(), |b, (level, x)| b.copper_bulb((x, 0, 0), level))
+            .build()
+            .unwrap();
+        assert_eq!(from_msgpack::<World>(&to_msgpack(&world).unwrap()).unwrap(), world);
+    }
+
+    #[test]
+    fn reports_decode_and_simulation_errors() {
+        assert!(matches!(simulate_msgpack(&[0xc1]), Err(MsgPackError::Decode(_))));
+
+        let mut world = lever_lamp();
+        world.blocks.push(world.blocks[0].clone());
+        let bytes = to_msgpack(&request(world)).unwrap();
+        assert!(matches!(simulate_msgpack(&bytes), Err(MsgPackError::Simulation(_))));
+    }
+}
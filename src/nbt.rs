@@ -0,0 +1,163 @@
+// src/nbt.rs
+
+// Structure block files (`.nbt`): the format Minecraft's structure blocks
+// save and load. Only built with the `nbt` feature.
+
+use crate::blockstate::{from_block_state, to_block_state, Properties};
+use crate::{PlacedBlock, Pos, World};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
+/// Data version written to exported files (Minecraft 1.21.1).
+const DATA_VERSION: i32 = 3955;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum NbtError {
+    Io(String),     // gzip stream could not be read or written
+    Format(String), // not a structure file, or malformed NBT
+}
+
+impl std::fmt::Display for NbtError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NbtError::Io(e) => write!(f, "NBT I/O error: {}", e),
+            NbtError::Format(e) => write!(f, "invalid structure NBT: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for NbtError {}
+
+#[derive(Serialize, Deserialize)]
+struct Structure {
+    #[serde(rename = "DataVersion")]
+    data_version: i32,
+    size: Vec<i32>,
+    #[serde(default)]
+    palette: Vec<PaletteEntry>,
+    #[serde(default)]
+    blocks: Vec<StructureBlock>,
+    #[serde(default)]
+    entities: Vec<fastnbt::Value>,
+}
+
+#[derive(PartialEq, Serialize, Deserialize)]
+struct PaletteEntry {
+    #[serde(rename = "Name")]
+    name: String,
+    #[serde(rename = "Properties", default, skip_serializing_if = "Properties::is_empty")]
+    properties: Properties,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StructureBlock {
+    pos: Vec<i32>,
+    state: i32,
+}
+
+/// Read a structure file (gzip-compressed or raw NBT). Blocks the simulator
+/// does not model, including air, are skipped.
+pub fn world_from_nbt(nbt_bytes: &[u8]) -> Result<World, NbtError> {
+    let mut raw = Vec::new();
+    let bytes = if nbt_bytes.starts_with(&[0x1f, 0x8b]) {
+        GzDecoder::new(nbt_bytes).read_to_end(&mut raw).map_err(|e| NbtError::Io(e.to_string()))?;
+        &raw[..]
+    } else {
+        nbt_bytes
+    };
+    let structure: Structure = fastnbt::from_bytes(bytes).map_err(|e| NbtError::Format(e.to_string()))?;
+
+    let mut blocks = Vec::new();
+    for b in &structure.blocks {
+        let [x, y, z] = b.pos[..] else {
+            return Err(NbtError::Format(format!("block position {:?} is not x, y, z", b.pos)));
+        };
+        let entry = usize::try_from(b.state)
+            .ok()
+            .and_then(|i| structure.palette.get(i))
+            .ok_or_else(|| NbtError::Format(format!("palette index {} out of range", b.state)))?;
+        if let Some(kind) = from_block_state(&entry.name, &entry.properties) {
+            blocks.push(PlacedBlock { pos: Pos { x, y, z }, kind });
+        }
+    }
+    Ok(World { blocks })
+}
+
+/// Write the world as a gzip-compressed structure file, moved so that its
+/// bounds start at the structure origin.
+pub fn world_to_nbt(world: &World) -> Result<Vec<u8>, NbtError> {
+    let world = world.normalize();
+    let size = world.bounds().map_or((0, 0, 0), |bb| bb.size());
+
+    let mut palette: Vec<PaletteEntry> = Vec::new();
+    let mut blocks = Vec::new();
+    for b in &world.blocks {
+        let (name, properties) = to_block_state(&b.kind);
+        let entry = PaletteEntry { name, properties };
+        let state = match palette.iter().position(|p| *p == entry) {
+            Some(i) => i,
+            None => {
+                palette.push(entry);
+                palette.len() - 1
+            }
+        };
+        blocks.push(StructureBlock { pos: vec![b.pos.x, b.pos.y, b.pos.z], state: state as i32 });
+    }
+    let structure = Structure {
+        data_version: DATA_VERSION,
+        size: vec![size.0 as i32, size.1 as i32, size.2 as i32],
+        palette,
+        blocks,
+        entities: vec![],
+    };
+
+    let nbt = fastnbt::to_bytes(&structure).map_err(|e| NbtError::Format(e.to_string()))?;
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&nbt).map_err(|e| NbtError::Io(e.to_string()))?;
+    encoder.finish().map_err(|e| NbtError::Io(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ComparatorMode, Direction, WorldBuilder};
+
+    #[test]
+    fn round_trips_through_structure_file() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::Down, true)
+            .dust((1, 0, 0))
+            .repeater((2, 0, 0), Direction::East, 2)
+            .comparator((3, 0, 0), Direction::East, ComparatorMode::Compare)
+            .lamp((4, 0, 0))
+            .build()
+            .unwrap();
+        let bytes = world_to_nbt(&world.translate(Pos { x: 10, y: 64, z: -3 })).unwrap();
+        assert_eq!(world_from_nbt(&bytes).unwrap(), world);
+    }
+
+    #[test]
+    fn skips_unknown_blocks_and_rejects_garbage() {
+        let structure = Structure {
+            data_version: DATA_VERSION,
+            size: vec![2, 1, 1],
+            palette: vec![
+                PaletteEntry { name: "minecraft:glass".into(), properties: Properties::new() },
+                PaletteEntry { name: "minecraft:redstone_block".into(), properties: Properties::new() },
+            ],
+            blocks: vec![
+                StructureBlock { pos: vec![0, 0, 0], state: 0 },
+                StructureBlock { pos: vec![1, 0, 0], state: 1 },
+            ],
+            entities: vec![],
+        };
+        let world = world_from_nbt(&fastnbt::to_bytes(&structure).unwrap()).unwrap();
+        assert_eq!(world.blocks.len(), 1);
+        assert_eq!(world.blocks[0].pos, Pos { x: 1, y: 0, z: 0 });
+
+        assert!(world_from_nbt(b"not nbt").is_err());
+    }
+}
@@ -1,22 +1,161 @@
-use crate::{simulate, Connectable, PlacedBlock, SimRequest};
-use pyo3::exceptions::PyValueError;
+use crate::analysis::{dead_blocks, sourceless_blocks, timing_diagram};
+use crate::{
+    simulate, simulate_many, simulate_until, simulate_while, BlockKind, BuildError, ComparatorMode, Connectable,
+    Direction, DirectionError, PlacedBlock, Pos, SimError, SimRequest, SimResponse, SpatialGrid, TickDiff, World,
+    WorldBuilder,
+};
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
-use pyo3::types::PyModule;
+use pyo3::types::{PyBytes, PyModule};
 use pyo3::{wrap_pyfunction, Bound}; // ← 追加
 use serde_json;
+use std::collections::{BTreeMap, HashMap};
 
 // ─── Rust ロジック ──────────────────────────────────
+// 入力の誤りは ValueError、シミュレーションできないワールドは RuntimeError
+fn to_py(e: SimError) -> PyErr {
+    match e {
+        SimError::DeserializationFailed(_) => PyValueError::new_err(e.to_string()),
+        _ => PyRuntimeError::new_err(e.to_string()),
+    }
+}
+
 fn simulate_impl(json_text: &str) -> PyResult<String> {
-    let req: SimRequest =
-        serde_json::from_str(json_text).map_err(|e| PyValueError::new_err(e.to_string()))?;
-    let resp = simulate(req);
+    let req: SimRequest = serde_json::from_str(json_text)
+        .map_err(|e| to_py(SimError::DeserializationFailed(e.to_string())))?;
+    let resp = simulate(req).map_err(to_py)?;
+    serde_json::to_string(&resp).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+// simulate_until_py / simulate_while_py に渡す条件。例:
+//   {"lamp_at": [5, 0, 0], "on": true}      ランプが点いている (消えている)
+//   {"power_at": [1, 0, 0], "at_least": 8}  電力レベルが 8 以上 (省略時 1)
+//   {"all": [...]} / {"any": [...]}          すべて / どれかを満たす
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum Predicate {
+    LampAt {
+        lamp_at: (i32, i32, i32),
+        on: bool,
+    },
+    PowerAt {
+        power_at: (i32, i32, i32),
+        #[serde(default = "one")]
+        at_least: u8,
+    },
+    All {
+        all: Vec<Predicate>,
+    },
+    Any {
+        any: Vec<Predicate>,
+    },
+}
+
+fn one() -> u8 {
+    1
+}
+
+impl Predicate {
+    fn holds(&self, world: &SpatialGrid<BlockKind>) -> bool {
+        match self {
+            Predicate::LampAt { lamp_at, on } => {
+                matches!(world.get(&Pos::from(*lamp_at)), Some(BlockKind::Lamp { on: lit }) if lit == on)
+            }
+            Predicate::PowerAt { power_at, at_least } => {
+                world.get(&Pos::from(*power_at)).map_or(0, crate::power_level) >= *at_least
+            }
+            Predicate::All { all } => all.iter().all(|p| p.holds(world)),
+            Predicate::Any { any } => any.iter().any(|p| p.holds(world)),
+        }
+    }
+}
+
+fn simulate_predicate_impl(json_text: &str, predicate_json: &str, until: bool) -> PyResult<String> {
+    let req: SimRequest = serde_json::from_str(json_text)
+        .map_err(|e| to_py(SimError::DeserializationFailed(e.to_string())))?;
+    let predicate: Predicate =
+        serde_json::from_str(predicate_json).map_err(|e| PyValueError::new_err(format!("invalid predicate: {e}")))?;
+    let holds = |world: &SpatialGrid<BlockKind>| predicate.holds(world);
+    let resp = if until { simulate_until(req, holds) } else { simulate_while(req, holds) };
+    let resp = resp.map_err(to_py)?;
     serde_json::to_string(&resp).map_err(|e| PyValueError::new_err(e.to_string()))
 }
 
+// 失敗したリクエストはその位置に {"error": "..."} を入れ、他の結果は返す
+fn simulate_many_impl(json_text: &str) -> PyResult<String> {
+    let reqs: Vec<SimRequest> = serde_json::from_str(json_text)
+        .map_err(|e| to_py(SimError::DeserializationFailed(e.to_string())))?;
+    let results: Vec<serde_json::Value> = simulate_many(reqs)
+        .into_iter()
+        .map(|r| match r {
+            Ok(resp) => serde_json::to_value(resp).unwrap_or_else(|e| serde_json::json!({ "error": e.to_string() })),
+            Err(e) => serde_json::json!({ "error": e.to_string() }),
+        })
+        .collect();
+    serde_json::to_string(&results).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+#[cfg(feature = "bincode")]
+fn simulate_bin_impl(bytes: &[u8]) -> PyResult<Vec<u8>> {
+    use crate::binary::{deserialize_request_bin, serialize_response_bin};
+    let req = deserialize_request_bin(bytes)
+        .map_err(|e| to_py(SimError::DeserializationFailed(e.to_string())))?;
+    let resp = simulate(req).map_err(to_py)?;
+    Ok(serialize_response_bin(&resp))
+}
+
+#[cfg(not(feature = "bincode"))]
+fn simulate_bin_impl(_bytes: &[u8]) -> PyResult<Vec<u8>> {
+    Err(PyValueError::new_err("redstonesim was built without the bincode feature"))
+}
+
 // ─── Python から直接呼ぶ関数 ─────────────────────────
+/// 入出力の形式。Json は str、Binary は bytes (bincode) をやり取りする
+#[pyclass(eq, eq_int)]
+#[derive(Clone, Copy, PartialEq)]
+enum SerializationFormat {
+    Json,
+    Binary,
+}
+
 #[pyfunction]
-fn simulate_py(json_text: &str) -> PyResult<String> {
-    simulate_impl(json_text)
+#[pyo3(signature = (request, fmt = SerializationFormat::Json))]
+fn simulate_py(py: Python<'_>, request: &Bound<'_, PyAny>, fmt: SerializationFormat) -> PyResult<PyObject> {
+    match fmt {
+        SerializationFormat::Json => Ok(simulate_impl(&request.extract::<String>()?)?.into_py(py)),
+        SerializationFormat::Binary => {
+            let out = simulate_bin_impl(&request.extract::<Vec<u8>>()?)?;
+            Ok(PyBytes::new_bound(py, &out).into_py(py))
+        }
+    }
+}
+
+// SimRequest の JSON 配列を受け取り、SimResponse の JSON 配列を同じ順で返す。
+// parallel フィーチャー付きなら複数コアで並列に実行する。実行中は GIL を手放すので
+// 他の Python スレッドは止まらない
+#[pyfunction]
+fn simulate_many_py(py: Python<'_>, json_list: &str) -> PyResult<String> {
+    py.allow_threads(|| simulate_many_impl(json_list))
+}
+
+// MessagePack の SimRequest を受け取り、SimResponse を MessagePack で返す
+#[cfg(feature = "msgpack")]
+#[pyfunction]
+fn simulate_msgpack_py<'py>(py: Python<'py>, data: &[u8]) -> PyResult<Bound<'py, PyBytes>> {
+    use crate::msgpack::{simulate_msgpack, MsgPackError};
+    let out = simulate_msgpack(data).map_err(|e| match e {
+        MsgPackError::Simulation(e) => to_py(e),
+        _ => PyValueError::new_err(e.to_string()),
+    })?;
+    Ok(PyBytes::new_bound(py, &out))
+}
+
+// CSV (x,y,z,type,facing,on_or_power,...) のワールドを World の JSON にして返す
+#[cfg(feature = "csv")]
+#[pyfunction]
+fn world_from_csv_py(s: &str) -> PyResult<String> {
+    let world = World::from_csv(s).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    serde_json::to_string(&world).map_err(|e| PyValueError::new_err(e.to_string()))
 }
 
 fn connections_impl(json_text: &str) -> PyResult<String> {
@@ -33,11 +172,434 @@ fn block_connections_py(json_text: &str) -> PyResult<String> {
     connections_impl(json_text)
 }
 
+fn validate_world_impl(json_text: &str) -> PyResult<String> {
+    let world: World = serde_json::from_str(json_text)
+        .map_err(|e| to_py(SimError::DeserializationFailed(e.to_string())))?;
+    world.validate().map_err(|e| to_py(SimError::from(e)))?;
+    serde_json::to_string(&world.validate_wiring()).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+// World の JSON を受け取り、配線の警告一覧を JSON 配列で返す
+#[pyfunction]
+fn validate_world_py(json_text: &str) -> PyResult<String> {
+    validate_world_impl(json_text)
+}
+
+fn world_positions_impl(json_text: &str, f: fn(&SpatialGrid<BlockKind>) -> Vec<Pos>) -> PyResult<String> {
+    let world: World = serde_json::from_str(json_text)
+        .map_err(|e| to_py(SimError::DeserializationFailed(e.to_string())))?;
+    world.validate().map_err(|e| to_py(SimError::from(e)))?;
+    serde_json::to_string(&f(&world.into_map())).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+// 条件を満たした tick で止める simulate_py。止まると terminated は "predicate_met"
+#[pyfunction]
+fn simulate_until_py(json_text: &str, predicate_json: &str) -> PyResult<String> {
+    simulate_predicate_impl(json_text, predicate_json, true)
+}
+
+// 条件を満たさなくなった tick で止める simulate_py
+#[pyfunction]
+fn simulate_while_py(json_text: &str, predicate_json: &str) -> PyResult<String> {
+    simulate_predicate_impl(json_text, predicate_json, false)
+}
+
+// どの電源からも信号が届かないブロックの座標を JSON 配列で返す
+#[pyfunction]
+fn dead_blocks_py(json_text: &str) -> PyResult<String> {
+    world_positions_impl(json_text, dead_blocks)
+}
+
+// 上のうち、ダストと不透過ブロックを除いた (決して動作しない) 部品の座標を返す
+#[pyfunction]
+fn sourceless_blocks_py(json_text: &str) -> PyResult<String> {
+    world_positions_impl(json_text, sourceless_blocks)
+}
+
+// SimResponse の JSON から、positions の各座標の 1..=ticks の波形を
+// テキストのタイミングチャートにして返す (`_` = 0, `1`–`F` = 電力, `#` = 点灯)
+#[pyfunction]
+fn timing_diagram_py(response_json: &str, positions: Vec<(i32, i32, i32)>, ticks: u32) -> PyResult<String> {
+    let resp: SimResponse = serde_json::from_str(response_json)
+        .map_err(|e| to_py(SimError::DeserializationFailed(e.to_string())))?;
+    let positions: Vec<Pos> = positions.into_iter().map(Pos::from).collect();
+    Ok(timing_diagram(&resp.diffs, &positions, ticks))
+}
+
+// asyncio から await できる simulate_py。計算は tokio のブロッキング用スレッドで
+// GIL なしに行うので、イベントループは止まらない
+#[cfg(feature = "python-async")]
+#[pyfunction]
+fn simulate_async_py(py: Python<'_>, json_text: String) -> PyResult<Bound<'_, PyAny>> {
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        let req: SimRequest = serde_json::from_str(&json_text)
+            .map_err(|e| to_py(SimError::DeserializationFailed(e.to_string())))?;
+        let resp = crate::simulate_async(req).await.map_err(to_py)?;
+        serde_json::to_string(&resp).map_err(|e| PyValueError::new_err(e.to_string()))
+    })
+}
+
+#[cfg(feature = "python-async")]
+type DiffStream = std::pin::Pin<Box<dyn tokio_stream::Stream<Item = Result<TickDiff, SimError>> + Send>>;
+
+/// simulate_streaming_py が返す非同期イテレーター。`async for` で TickDiff の JSON を 1 つずつ受け取る
+#[cfg(feature = "python-async")]
+#[pyclass]
+struct TickDiffStream {
+    stream: std::sync::Arc<tokio::sync::Mutex<DiffStream>>,
+}
+
+#[cfg(feature = "python-async")]
+#[pymethods]
+impl TickDiffStream {
+    fn __aiter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __anext__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        use tokio_stream::StreamExt;
+        let stream = self.stream.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let next = stream.lock().await.next().await;
+            match next {
+                Some(Ok(diff)) => serde_json::to_string(&diff).map_err(|e| PyValueError::new_err(e.to_string())),
+                Some(Err(e)) => Err(to_py(e)),
+                None => Err(pyo3::exceptions::PyStopAsyncIteration::new_err(())),
+            }
+        })
+    }
+}
+
+// 1 tick 終わるごとに空でない TickDiff の JSON を返す。壊れたワールドは途中で RuntimeError
+#[cfg(feature = "python-async")]
+#[pyfunction]
+fn simulate_streaming_py(json_text: &str) -> PyResult<TickDiffStream> {
+    let req: SimRequest = serde_json::from_str(json_text)
+        .map_err(|e| to_py(SimError::DeserializationFailed(e.to_string())))?;
+    // simulate_streaming はスレッドを起こしてすぐ返るので、ここで待っても止まらない
+    let stream = pyo3_async_runtimes::tokio::get_runtime().block_on(crate::simulate_streaming(req));
+    Ok(TickDiffStream { stream: std::sync::Arc::new(tokio::sync::Mutex::new(Box::pin(stream))) })
+}
+
+// World の JSON の y = y_level の断面を、各マスの電力レベル (0–15) の
+// uint8 配列にして返す。範囲は (start, stop) の半開区間で、配列の形は
+// (z の数, x の数)。plt.imshow にそのまま渡すと北が上になる
+#[cfg(feature = "numpy")]
+#[pyfunction]
+fn power_grid_py<'py>(
+    py: Python<'py>,
+    world_json: &str,
+    y_level: i32,
+    x_range: (i32, i32),
+    z_range: (i32, i32),
+) -> PyResult<Bound<'py, numpy::PyArray2<u8>>> {
+    use numpy::ndarray::Array2;
+    use numpy::IntoPyArray;
+    let world: World = serde_json::from_str(world_json)
+        .map_err(|e| to_py(SimError::DeserializationFailed(e.to_string())))?;
+    let grid = world.into_map();
+    let width = (x_range.1 - x_range.0).max(0) as usize;
+    let depth = (z_range.1 - z_range.0).max(0) as usize;
+    let levels = Array2::from_shape_fn((depth, width), |(row, col)| {
+        let pos = Pos { x: x_range.0 + col as i32, y: y_level, z: z_range.0 + row as i32 };
+        grid.get(&pos).map_or(0, crate::power_level)
+    });
+    Ok(levels.into_pyarray_bound(py))
+}
+
+// ─── JSON を書かずに使うためのクラス ─────────────────
+fn parse_facing(facing: &str) -> PyResult<Direction> {
+    facing.parse().map_err(|e: DirectionError| PyValueError::new_err(e.to_string()))
+}
+
+// serde_json で文字列にしてから json.loads で dict に戻す
+fn to_py_json<T: serde::Serialize>(py: Python<'_>, value: &T) -> PyResult<PyObject> {
+    let text = serde_json::to_string(value).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    Ok(py.import_bound("json")?.call_method1("loads", (text,))?.unbind())
+}
+
+/// ブロックを 1 つずつ置いて組み立てるワールド
+#[pyclass]
+#[derive(Clone)]
+struct PyWorld {
+    world: World,
+}
+
+impl PyWorld {
+    // WorldBuilder の 1 手で作ったブロックを足す。同じ座標に 2 つ目は置けない
+    fn place(&mut self, build: impl FnOnce(WorldBuilder) -> WorldBuilder) -> PyResult<()> {
+        let World { blocks } = build(WorldBuilder::new()).build().map_err(|e| PyValueError::new_err(e.to_string()))?;
+        for b in blocks {
+            if self.world.blocks.iter().any(|placed| placed.pos == b.pos) {
+                return Err(PyValueError::new_err(BuildError::DuplicatePosition(b.pos).to_string()));
+            }
+            self.world.blocks.push(b);
+        }
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl PyWorld {
+    #[new]
+    fn new() -> Self {
+        PyWorld { world: World { blocks: Vec::new() } }
+    }
+
+    #[pyo3(signature = (x, y, z, facing, on = false))]
+    fn add_lever(&mut self, x: i32, y: i32, z: i32, facing: &str, on: bool) -> PyResult<()> {
+        let facing = parse_facing(facing)?;
+        self.place(|b| b.lever((x, y, z), facing, on))
+    }
+
+    fn add_button(&mut self, x: i32, y: i32, z: i32, facing: &str) -> PyResult<()> {
+        let facing = parse_facing(facing)?;
+        self.place(|b| b.button((x, y, z), facing))
+    }
+
+    fn add_dust(&mut self, x: i32, y: i32, z: i32) -> PyResult<()> {
+        self.place(|b| b.dust((x, y, z)))
+    }
+
+    fn add_lamp(&mut self, x: i32, y: i32, z: i32) -> PyResult<()> {
+        self.place(|b| b.lamp((x, y, z)))
+    }
+
+    #[pyo3(signature = (x, y, z, facing, delay = 1))]
+    fn add_repeater(&mut self, x: i32, y: i32, z: i32, facing: &str, delay: u8) -> PyResult<()> {
+        let facing = parse_facing(facing)?;
+        self.place(|b| b.repeater((x, y, z), facing, delay))
+    }
+
+    #[pyo3(signature = (x, y, z, facing, subtract = false))]
+    fn add_comparator(&mut self, x: i32, y: i32, z: i32, facing: &str, subtract: bool) -> PyResult<()> {
+        let facing = parse_facing(facing)?;
+        let mode = if subtract { ComparatorMode::Subtract } else { ComparatorMode::Compare };
+        self.place(|b| b.comparator((x, y, z), facing, mode))
+    }
+
+    fn add_torch(&mut self, x: i32, y: i32, z: i32, facing: &str) -> PyResult<()> {
+        let facing = parse_facing(facing)?;
+        self.place(|b| b.torch((x, y, z), facing))
+    }
+
+    #[pyo3(signature = (x, y, z, facing, sticky = false))]
+    fn add_piston(&mut self, x: i32, y: i32, z: i32, facing: &str, sticky: bool) -> PyResult<()> {
+        let facing = parse_facing(facing)?;
+        self.place(|b| b.piston((x, y, z), facing, sticky))
+    }
+
+    fn add_observer(&mut self, x: i32, y: i32, z: i32, facing: &str) -> PyResult<()> {
+        let facing = parse_facing(facing)?;
+        self.place(|b| b.observer((x, y, z), facing))
+    }
+
+    fn add_solid(&mut self, x: i32, y: i32, z: i32) -> PyResult<()> {
+        self.place(|b| b.solid((x, y, z)))
+    }
+
+    fn add_redstone_block(&mut self, x: i32, y: i32, z: i32) -> PyResult<()> {
+        self.place(|b| b.redstone_block((x, y, z)))
+    }
+
+    // 上にない種類は JSON (例: '{"type": "target", "power": 0}') で置く
+    fn add_block(&mut self, x: i32, y: i32, z: i32, kind_json: &str) -> PyResult<()> {
+        let kind: BlockKind = serde_json::from_str(kind_json).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        self.place(|b| b.block((x, y, z), kind))
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        serde_json::to_string(&self.world).map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    #[staticmethod]
+    fn from_json(s: &str) -> PyResult<PyWorld> {
+        let world: World = serde_json::from_str(s)
+            .map_err(|e| to_py(SimError::DeserializationFailed(e.to_string())))?;
+        Ok(PyWorld { world })
+    }
+
+    // 種類名 ("dust" など) → 個数の dict
+    fn count_by_kind(&self) -> HashMap<&'static str, usize> {
+        self.world.count_by_kind()
+    }
+
+    fn total_blocks(&self) -> usize {
+        self.world.total_blocks()
+    }
+
+    fn has_block_at(&self, x: i32, y: i32, z: i32) -> bool {
+        self.world.has_block_at(Pos { x, y, z })
+    }
+
+    // 置いた順の (x, y, z) のリスト
+    fn blocks_of_kind(&self, kind_name: &str) -> Vec<(i32, i32, i32)> {
+        self.world.blocks_of_kind(kind_name).into_iter().map(|p| (p.x, p.y, p.z)).collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.world.total_blocks()
+    }
+
+    // 例: PyWorld(3 blocks: 1 dust, 1 lamp, 1 lever)
+    fn __repr__(&self) -> String {
+        let counts: BTreeMap<&str, usize> = self.world.count_by_kind().into_iter().collect();
+        let kinds: Vec<String> = counts.iter().map(|(name, n)| format!("{n} {name}")).collect();
+        match self.world.blocks.len() {
+            0 => "PyWorld(empty)".to_string(),
+            1 => format!("PyWorld(1 block: {})", kinds.join(", ")),
+            n => format!("PyWorld({n} blocks: {})", kinds.join(", ")),
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Clone)]
+struct PySimRequest {
+    req: SimRequest,
+}
+
+#[pymethods]
+impl PySimRequest {
+    #[new]
+    #[pyo3(signature = (world, ticks, early_exit = true))]
+    fn new(world: &PyWorld, ticks: u32, early_exit: bool) -> Self {
+        let req = SimRequest { ticks, world: world.world.clone(), early_exit, ..Default::default() };
+        PySimRequest { req }
+    }
+
+    #[getter]
+    fn ticks(&self) -> u32 {
+        self.req.ticks
+    }
+
+    #[getter]
+    fn early_exit(&self) -> bool {
+        self.req.early_exit
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        serde_json::to_string(&self.req).map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    fn simulate(&self) -> PyResult<PySimResponse> {
+        let resp = simulate(self.req.clone()).map_err(to_py)?;
+        Ok(PySimResponse { resp })
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "PySimRequest({} blocks, ticks={}, early_exit={})",
+            self.req.world.blocks.len(),
+            self.req.ticks,
+            if self.req.early_exit { "True" } else { "False" },
+        )
+    }
+}
+
+#[pyclass]
+struct PySimResponse {
+    resp: SimResponse,
+}
+
+#[pymethods]
+impl PySimResponse {
+    #[getter]
+    fn diffs(&self) -> Vec<PyTickDiff> {
+        self.resp.diffs.iter().map(|d| PyTickDiff { diff: d.clone() }).collect()
+    }
+
+    // "stable"、"max_ticks_reached"、"predicate_met" のどれか
+    #[getter]
+    fn terminated(&self) -> PyResult<String> {
+        let json = serde_json::to_value(&self.resp.terminated).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(json.as_str().unwrap_or_default().to_string())
+    }
+
+    #[getter]
+    fn oscillation_period(&self) -> Option<u32> {
+        self.resp.oscillation_period
+    }
+
+    #[getter]
+    fn seed_used(&self) -> u64 {
+        self.resp.seed_used
+    }
+
+    fn to_json(&self) -> PyResult<String> {
+        serde_json::to_string(&self.resp).map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    fn __len__(&self) -> usize {
+        self.resp.diffs.len()
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(format!("PySimResponse({} diffs, terminated={:?})", self.resp.diffs.len(), self.terminated()?))
+    }
+}
+
+#[pyclass]
+struct PyTickDiff {
+    diff: TickDiff,
+}
+
+#[pymethods]
+impl PyTickDiff {
+    #[getter]
+    fn tick(&self) -> u32 {
+        self.diff.tick
+    }
+
+    // 変化したブロックを JSON と同じ形の dict で返す
+    #[getter]
+    fn changes(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_py_json(py, &self.diff.changes)
+    }
+
+    #[getter]
+    fn removed(&self) -> Vec<(i32, i32, i32)> {
+        self.diff.removed.iter().map(|p| (p.x, p.y, p.z)).collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.diff.changes.len() + self.diff.removed.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("PyTickDiff(tick={}, {} changes)", self.diff.tick, self.diff.changes.len())
+    }
+}
+
 // ─── モジュール初期化関数 ────────────────────────────
 //            ↓↓↓ ここを &Bound<'_, PyModule> に変更
 #[pymodule]
 fn redstonesim(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(simulate_py, m)?);
     m.add_function(wrap_pyfunction!(block_connections_py, m)?);
+    m.add_function(wrap_pyfunction!(simulate_many_py, m)?)?;
+    m.add_function(wrap_pyfunction!(validate_world_py, m)?)?;
+    m.add_function(wrap_pyfunction!(dead_blocks_py, m)?)?;
+    m.add_function(wrap_pyfunction!(sourceless_blocks_py, m)?)?;
+    m.add_function(wrap_pyfunction!(timing_diagram_py, m)?)?;
+    m.add_function(wrap_pyfunction!(simulate_until_py, m)?)?;
+    m.add_function(wrap_pyfunction!(simulate_while_py, m)?)?;
+    m.add_class::<SerializationFormat>()?;
+    m.add_class::<PyWorld>()?;
+    m.add_class::<PySimRequest>()?;
+    m.add_class::<PySimResponse>()?;
+    m.add_class::<PyTickDiff>()?;
+    #[cfg(feature = "msgpack")]
+    m.add_function(wrap_pyfunction!(simulate_msgpack_py, m)?)?;
+    #[cfg(feature = "csv")]
+    m.add_function(wrap_pyfunction!(world_from_csv_py, m)?)?;
+    #[cfg(feature = "numpy")]
+    m.add_function(wrap_pyfunction!(power_grid_py, m)?)?;
+    #[cfg(feature = "python-async")]
+    {
+        m.add_function(wrap_pyfunction!(simulate_async_py, m)?)?;
+        m.add_function(wrap_pyfunction!(simulate_streaming_py, m)?)?;
+        m.add_class::<TickDiffStream>()?;
+    }
     Ok(())
 }
@@ -0,0 +1,257 @@
+// src/recording.rs
+
+// Full world history of a run, for debuggers that scrub back and forth
+// through it. `SimResponse::state_at_tick` replays diffs on every call; a
+// recording keeps the world at the end of every tick, so looking one up is
+// an index. On the wire (serde, `to_compressed`) only the initial world and
+// the diffs are stored, and the states are rebuilt when it is read back.
+
+use crate::{finish, BlockKind, Pos, SimError, SimRequest, SimResponse, SpatialGrid, TickDiff, World};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+/// The world at the end of every tick of one run, from tick 0 (the world
+/// before the first tick) to the last tick simulated.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(into = "RecordedDeltas", try_from = "RecordedDeltas")]
+pub struct SimRecording {
+    states: Vec<SpatialGrid<BlockKind>>,
+    diffs: Vec<TickDiff>, // non-empty ones only, as in `SimResponse::diffs`
+}
+
+// what a recording is stored as: tick 0 in full, then each tick as a delta
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+struct RecordedDeltas {
+    initial: World, // sorted by position, so equal recordings encode alike
+    ticks: u32,
+    diffs: Vec<TickDiff>,
+}
+
+impl From<SimRecording> for RecordedDeltas {
+    fn from(rec: SimRecording) -> Self {
+        let mut initial: Vec<(Pos, BlockKind)> = rec.states[0].iter().map(|(p, b)| (*p, b.clone())).collect();
+        initial.sort_by_key(|(p, _)| *p);
+        RecordedDeltas { initial: initial.into_iter().collect(), ticks: rec.ticks(), diffs: rec.diffs }
+    }
+}
+
+// most block states rebuilding a recording may produce (ticks × world
+// size), so a few bytes claiming billions of ticks cannot exhaust memory;
+// far above anything `record_simulation` keeps in memory itself
+const MAX_REBUILT_STATES: u64 = 1 << 24;
+
+impl TryFrom<RecordedDeltas> for SimRecording {
+    type Error = RecordingError;
+
+    fn try_from(deltas: RecordedDeltas) -> Result<Self, RecordingError> {
+        let mut last = 0;
+        for d in &deltas.diffs {
+            if d.tick <= last || d.tick > deltas.ticks {
+                let msg = format!("diff at tick {} is out of order or past tick {}", d.tick, deltas.ticks);
+                return Err(RecordingError(msg));
+            }
+            last = d.tick;
+        }
+        let world_size = deltas.initial.blocks.len().max(1) as u64;
+        if (u64::from(deltas.ticks) + 1) * world_size > MAX_REBUILT_STATES {
+            let msg = format!("{} ticks of {} blocks is too large to rebuild", deltas.ticks, world_size);
+            return Err(RecordingError(msg));
+        }
+        let mut states = Vec::new();
+        states.push(deltas.initial.into_map());
+        let mut diffs = deltas.diffs.iter().peekable();
+        for tick in 1..=deltas.ticks {
+            let mut world = states[states.len() - 1].clone();
+            while let Some(diff) = diffs.next_if(|d| d.tick <= tick) {
+                for c in &diff.changes {
+                    world.insert(c.pos, c.kind.clone());
+                }
+                for p in &diff.removed {
+                    world.remove(p);
+                }
+            }
+            states.push(world);
+        }
+        Ok(SimRecording { states, diffs: deltas.diffs })
+    }
+}
+
+impl SimRecording {
+    /// Last tick recorded; the run stopped there (it settled, hit its limit
+    /// or failed).
+    pub fn ticks(&self) -> u32 {
+        (self.states.len() - 1) as u32
+    }
+
+    /// Rough heap use of the kept states, in bytes: every map slot holds a
+    /// position and a block. The diffs are not counted.
+    pub fn memory_estimate(&self) -> usize {
+        let slot = core::mem::size_of::<(Pos, BlockKind)>();
+        self.states.iter().map(|s| s.capacity() * slot).sum()
+    }
+}
+
+/// Simulate `request` like `simulate`, also keeping the world after every
+/// tick. An invalid world fails the same way it does there.
+pub fn record_simulation(request: SimRequest) -> Result<(SimResponse, SimRecording), SimError> {
+    let mut sim = request.into_iter();
+    let mut states = Vec::new();
+    states.push(sim.world().clone());
+    let mut diffs = Vec::new();
+    while let Some(diff) = sim.next() {
+        states.push(sim.world().clone());
+        if !diff.changes.is_empty() || !diff.removed.is_empty() {
+            diffs.push(diff);
+        }
+    }
+    let recording = SimRecording { states, diffs: diffs.clone() };
+    Ok((finish(sim, diffs)?, recording))
+}
+
+/// The world at the end of `tick` (0 = before the first tick). Past the
+/// last recorded tick this is the final state.
+pub fn replay_simulation(recording: &SimRecording, tick: u32) -> &SpatialGrid<BlockKind> {
+    &recording.states[(tick as usize).min(recording.states.len() - 1)]
+}
+
+/// Byte formats for [`SimRecording::to_compressed`]. Each has the feature of
+/// the same name.
+#[cfg(any(feature = "bincode", feature = "msgpack"))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CompressionEncoding {
+    #[cfg(feature = "bincode")]
+    Bincode,
+    #[cfg(feature = "msgpack")]
+    MsgPack,
+}
+
+/// Error from reading back a recording, e.g. [`SimRecording::to_compressed`]
+/// output: undecodable bytes, or diffs that do not fit its tick count.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RecordingError(String);
+
+impl core::fmt::Display for RecordingError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid recording: {}", self.0)
+    }
+}
+
+impl core::error::Error for RecordingError {}
+
+#[cfg(any(feature = "bincode", feature = "msgpack"))]
+impl SimRecording {
+    /// The initial world and the diffs of each tick, in `encoding`; far
+    /// smaller than the states themselves.
+    pub fn to_compressed(&self, encoding: CompressionEncoding) -> Vec<u8> {
+        let deltas = RecordedDeltas::from(self.clone());
+        match encoding {
+            #[cfg(feature = "bincode")]
+            CompressionEncoding::Bincode => bincode::encode_to_vec(&deltas, bincode::config::standard())
+                .expect("recordings always encode with bincode"),
+            #[cfg(feature = "msgpack")]
+            CompressionEncoding::MsgPack => {
+                rmp_serde::to_vec_named(&deltas).expect("recordings always encode as MessagePack")
+            }
+        }
+    }
+
+    /// Read back what [`to_compressed`](Self::to_compressed) wrote with the
+    /// same `encoding`.
+    pub fn from_compressed(bytes: &[u8], encoding: CompressionEncoding) -> Result<Self, RecordingError> {
+        let deltas: RecordedDeltas = match encoding {
+            #[cfg(feature = "bincode")]
+            CompressionEncoding::Bincode => {
+                bincode::decode_from_slice(bytes, bincode::config::standard())
+                    .map_err(|e| RecordingError(e.to_string()))?
+                    .0
+            }
+            #[cfg(feature = "msgpack")]
+            CompressionEncoding::MsgPack => rmp_serde::from_slice(bytes).map_err(|e| RecordingError(e.to_string()))?,
+        };
+        deltas.try_into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{circuits, simulate, Direction, Termination, TimedEvent, WorldBuilder};
+
+    #[test]
+    fn every_tick_matches_the_diffs() {
+        let world = WorldBuilder::new()
+            .lever((0, 0, 0), Direction::East, false)
+            .dust((1, 0, 0))
+            .repeater((2, 0, 0), Direction::East, 3)
+            .lamp((3, 0, 0))
+            .build()
+            .unwrap();
+        let req = SimRequest {
+            ticks: 30,
+            world: world.clone(),
+            events: vec![(2, TimedEvent::LeverToggle(Pos::default()))],
+            ..Default::default()
+        };
+        let (resp, rec) = record_simulation(req.clone()).unwrap();
+        assert_eq!(resp, simulate(req).unwrap());
+        assert_eq!(resp.terminated, Termination::Stable);
+        for tick in 0..=rec.ticks() + 5 {
+            assert_eq!(*replay_simulation(&rec, tick), resp.state_at_tick(tick, &world), "tick {}", tick);
+        }
+
+        // rebuilt from the deltas exactly as recorded
+        let deltas = RecordedDeltas::from(rec.clone());
+        assert_eq!(deltas.initial.blocks.len(), 4);
+        assert_eq!(SimRecording::try_from(deltas).unwrap(), rec);
+    }
+
+    #[test]
+    fn deltas_must_fit_their_tick_count() {
+        let world = WorldBuilder::new().lever((0, 0, 0), Direction::East, false).lamp((1, 0, 0)).build().unwrap();
+        let req = SimRequest { ticks: 10, world, events: vec![(2, TimedEvent::LeverToggle(Pos::default()))], ..Default::default() };
+        let (_, rec) = record_simulation(req).unwrap();
+
+        let mut deltas = RecordedDeltas::from(rec.clone());
+        deltas.ticks = u32::MAX;
+        assert!(SimRecording::try_from(deltas).is_err());
+        let mut deltas = RecordedDeltas::from(rec.clone());
+        deltas.ticks = 1; // the lever flips at tick 2
+        assert!(SimRecording::try_from(deltas).is_err());
+        let mut deltas = RecordedDeltas::from(rec);
+        deltas.diffs.push(deltas.diffs[0].clone());
+        assert!(SimRecording::try_from(deltas).is_err());
+    }
+
+    #[test]
+    fn small_world_fits_in_a_megabyte() {
+        // a clock keeps changing, so every one of the 100 ticks is recorded
+        let mut builder = WorldBuilder::new();
+        for x in 0..91 {
+            builder = builder.lamp((x, 5, 0));
+        }
+        let world = builder.build().unwrap().merge(&circuits::clock(Pos::default(), 4).unwrap(), Pos::default()).unwrap();
+        assert!(world.blocks.len() >= 100, "{}", world.blocks.len());
+        let (_, rec) = record_simulation(SimRequest { ticks: 100, world, early_exit: false, ..Default::default() }).unwrap();
+        assert_eq!(rec.ticks(), 100);
+        assert!(rec.memory_estimate() < 1 << 20, "{} bytes", rec.memory_estimate());
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn compressed_recording_round_trips() {
+        let clock = circuits::clock(Pos::default(), 4).unwrap();
+        let (_, rec) = record_simulation(SimRequest { ticks: 40, world: clock, early_exit: false, ..Default::default() }).unwrap();
+        let bytes = rec.to_compressed(CompressionEncoding::MsgPack);
+        assert!(bytes.len() < rec.memory_estimate());
+        assert_eq!(SimRecording::from_compressed(&bytes, CompressionEncoding::MsgPack).unwrap(), rec);
+        assert!(SimRecording::from_compressed(&bytes[..bytes.len() / 2], CompressionEncoding::MsgPack).is_err());
+
+        // a few bytes claiming u32::MAX ticks are refused, not rebuilt
+        let huge = RecordedDeltas { initial: World::default(), ticks: u32::MAX, diffs: Vec::new() };
+        let bytes = rmp_serde::to_vec_named(&huge).unwrap();
+        assert!(SimRecording::from_compressed(&bytes, CompressionEncoding::MsgPack).is_err());
+    }
+}
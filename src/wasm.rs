@@ -0,0 +1,40 @@
+// src/wasm.rs
+
+// WebAssembly bindings for browser-based editors, built with
+// `wasm-pack build --target web -- --no-default-features --features wasm`
+// (see the Makefile). Only built with the `wasm` feature. JSON goes in and
+// out exactly as with the Python functions; failures come back as
+// `{"error": "..."}` so callers always get JSON.
+
+use crate::{simulate, Connectable, PlacedBlock, SimError, SimRequest};
+use wasm_bindgen::prelude::*;
+
+fn error_json(message: String) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
+
+/// Run a `SimRequest` given as JSON and return the `SimResponse` as JSON.
+#[wasm_bindgen]
+pub fn simulate_json(request_json: &str) -> String {
+    let result = serde_json::from_str::<SimRequest>(request_json)
+        .map_err(|e| SimError::DeserializationFailed(e.to_string()))
+        .and_then(simulate);
+    match result {
+        Ok(resp) => serde_json::to_string(&resp).unwrap_or_else(|e| error_json(e.to_string())),
+        Err(e) => error_json(e.to_string()),
+    }
+}
+
+/// Input and output positions of a `PlacedBlock` given as JSON, as
+/// `{"inputs": [...], "outputs": [...]}`.
+#[wasm_bindgen]
+pub fn block_connections_json(block_json: &str) -> String {
+    match serde_json::from_str::<PlacedBlock>(block_json) {
+        Ok(block) => serde_json::json!({
+            "inputs": block.kind.input_positions(block.pos),
+            "outputs": block.kind.output_positions(block.pos),
+        })
+        .to_string(),
+        Err(e) => error_json(e.to_string()),
+    }
+}
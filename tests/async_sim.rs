@@ -0,0 +1,39 @@
+// tests/async_sim.rs
+
+// simulate_async and simulate_streaming against the blocking simulate.
+// Run with `cargo test --features async`.
+
+#![cfg(feature = "async")]
+
+use redstonesim::{
+    simulate, simulate_async, simulate_streaming, BlockKind, Direction, SimRequest, WorldBuilder,
+};
+use tokio_stream::StreamExt;
+
+fn request(ticks: u32) -> SimRequest {
+    let world = WorldBuilder::new()
+        .lever((0, 0, 0), Direction::East, true)
+        .dust((1, 0, 0))
+        .repeater((2, 0, 0), Direction::East, 3)
+        .lamp((3, 0, 0))
+        .build()
+        .unwrap();
+    SimRequest { ticks, world, ..Default::default() }
+}
+
+#[tokio::test]
+async fn async_matches_blocking() {
+    let expected = simulate(request(20)).unwrap();
+    let got = simulate_async(request(20)).await.unwrap();
+    assert_eq!(format!("{:?}", got), format!("{:?}", expected));
+}
+
+#[tokio::test]
+async fn streams_diffs_in_tick_order() {
+    let expected = simulate(request(20)).unwrap().diffs;
+    let stream = simulate_streaming(request(20)).await;
+    let diffs: Vec<_> = stream.map(Result::unwrap).collect().await;
+    assert_eq!(format!("{:?}", diffs), format!("{:?}", expected));
+    assert!(diffs.windows(2).all(|w| w[0].tick < w[1].tick));
+    assert!(diffs.iter().any(|d| d.changed_kinds().any(|k| *k == BlockKind::Lamp { on: true })));
+}
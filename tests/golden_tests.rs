@@ -0,0 +1,69 @@
+// tests/golden_tests.rs
+
+// Golden-file regression tests: every tests/golden/<name>.request.json is
+// simulated and the response compared against <name>.response.json.
+// After an intended behaviour change, regenerate the files with
+// `UPDATE_GOLDEN=1 cargo test --test golden_tests` and review the diff.
+
+#![cfg(feature = "std")]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use redstonesim::{simulate, SimRequest};
+use serde_json::Value;
+
+fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden")
+}
+
+fn requests() -> Vec<(String, PathBuf)> {
+    let mut out: Vec<(String, PathBuf)> = fs::read_dir(golden_dir())
+        .expect("tests/golden is missing")
+        .map(|entry| entry.unwrap().path())
+        .filter_map(|path| {
+            let name = path.file_name()?.to_str()?.strip_suffix(".request.json")?.to_owned();
+            Some((name, path))
+        })
+        .collect();
+    out.sort();
+    out
+}
+
+#[test]
+fn golden_responses_match() {
+    let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+    let cases = requests();
+    assert!(!cases.is_empty(), "no *.request.json files in tests/golden");
+
+    let mut mismatched = Vec::new();
+    for (name, path) in &cases {
+        let text = fs::read_to_string(path).unwrap();
+        let req: SimRequest =
+            serde_json::from_str(&text).unwrap_or_else(|e| panic!("{name}.request.json: {e}"));
+        let actual = serde_json::to_value(simulate(req).unwrap_or_else(|e| panic!("{name}: {e}"))).unwrap();
+
+        let expected_path = golden_dir().join(format!("{name}.response.json"));
+        if update {
+            fs::write(&expected_path, serde_json::to_string_pretty(&actual).unwrap() + "\n").unwrap();
+            continue;
+        }
+        // compared as Values so key order and whitespace in the file don't matter
+        let expected: Value = match fs::read_to_string(&expected_path) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| panic!("{name}.response.json: {e}")),
+            Err(_) => {
+                mismatched.push(format!("{name} (no response file)"));
+                continue;
+            }
+        };
+        if actual != expected {
+            mismatched.push(name.clone());
+        }
+    }
+
+    assert!(
+        mismatched.is_empty(),
+        "golden responses differ: {}\nrerun with UPDATE_GOLDEN=1 if the change is intended",
+        mismatched.join(", ")
+    );
+}
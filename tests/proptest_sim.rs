@@ -0,0 +1,99 @@
+// tests/proptest_sim.rs
+
+// Property tests over random worlds: simulate is deterministic and
+// early_exit only ever shortens a run.
+// Run with `cargo test --features proptest`.
+
+#![cfg(feature = "proptest")]
+
+use proptest::prelude::*;
+use redstonesim::{
+    simulate, simulate_many, BlockKind, PlacedBlock, Pos, SimRequest, TimedEvent, UpdateOrder, World,
+};
+
+// blocks packed into a small cube so most of them end up neighbours
+fn near() -> impl Strategy<Value = Pos> {
+    (-4i32..4, -2i32..2, -4i32..4).prop_map(Pos::from)
+}
+
+fn world() -> impl Strategy<Value = World> {
+    prop::collection::vec((near(), any::<BlockKind>()), 0..24)
+        .prop_map(|blocks| World { blocks: blocks.into_iter().map(|(pos, kind)| PlacedBlock { pos, kind }).collect() })
+}
+
+fn event() -> impl Strategy<Value = (u32, TimedEvent)> {
+    // mostly inside the cube; any::<Pos>() is almost always empty space
+    let pos = prop_oneof![4 => near(), 1 => any::<Pos>()];
+    let event = prop_oneof![
+        pos.clone().prop_map(TimedEvent::LeverToggle),
+        pos.clone().prop_map(TimedEvent::ButtonPress),
+        (pos, any::<BlockKind>()).prop_map(|(p, kind)| TimedEvent::BlockReplace(p, kind)),
+    ];
+    (0u32..40, event)
+}
+
+fn request() -> impl Strategy<Value = SimRequest> {
+    let order = prop_oneof![
+        Just(UpdateOrder::Arbitrary),
+        Just(UpdateOrder::MojangJava),
+        Just(UpdateOrder::Deterministic),
+        Just(UpdateOrder::Random),
+    ];
+    // always seeded: a drawn seed would make repeated runs differ
+    let seed = any::<u64>().prop_map(Some);
+    (0u32..60, world(), any::<bool>(), prop::collection::vec(event(), 0..4), any::<bool>(), order, seed).prop_map(
+        |(ticks, world, early_exit, events, quasi_connectivity, update_order, seed)| SimRequest {
+            ticks,
+            world,
+            early_exit,
+            events,
+            quasi_connectivity,
+            update_order,
+            seed,
+            ..Default::default()
+        },
+    )
+}
+
+// The value tree of the derived `any::<BlockKind>()` nests one level per
+// variant and outgrows the default 2 MiB test thread in debug builds, so
+// each property runs on a thread of its own.
+fn on_big_stack(test: fn()) {
+    std::thread::Builder::new().stack_size(16 << 20).spawn(test).unwrap().join().unwrap();
+}
+
+fn config() -> ProptestConfig {
+    ProptestConfig::with_cases(128)
+}
+
+#[test]
+fn simulate_is_deterministic() {
+    on_big_stack(|| {
+        proptest!(config(), |(req in request())| {
+            prop_assert_eq!(simulate(req.clone()), simulate(req));
+        })
+    });
+}
+
+#[test]
+fn simulate_many_matches_for_identical_requests() {
+    on_big_stack(|| {
+        proptest!(config(), |(req in request())| {
+            let results = simulate_many(vec![req.clone(), req]);
+            prop_assert_eq!(&results[0], &results[1]);
+        })
+    });
+}
+
+#[test]
+fn early_exit_never_adds_diffs() {
+    on_big_stack(|| {
+        proptest!(config(), |(req in request())| {
+            let full = simulate(SimRequest { early_exit: false, ..req.clone() });
+            let early = simulate(SimRequest { early_exit: true, ..req });
+            if let (Ok(full), Ok(early)) = (full, early) {
+                prop_assert!(early.diffs.len() <= full.diffs.len());
+            }
+        })
+    });
+}
@@ -0,0 +1,51 @@
+# tests/python/test_async.py
+#
+# simulate_async_py / simulate_streaming_py のテスト。python-async フィーチャー付きで
+# ビルドしたモジュールが必要 (maturin develop --features python-async)。
+
+import asyncio
+import json
+
+import pytest
+
+import redstonesim
+
+if not hasattr(redstonesim, "simulate_async_py"):
+    pytest.skip("built without the python-async feature", allow_module_level=True)
+
+REQUEST = json.dumps({
+    "ticks": 20,
+    "world": {"blocks": [
+        {"x": 0, "y": 0, "z": 0, "type": "lever", "on": True, "facing": "east"},
+        {"x": 1, "y": 0, "z": 0, "type": "repeater", "delay": 2, "ticks_remaining": 0,
+         "powered": False, "facing": "east"},
+        {"x": 2, "y": 0, "z": 0, "type": "lamp", "on": False},
+    ]},
+})
+
+
+def test_coroutine_resolves_to_the_same_json_as_simulate_py():
+    resp = asyncio.run(redstonesim.simulate_async_py(REQUEST))
+    assert json.loads(resp) == json.loads(redstonesim.simulate_py(REQUEST))
+
+
+def test_requests_run_concurrently_on_one_loop():
+    async def main():
+        return await asyncio.gather(*(redstonesim.simulate_async_py(REQUEST) for _ in range(10)))
+
+    results = asyncio.run(main())
+    assert len(results) == 10
+    assert all(json.loads(r)["terminated"] == "stable" for r in results)
+
+
+def test_stream_yields_the_diffs_simulate_py_collects():
+    async def collect():
+        return [json.loads(d) async for d in redstonesim.simulate_streaming_py(REQUEST)]
+
+    diffs = asyncio.run(collect())
+    assert diffs == json.loads(redstonesim.simulate_py(REQUEST))["diffs"]
+
+
+def test_invalid_json_raises_value_error():
+    with pytest.raises(ValueError):
+        asyncio.run(redstonesim.simulate_async_py("{"))
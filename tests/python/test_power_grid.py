@@ -0,0 +1,36 @@
+# tests/python/test_power_grid.py
+#
+# power_grid_py のテスト。numpy フィーチャー付きでビルドしたモジュールが必要
+# (maturin develop --features numpy)。
+
+import json
+
+import numpy as np
+import pytest
+
+import redstonesim
+
+if not hasattr(redstonesim, "power_grid_py"):
+    pytest.skip("built without the numpy feature", allow_module_level=True)
+
+
+def test_shape_and_levels_of_a_dust_line():
+    # シミュレーション後の状態: レバー → 15, 14, 13 のダスト、z = 1 にランプ
+    blocks = [
+        {"x": 0, "y": 0, "z": 0, "type": "lever", "on": True, "facing": "east"},
+        {"x": 1, "y": 0, "z": 0, "type": "dust", "power": 15},
+        {"x": 2, "y": 0, "z": 0, "type": "dust", "power": 14},
+        {"x": 3, "y": 0, "z": 0, "type": "dust", "power": 13},
+        {"x": 1, "y": 0, "z": 1, "type": "lamp", "on": True},
+        {"x": 2, "y": 1, "z": 0, "type": "redstoneblock"},
+    ]
+    grid = redstonesim.power_grid_py(json.dumps({"blocks": blocks}), 0, (0, 5), (0, 2))
+
+    assert grid.dtype == np.uint8
+    assert grid.shape == (2, 5)  # (z, x)
+    np.testing.assert_array_equal(grid, [[15, 15, 14, 13, 0], [0, 0, 0, 0, 0]])
+
+
+def test_empty_range_gives_an_empty_array():
+    grid = redstonesim.power_grid_py('{"blocks": []}', 0, (3, 3), (0, 4))
+    assert grid.shape == (4, 0)
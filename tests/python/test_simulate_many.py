@@ -0,0 +1,50 @@
+# tests/python/test_simulate_many.py
+#
+# simulate_many_py のテスト。maturin develop でモジュールを入れてから
+# `python -m pytest tests/python` で実行する (cargo test では動かない)。
+
+import json
+
+import redstonesim
+
+
+def chain_request(length, ticks=20):
+    # レバー → ダスト (length 個) → ランプ
+    blocks = [{"x": 0, "y": 0, "z": 0, "type": "lever", "on": True, "facing": "east"}]
+    blocks += [{"x": x, "y": 0, "z": 0, "type": "dust", "power": 0} for x in range(1, length + 1)]
+    blocks.append({"x": length + 1, "y": 0, "z": 0, "type": "lamp", "on": False})
+    return {"ticks": ticks, "world": {"blocks": blocks}, "early_exit": True}
+
+
+def test_fifty_requests_give_fifty_responses_in_order():
+    requests = [chain_request(n % 20 + 1) for n in range(50)]
+    responses = json.loads(redstonesim.simulate_many_py(json.dumps(requests)))
+
+    assert len(responses) == 50
+    for req, resp in zip(requests, responses):
+        assert "error" not in resp
+        assert set(resp) >= {"diffs", "terminated", "notes", "launch_events", "oscillation_period"}
+        assert resp["terminated"] == "stable"
+        # ダストが 15 を超えて届かないので、17 個目以降のランプは点かない
+        length = len(req["world"]["blocks"]) - 2
+        lamp_on = any(
+            c["type"] == "lamp" and c["on"] for d in resp["diffs"] for c in d["changes"]
+        )
+        assert lamp_on == (length <= 15)
+
+
+def test_matches_simulate_py_one_by_one():
+    requests = [chain_request(n + 1) for n in range(5)]
+    batch = json.loads(redstonesim.simulate_many_py(json.dumps(requests)))
+    single = [json.loads(redstonesim.simulate_py(json.dumps(r))) for r in requests]
+    assert batch == single
+
+
+def test_failed_request_does_not_hide_the_others():
+    # ワールドの境界 (±30,000,000) の外に置いたブロックは RuntimeError 相当のエラーになる
+    bad = {"ticks": 5, "world": {"blocks": [{"x": 40_000_000, "y": 0, "z": 0, "type": "lamp", "on": False}]}}
+    responses = json.loads(redstonesim.simulate_many_py(json.dumps([chain_request(3), bad])))
+
+    assert len(responses) == 2
+    assert "diffs" in responses[0]
+    assert "error" in responses[1]
@@ -0,0 +1,50 @@
+# tests/python/test_simulate_until.py
+#
+# simulate_until_py / simulate_while_py のテスト。maturin develop でビルドしたモジュールが必要。
+
+import json
+
+import pytest
+
+import redstonesim
+
+LAMP = [3, 0, 0]
+
+
+def _request():
+    # 常に入っているレバーから 4 tick リピーター 2 段でランプへ
+    blocks = [
+        {"x": 0, "y": 0, "z": 0, "type": "lever", "on": True, "facing": "east"},
+        {"x": 1, "y": 0, "z": 0, "type": "repeater", "facing": "east", "delay": 4, "ticks_remaining": 0, "powered": False},
+        {"x": 2, "y": 0, "z": 0, "type": "repeater", "facing": "east", "delay": 4, "ticks_remaining": 0, "powered": False},
+        {"x": 3, "y": 0, "z": 0, "type": "lamp", "on": False},
+    ]
+    return json.dumps({"ticks": 50, "world": {"blocks": blocks}})
+
+
+def _lamp_changes(resp):
+    return [c for d in resp["diffs"] for c in d["changes"] if [c["x"], c["y"], c["z"]] == LAMP]
+
+
+def test_until_stops_on_the_tick_the_lamp_lights():
+    resp = json.loads(redstonesim.simulate_until_py(_request(), json.dumps({"lamp_at": LAMP, "on": True})))
+    assert resp["terminated"] == "predicate_met"
+    assert _lamp_changes(resp)[-1]["on"] is True
+
+
+def test_while_is_the_negation():
+    until = redstonesim.simulate_until_py(_request(), json.dumps({"lamp_at": LAMP, "on": True}))
+    while_ = redstonesim.simulate_while_py(_request(), json.dumps({"lamp_at": LAMP, "on": False}))
+    assert json.loads(until) == json.loads(while_)
+
+
+def test_unmet_condition_runs_like_simulate_py():
+    never = {"all": [{"power_at": [9, 9, 9]}, {"any": []}]}
+    resp = json.loads(redstonesim.simulate_until_py(_request(), json.dumps(never)))
+    assert resp == json.loads(redstonesim.simulate_py(_request()))
+    assert resp["terminated"] == "stable"
+
+
+def test_bad_predicate_is_a_value_error():
+    with pytest.raises(ValueError):
+        redstonesim.simulate_until_py(_request(), '{"lamp": [0, 0, 0]}')
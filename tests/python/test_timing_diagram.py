@@ -0,0 +1,41 @@
+# tests/python/test_timing_diagram.py
+#
+# timing_diagram_py のテスト。maturin develop でビルドしたモジュールが必要。
+
+import json
+
+import pytest
+
+import redstonesim
+
+
+def _request(ticks):
+    # レバーを tick 1 で入れ、4 tick リピーター 2 段を通してランプを点ける
+    blocks = [
+        {"x": 0, "y": 0, "z": 0, "type": "lever", "on": False, "facing": "east"},
+        {"x": 1, "y": 0, "z": 0, "type": "repeater", "facing": "east", "delay": 4, "ticks_remaining": 0, "powered": False},
+        {"x": 2, "y": 0, "z": 0, "type": "repeater", "facing": "east", "delay": 4, "ticks_remaining": 0, "powered": False},
+        {"x": 3, "y": 0, "z": 0, "type": "lamp", "on": False},
+    ]
+    return json.dumps({
+        "ticks": ticks,
+        "world": {"blocks": blocks},
+        "early_exit": False,
+        "events": [[1, {"lever_toggle": {"x": 0, "y": 0, "z": 0}}]],
+    })
+
+
+def test_repeater_chain_is_staggered():
+    resp = redstonesim.simulate_py(_request(14))
+    diagram = redstonesim.timing_diagram_py(resp, [(0, 0, 0), (1, 0, 0), (2, 0, 0), (3, 0, 0)], 14)
+    assert diagram.splitlines() == [
+        "(0,0,0) lever:    FFFFFFFFFFFFFF",
+        "(1,0,0) repeater: ___FFFFFFFFFFF",
+        "(2,0,0) repeater: _______FFFFFFF",
+        "(3,0,0) lamp:     ________######",
+    ]
+
+
+def test_bad_response_json_is_a_value_error():
+    with pytest.raises(ValueError):
+        redstonesim.timing_diagram_py("{}", [(0, 0, 0)], 3)
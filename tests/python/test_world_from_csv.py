@@ -0,0 +1,32 @@
+# tests/python/test_world_from_csv.py
+#
+# world_from_csv_py のテスト。csv フィーチャー付きでビルドしたモジュールが必要
+# (maturin develop --features csv)。
+
+import json
+
+import pytest
+
+import redstonesim
+
+if not hasattr(redstonesim, "world_from_csv_py"):
+    pytest.skip("built without the csv feature", allow_module_level=True)
+
+
+def test_reads_blocks_with_empty_cells():
+    text = "x,y,z,type,facing,on_or_power,delay,ticks_remaining\n" \
+           "0,0,0,lever,east,true,,\n" \
+           "1,0,0,repeater,east,false,2,0\n" \
+           "2,0,0,lamp,,false,,\n"
+    world = json.loads(redstonesim.world_from_csv_py(text))
+
+    assert [b["type"] for b in world["blocks"]] == ["lever", "repeater", "lamp"]
+    assert world["blocks"][0]["on"] is True
+    assert world["blocks"][1]["delay"] == 2
+    # 空欄の locked は既定値
+    assert world["blocks"][1]["locked"] is False
+
+
+def test_bad_rows_raise_value_error():
+    with pytest.raises(ValueError, match="row 1"):
+        redstonesim.world_from_csv_py("x,y,z,type\n0,0,0,glass\n")
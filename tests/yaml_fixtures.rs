@@ -0,0 +1,56 @@
+// tests/yaml_fixtures.rs
+
+// Every golden request and response (tests/golden) must survive a trip
+// through YAML unchanged, so YAML files can stand in for the JSON ones.
+//   cargo test --features yaml --test yaml_fixtures
+
+#![cfg(feature = "yaml")]
+
+use std::fs;
+use std::path::Path;
+
+use redstonesim::yaml::{sim_request_from_yaml, sim_request_to_yaml, sim_response_to_yaml, world_from_yaml, world_to_yaml};
+use redstonesim::{SimRequest, SimResponse};
+use serde_json::Value;
+
+fn golden_files(suffix: &str) -> Vec<(String, String)> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden");
+    let mut out: Vec<(String, String)> = fs::read_dir(dir)
+        .expect("tests/golden is missing")
+        .map(|entry| entry.unwrap().path())
+        .filter_map(|path| {
+            let name = path.file_name()?.to_str()?.strip_suffix(suffix)?.to_owned();
+            Some((name, fs::read_to_string(&path).unwrap()))
+        })
+        .collect();
+    out.sort();
+    out
+}
+
+#[test]
+fn golden_requests_round_trip() {
+    let cases = golden_files(".request.json");
+    assert!(!cases.is_empty(), "no *.request.json files in tests/golden");
+    for (name, text) in cases {
+        let req: SimRequest = serde_json::from_str(&text).unwrap_or_else(|e| panic!("{name}: {e}"));
+        let yaml = sim_request_to_yaml(&req);
+        let back = sim_request_from_yaml(&yaml).unwrap_or_else(|e| panic!("{name}: {e}"));
+        assert_eq!(serde_json::to_value(&back).unwrap(), serde_json::to_value(&req).unwrap(), "{name}");
+
+        let world = world_from_yaml(&world_to_yaml(&req.world)).unwrap_or_else(|e| panic!("{name}: {e}"));
+        assert_eq!(world, req.world, "{name}");
+    }
+}
+
+#[test]
+fn golden_responses_round_trip() {
+    let cases = golden_files(".response.json");
+    assert!(!cases.is_empty(), "no *.response.json files in tests/golden");
+    for (name, text) in cases {
+        let resp: SimResponse = serde_json::from_str(&text).unwrap_or_else(|e| panic!("{name}: {e}"));
+        let yaml = sim_response_to_yaml(&resp);
+        // valid YAML that reads back as the same document
+        let value: Value = serde_yaml::from_str(&yaml).unwrap_or_else(|e| panic!("{name}: {e}"));
+        assert_eq!(value, serde_json::from_str::<Value>(&text).unwrap(), "{name}");
+    }
+}
@@ -0,0 +1,151 @@
+// JSON shapes accepted and returned by simulate_json and
+// block_connections_json (src/wasm.rs). These mirror the serde
+// representation of the Rust types; keep them in sync with src/lib.rs,
+// documentation/simulate_py_input.md and the Python stubs in redstonesim.pyi.
+
+export interface Pos {
+  x: number;
+  y: number;
+  z: number;
+}
+
+export type Direction = "north" | "east" | "south" | "west" | "up" | "down";
+export type ComparatorMode = "compare" | "subtract";
+export type Instrument =
+  | "harp" | "basedrum" | "snare" | "hat" | "bass" | "flute" | "bell" | "guitar"
+  | "chime" | "xylophone" | "iron_xylophone" | "cow_bell" | "didgeridoo" | "bit"
+  | "banjo" | "pling";
+export type RailShape =
+  | "north_south" | "east_west" | "ascending_east" | "ascending_west"
+  | "ascending_north" | "ascending_south" | "south_east" | "south_west"
+  | "north_west" | "north_east";
+export type PlateKind = "stone" | "wood" | "light_weighted" | "heavy_weighted";
+
+// `type` selects the block; fields marked optional default when omitted
+export type BlockKind =
+  | { type: "lever"; on: boolean; facing: Direction }
+  | { type: "button"; ticks_remaining: number; facing: Direction }
+  | { type: "dust"; power: number; connections?: [boolean, boolean, boolean, boolean] }
+  | { type: "lamp"; on: boolean }
+  | { type: "repeater"; delay: number; ticks_remaining: number; powered: boolean; facing: Direction; locked?: boolean }
+  | { type: "comparator"; output: number; facing: Direction; mode?: ComparatorMode }
+  | { type: "torch"; lit: boolean; facing: Direction; burnout_counter?: number; burnout_cooldown?: number; burned_out?: boolean }
+  | { type: "piston"; extended: boolean; facing: Direction; sticky?: boolean }
+  | { type: "pistonarm"; facing: Direction }
+  | { type: "hopper"; enabled: boolean; facing: Direction; items?: number }
+  | { type: "observer"; facing: Direction; powered: boolean }
+  | { type: "opaquesolid"; strong_power: number }
+  | { type: "redstoneblock" }
+  | { type: "target"; power: number; ticks_remaining: number }
+  | { type: "sculksensor"; power: number; ticks_remaining: number; waterlogged: boolean }
+  | { type: "calibratedsculksensor"; power: number; ticks_remaining: number; waterlogged: boolean; facing: Direction }
+  | { type: "door"; open: boolean; hinge: "left" | "right"; facing: Direction; half: "upper" | "lower" }
+  | { type: "trapdoor"; open: boolean; facing: Direction; half: "top" | "bottom" }
+  | { type: "noteblock"; instrument: Instrument; pitch: number; triggered: boolean; powered?: boolean }
+  | { type: "poweredrail"; powered: boolean; shape: RailShape }
+  | { type: "detectorrail"; powered: boolean; shape: RailShape }
+  | { type: "activatorrail"; powered: boolean; shape: RailShape }
+  | { type: "pressureplate"; powered: boolean; kind: PlateKind; power?: number }
+  | { type: "dropper"; facing: Direction; powered: boolean; cooldown?: number }
+  | { type: "dispenser"; facing: Direction; powered: boolean; cooldown?: number }
+  | { type: "chest"; items: number; max_items?: number }
+  | { type: "barrel"; items: number }
+  | { type: "furnace"; fuel_ticks: number; cooking: boolean }
+  | { type: "tripwirehook"; attached: boolean; powered: boolean; facing: Direction }
+  | { type: "tripwire"; powered: boolean }
+  | { type: "daylightsensor"; inverted: boolean; power: number }
+  | { type: "lightningrod"; powered: boolean; ticks_remaining?: number }
+  | {
+      type: "copperbulb";
+      lit: boolean;
+      powered: boolean;
+      oxidation?: "unoxidized" | "exposed" | "weathered" | "oxidized";
+    }
+  | { type: "crafter"; powered: boolean; cooldown?: number; craft_count?: number }
+  | {
+      type: "bell";
+      attachment: "floor" | "ceiling" | "single_wall" | "double_wall";
+      facing: Direction;
+      ringing: boolean;
+      powered?: boolean;
+      ticks_remaining?: number;
+    }
+  | { type: "fencegate"; open: boolean; facing: Direction; in_wall: boolean };
+
+export type PlacedBlock = Pos & BlockKind;
+
+export interface World {
+  blocks: PlacedBlock[];
+}
+
+// applied at the start of a tick; a bare `Pos & { strength }` is a target hit
+export type TimedEvent =
+  | { target_hit: Pos & { strength: number } }
+  | { lever_toggle: Pos }
+  | { button_press: Pos }
+  | { block_replace: [Pos, BlockKind] }
+  | { furnace_fuel: [Pos, number] };
+
+export interface SimRequest {
+  ticks: number;
+  world: World;
+  early_exit?: boolean;
+  events?: [number, TimedEvent][];
+  vibrations?: [number, Pos, number][];
+  minecart_events?: [number, Pos, boolean][];
+  entity_events?: [number, Pos, { mobs?: number; items?: number }][];
+  lightning_strikes?: [number, Pos][];
+  parallel?: boolean;
+  quasi_connectivity?: boolean;
+  update_order?: "arbitrary" | "mojang_java" | "deterministic" | "random";
+  time_of_day?: number;
+  time_advance_per_tick?: number;
+  seed?: number | null;
+}
+
+export interface TickDiff {
+  tick: number;
+  changes: PlacedBlock[];
+  removed?: Pos[];
+}
+
+export interface LaunchEvent extends Pos {
+  tick: number;
+  facing: Direction;
+  kind: "dispense" | "drop";
+}
+
+export interface LightningEvent extends Pos {
+  tick: number;
+}
+
+export interface CraftEvent extends Pos {
+  tick: number;
+}
+
+export interface BellRingEvent extends Pos {
+  tick: number;
+  facing: Direction;
+}
+
+export interface SimResponse {
+  diffs: TickDiff[];
+  terminated: "stable" | "max_ticks_reached" | "predicate_met";
+  notes: [number, Pos, Instrument, number][];
+  launch_events: LaunchEvent[];
+  lightning_events: LightningEvent[];
+  craft_events: CraftEvent[];
+  bell_events: BellRingEvent[];
+  oscillation_period: number | null;
+  seed_used: number;
+}
+
+export interface BlockConnections {
+  inputs: Pos[];
+  outputs: Pos[];
+}
+
+// every function returns this instead of throwing
+export interface ErrorResult {
+  error: string;
+}